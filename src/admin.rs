@@ -0,0 +1,155 @@
+//! Local admin control socket.
+//!
+//! A Unix socket accepting line-based commands so operators can manage
+//! a running collector without restarts:
+//!
+//! ```text
+//! echo status | socat - UNIX-CONNECT:data/admin.sock
+//! ```
+//!
+//! Supported commands: `pause SYMBOL`, `resume SYMBOL`, `flush`,
+//! `status`, `add-symbol SYMBOL`, `handover`.
+
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixListener;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::{Error, Result};
+
+/// The `[admin]` config section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// Socket path; defaults to `<data_dir>/admin.sock`.
+    #[serde(default)]
+    pub socket: Option<String>,
+}
+
+/// A runtime command from the operator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// Stop collecting a symbol (subscription stays up).
+    Pause(String),
+    /// Resume a paused symbol.
+    Resume(String),
+    /// Flush every sink now.
+    Flush,
+    /// One-line runtime summary.
+    Status,
+    /// Onboard a new symbol at runtime.
+    AddSymbol(String),
+    /// Rolling-restart handover: flush, release leadership, stand by.
+    Handover,
+}
+
+/// Parse one command line.
+pub fn parse_command(line: &str) -> Result<AdminCommand> {
+    let mut parts = line.split_whitespace();
+    let verb = parts.next().unwrap_or_default().to_lowercase();
+    let arg = parts.next().map(str::to_uppercase);
+    let need_arg = |arg: Option<String>, verb: &str| {
+        arg.ok_or_else(|| Error::Config(format!("`{verb}` needs a symbol argument")))
+    };
+    match verb.as_str() {
+        "pause" => Ok(AdminCommand::Pause(need_arg(arg, "pause")?)),
+        "resume" => Ok(AdminCommand::Resume(need_arg(arg, "resume")?)),
+        "flush" => Ok(AdminCommand::Flush),
+        "status" => Ok(AdminCommand::Status),
+        "add-symbol" => Ok(AdminCommand::AddSymbol(need_arg(arg, "add-symbol")?)),
+        "handover" => Ok(AdminCommand::Handover),
+        other => Err(Error::Config(format!(
+            "unknown admin command `{other}` (known: pause, resume, flush, status, add-symbol, handover)"
+        ))),
+    }
+}
+
+/// A command paired with its reply channel.
+pub struct AdminRequest {
+    /// The parsed command.
+    pub command: AdminCommand,
+    /// Where the main loop sends the response line.
+    pub reply: oneshot::Sender<String>,
+}
+
+/// Bind the socket and forward commands into the channel. The stale
+/// socket file from a previous run is replaced.
+pub fn spawn(
+    socket_path: String,
+    tx: mpsc::Sender<AdminRequest>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let _ = std::fs::remove_file(&socket_path);
+    if let Some(dir) = std::path::Path::new(&socket_path).parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let listener = UnixListener::bind(&socket_path)
+        .map_err(|e| Error::Config(format!("admin socket {socket_path}: {e}")))?;
+    // The socket takes unauthenticated, destructive commands (`pause`,
+    // `handover`); restrict it to the owner so another local user can't
+    // disrupt collection or force a failover.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| Error::Config(format!("admin socket {socket_path}: {e}")))?;
+    }
+    tracing::info!(socket = socket_path, "admin socket listening");
+    Ok(tokio::spawn(async move {
+        loop {
+            let Ok((stream, _)) = listener.accept().await else {
+                break;
+            };
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = stream.into_split();
+                let mut lines = BufReader::new(read).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let response = match parse_command(&line) {
+                        Err(e) => format!("error: {e}"),
+                        Ok(command) => {
+                            let (reply_tx, reply_rx) = oneshot::channel();
+                            if tx
+                                .send(AdminRequest {
+                                    command,
+                                    reply: reply_tx,
+                                })
+                                .await
+                                .is_err()
+                            {
+                                break;
+                            }
+                            reply_rx.await.unwrap_or_else(|_| "error: no reply".to_string())
+                        }
+                    };
+                    if write.write_all(format!("{response}\n").as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse() {
+        assert_eq!(
+            parse_command("pause btcusdt").unwrap(),
+            AdminCommand::Pause("BTCUSDT".into())
+        );
+        assert_eq!(parse_command("flush").unwrap(), AdminCommand::Flush);
+        assert_eq!(parse_command("status").unwrap(), AdminCommand::Status);
+        assert_eq!(
+            parse_command("add-symbol ethusdt").unwrap(),
+            AdminCommand::AddSymbol("ETHUSDT".into())
+        );
+        assert_eq!(parse_command("handover").unwrap(), AdminCommand::Handover);
+        assert!(parse_command("pause").is_err());
+        assert!(parse_command("frobnicate").is_err());
+    }
+}
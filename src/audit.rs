@@ -0,0 +1,81 @@
+//! Operational audit log.
+//!
+//! Significant collector actions — backfills starting and finishing,
+//! symbols onboarded or retired, handovers, repairs — are recorded in
+//! an append-only `audit_log` table (and always in the normal log), so
+//! incidents can be reconstructed afterwards.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+/// One audit record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Event time in epoch milliseconds.
+    pub time: i64,
+    /// Host that performed the action.
+    pub host: String,
+    /// Action name, e.g. `backfill_started`.
+    pub action: String,
+    /// What it applied to (symbol, table, job id).
+    pub subject: String,
+    /// Free-form parameters.
+    pub detail: String,
+}
+
+/// Audit sink; without a database it degrades to plain logging.
+#[derive(Clone)]
+pub struct Audit {
+    db: Option<ClickHouse>,
+    host: String,
+}
+
+impl Audit {
+    /// An audit log writing to the given database.
+    pub fn new(db: Option<ClickHouse>) -> Self {
+        let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "collector".to_string());
+        Self {
+            db,
+            host: format!("{host}-{}", std::process::id()),
+        }
+    }
+
+    /// A log-only audit sink.
+    pub fn noop() -> Self {
+        Self::new(None)
+    }
+
+    /// Create the audit table.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        if let Some(db) = &self.db {
+            db.execute(
+                "CREATE TABLE IF NOT EXISTS audit_log (\
+                 time Int64, host String, action String, subject String, detail String) \
+                 ENGINE = MergeTree ORDER BY (time, host)",
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Record one action. Failures to persist are logged but never
+    /// interrupt collection.
+    pub async fn record(&self, action: &str, subject: &str, detail: &str) {
+        tracing::info!(action, subject, detail, "audit");
+        let Some(db) = &self.db else {
+            return;
+        };
+        let entry = AuditEntry {
+            time: chrono::Utc::now().timestamp_millis(),
+            host: self.host.clone(),
+            action: action.to_string(),
+            subject: subject.to_string(),
+            detail: detail.to_string(),
+        };
+        if let Err(e) = db.insert_rows("audit_log", &[entry]).await {
+            tracing::warn!(error = %e, "audit record failed");
+        }
+    }
+}
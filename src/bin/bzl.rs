@@ -0,0 +1,1054 @@
+//! Operational command-line tool for the archive.
+
+use clap::{Parser, Subcommand};
+
+use bzl_database::config::Config;
+use bzl_database::db::ClickHouse;
+use bzl_database::error::Result;
+use bzl_database::ops;
+use bzl_database::util::parse_date;
+
+#[derive(Debug, Parser)]
+#[command(name = "bzl", about = "Archive maintenance and operations")]
+struct Args {
+    #[command(flatten)]
+    verbosity: bzl_database::logging::Verbosity,
+
+    /// Output language for console messages.
+    #[arg(long, value_parser = clap::value_parser!(bzl_database::i18n::Lang))]
+    lang: Option<bzl_database::i18n::Lang>,
+
+    /// Path to the TOML config file.
+    #[arg(long, default_value = "config.toml", global = true)]
+    config: String,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Import an existing CSV archive tree into ClickHouse.
+    Import {
+        /// Archive root; defaults to the config `data_dir`.
+        #[arg(long)]
+        data_dir: Option<String>,
+
+        /// Parser worker threads.
+        #[arg(long, default_value_t = num_cpus())]
+        workers: usize,
+
+        /// Parse and validate only; do not write to the database.
+        #[arg(long)]
+        parse_only: bool,
+    },
+
+    /// List spot pairs matching quote/status/permission filters.
+    Pairs {
+        /// Comma-separated quote assets (default USDT).
+        #[arg(long)]
+        quote: Option<String>,
+
+        /// Comma-separated statuses (default TRADING).
+        #[arg(long)]
+        status: Option<String>,
+
+        /// Comma-separated required permissions.
+        #[arg(long)]
+        permission: Option<String>,
+
+        /// Regex the base asset must match.
+        #[arg(long)]
+        base_regex: Option<String>,
+    },
+
+    /// Download daily archive ZIPs with resume and checksum checks.
+    Archive {
+        /// Comma-separated symbols.
+        #[arg(long)]
+        symbols: String,
+
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+
+        /// UTC day to fetch, `YYYY-MM-DD`.
+        #[arg(long)]
+        day: String,
+
+        /// Destination directory; defaults to `<data_dir>/archives`.
+        #[arg(long)]
+        dest: Option<String>,
+
+        /// Archive host (mirror or test server).
+        #[arg(long, default_value = "https://data.binance.vision")]
+        base_url: String,
+    },
+
+    /// Cross-check stored candles against the official monthly archive.
+    VerifyArchive {
+        /// Comma-separated symbols.
+        #[arg(long)]
+        symbols: String,
+
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+
+        /// Month to verify, `YYYY-MM`.
+        #[arg(long)]
+        month: String,
+
+        /// Destination directory for the downloaded archive; defaults
+        /// to `<data_dir>/archives`.
+        #[arg(long)]
+        dest: Option<String>,
+
+        /// Archive host (mirror or test server).
+        #[arg(long, default_value = "https://data.binance.vision")]
+        base_url: String,
+    },
+
+    /// Report archive completeness per symbol/interval.
+    Report {
+        /// Comma-separated symbols.
+        #[arg(long)]
+        symbols: String,
+
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+
+        /// Window start (any date form); default all history.
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Output format.
+        #[arg(long, default_value = "text", value_parser = ["text", "json", "html"])]
+        format: String,
+    },
+
+    /// Roll raw 1m candles into the per-symbol daily summary table.
+    DailySummary {
+        /// Window start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Print the statement without executing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Roll raw trades into the per-symbol hourly turnover table.
+    Turnover {
+        /// Window start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Print the statement without executing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Re-fetch and lock in the final daily candle for each configured
+    /// symbol, for a UTC day that has already closed.
+    FinalizeDaily {
+        /// UTC day to finalize (any date form); default yesterday.
+        #[arg(long)]
+        day: Option<String>,
+    },
+
+    /// Build the USD reference conversion series for a window.
+    UsdRef {
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+
+        /// Window start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Print the statement without executing it.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move dead-lettered WAL segments back for reprocessing.
+    Redrive {
+        /// WAL directory; defaults to `<data_dir>/wal` and its
+        /// per-target subdirectories.
+        #[arg(long)]
+        wal_dir: Option<String>,
+    },
+
+    /// Manage the persistent backfill job queue.
+    Backfill {
+        #[command(subcommand)]
+        action: BackfillAction,
+    },
+
+    /// Delete and re-download a stored range, with a count post-check.
+    Repair {
+        /// Symbol to repair.
+        #[arg(long)]
+        symbol: String,
+
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+
+        /// Range start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Range end.
+        #[arg(long)]
+        to: String,
+    },
+
+    /// The last known candle and price for a symbol as of a time.
+    Asof {
+        /// Symbol to look up.
+        #[arg(long)]
+        symbol: String,
+
+        /// Point in time (any date form).
+        #[arg(long)]
+        at: String,
+
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+    },
+
+    /// Drive the configured pipelines with synthetic load.
+    Loadgen {
+        /// Target events per second (0 = unthrottled).
+        #[arg(long, default_value_t = 10_000)]
+        rate: u64,
+
+        /// Run duration in seconds.
+        #[arg(long, default_value_t = 5)]
+        seconds: u64,
+    },
+
+    /// Replay archived data through the configured pipelines.
+    Replay {
+        /// Comma-separated symbols.
+        #[arg(long)]
+        symbols: String,
+
+        /// Window start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Pacing relative to the original timestamps: 0 = as fast as
+        /// possible, 1 = real time, 60 = one minute per second.
+        #[arg(long, default_value_t = 0.0)]
+        speed: f64,
+    },
+
+    /// Snapshot futures index compositions (constituents and weights).
+    IndexInfo {
+        /// Futures REST host.
+        #[arg(long, default_value = "https://fapi.binance.com")]
+        fapi_url: String,
+    },
+
+    /// Poll the futures sentiment ratio endpoints once.
+    Sentiment,
+
+    /// Backfill top-trader position ratios and open interest.
+    TopPositions {
+        /// Window start (any date form).
+        #[arg(long)]
+        from: String,
+
+        /// Window end; default now.
+        #[arg(long)]
+        to: Option<String>,
+    },
+
+    /// Poll funding payments and upcoming funding times once.
+    Funding,
+
+    /// Pull asset metadata (names, rank, supply) into the database.
+    Enrich,
+
+    /// Snapshot exchangeInfo filters into the dimension table.
+    SnapshotInfo,
+
+    /// Regenerate the archive checksum manifest.
+    Manifest {
+        /// Archive root; defaults to the config `data_dir`.
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+
+    /// Re-check every file against the checksum manifest.
+    Verify {
+        /// Archive root; defaults to the config `data_dir`.
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+
+    /// One-shot migration of a legacy archive: sort, dedup, re-split
+    /// rows into the correct daily files.
+    Migrate {
+        /// Archive root; defaults to the config `data_dir`.
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+
+    /// Sort and deduplicate the daily CSV archive in place.
+    Compact {
+        /// Archive root; defaults to the config `data_dir`.
+        #[arg(long)]
+        data_dir: Option<String>,
+    },
+
+    /// Delete local daily files past the retention window.
+    Retention {
+        /// List what would be deleted without deleting.
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Downsample aged raw trades into 1s candles and drop the raws.
+    Downsample {
+        /// Print the statements without executing them.
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum BackfillAction {
+    /// Enqueue a backfill job.
+    Enqueue {
+        /// Symbol to backfill.
+        #[arg(long)]
+        symbol: String,
+        /// Kline interval.
+        #[arg(long, default_value = "1m")]
+        interval: String,
+        /// Range start (any date form).
+        #[arg(long)]
+        from: String,
+        /// Range end; default now.
+        #[arg(long)]
+        to: Option<String>,
+        /// Larger runs first.
+        #[arg(long, default_value_t = 0)]
+        priority: i32,
+    },
+    /// Show every job's latest status.
+    List,
+    /// Process pending jobs.
+    Work {
+        /// Stop after this many jobs.
+        #[arg(long, default_value_t = usize::MAX)]
+        max_jobs: usize,
+    },
+}
+
+fn num_cpus() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut cfg = Config::load_or_default(&args.config)?;
+    cfg.resolve_secrets(&bzl_database::http::client()).await?;
+    let _log_guard = bzl_database::logging::init(&cfg.logging, args.verbosity)?;
+    bzl_database::i18n::set_lang(args.lang.unwrap_or(cfg.output.lang));
+    match args.command {
+        Command::Import {
+            data_dir,
+            workers,
+            parse_only,
+        } => {
+            let root = data_dir.unwrap_or_else(|| cfg.data_dir.clone());
+            let db = if parse_only {
+                None
+            } else {
+                let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                    bzl_database::Error::Config(
+                        "import needs a [clickhouse] config section (or --parse-only)".to_string(),
+                    )
+                })?;
+                Some(ClickHouse::new(ch, bzl_database::http::client()))
+            };
+            let stats = ops::import::import_tree(&root, db, workers).await?;
+            println!(
+                "imported {} files: {} klines, {} trades, {} bad rows",
+                stats.files, stats.klines, stats.trades, stats.bad_rows
+            );
+        }
+        Command::Pairs {
+            quote,
+            status,
+            permission,
+            base_regex,
+        } => {
+            let split = |s: Option<String>| -> Option<Vec<String>> {
+                s.map(|s| {
+                    s.split(',')
+                        .map(str::trim)
+                        .filter(|x| !x.is_empty())
+                        .map(str::to_uppercase)
+                        .collect()
+                })
+            };
+            let mut filter = bzl_database::exchange::symbols::PairsFilter::default();
+            if let Some(quotes) = split(quote) {
+                filter.quote_assets = quotes;
+            }
+            if let Some(statuses) = split(status) {
+                filter.statuses = statuses;
+            }
+            if let Some(permissions) = split(permission) {
+                filter.permissions = permissions;
+            }
+            filter.base_regex = base_regex;
+            let exchange =
+                bzl_database::exchange::binance::Binance::from_config(&cfg.binance);
+            let known = bzl_database::exchange::symbols::fetch_exchange_info(
+                &bzl_database::http::client(),
+                exchange.rest_url(),
+            )
+            .await?;
+            for pair in bzl_database::exchange::symbols::filter_pairs(&known, &filter)? {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    pair.symbol, pair.base_asset, pair.quote_asset, pair.status
+                );
+            }
+        }
+        Command::Archive {
+            symbols,
+            interval,
+            day,
+            dest,
+            base_url,
+        } => {
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let dest = dest.unwrap_or_else(|| format!("{}/archives", cfg.data_dir));
+            let http = bzl_database::http::client();
+            for symbol in symbols.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let path = ops::archive::fetch_archive_day(
+                    &http,
+                    &base_url,
+                    symbol,
+                    interval,
+                    &day,
+                    std::path::Path::new(&dest),
+                )
+                .await?;
+                println!("verified {}", path.display());
+            }
+        }
+        Command::VerifyArchive {
+            symbols,
+            interval,
+            month,
+            dest,
+            base_url,
+        } => {
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let dest = dest.unwrap_or_else(|| format!("{}/archives", cfg.data_dir));
+            let http = bzl_database::http::client();
+            let store = match cfg.clickhouse.clone() {
+                Some(ch) => bzl_database::storage::kline_store::KlineStore::ClickHouse(
+                    ClickHouse::new(ch, bzl_database::http::client()),
+                ),
+                None => bzl_database::storage::kline_store::KlineStore::Csv {
+                    root: cfg.data_dir.clone().into(),
+                },
+            };
+            for symbol in symbols.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let report = ops::archive::verify_against_archive(
+                    &store,
+                    &http,
+                    &base_url,
+                    symbol,
+                    interval,
+                    &month,
+                    std::path::Path::new(&dest),
+                )
+                .await?;
+                println!(
+                    "{symbol} {interval} {month}: archive {}, local {}, {} mismatched, {} missing locally{}",
+                    report.archive_candles,
+                    report.local_candles,
+                    report.mismatched,
+                    report.missing_local,
+                    if report.consistent() { "" } else { " (DIVERGENCE)" }
+                );
+            }
+        }
+        Command::Report {
+            symbols,
+            interval,
+            from,
+            to,
+            format,
+        } => {
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let from = from.as_deref().map(parse_date).transpose()?.unwrap_or(0);
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            let known_gaps_db = cfg
+                .clickhouse
+                .clone()
+                .map(|ch| ClickHouse::new(ch, bzl_database::http::client()));
+            let store = match cfg.clickhouse.clone() {
+                Some(ch) => bzl_database::storage::kline_store::KlineStore::ClickHouse(
+                    ClickHouse::new(ch, bzl_database::http::client()),
+                ),
+                None => bzl_database::storage::kline_store::KlineStore::Csv {
+                    root: cfg.data_dir.clone().into(),
+                },
+            };
+            let mut rows = Vec::new();
+            for symbol in symbols.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                rows.push(
+                    ops::report::coverage_for_with_known_gaps(
+                        &store,
+                        known_gaps_db.as_ref(),
+                        &symbol.to_uppercase(),
+                        interval,
+                        from,
+                        to,
+                    )
+                    .await?,
+                );
+            }
+            match format.as_str() {
+                "json" => println!("{}", serde_json::to_string_pretty(&rows)?),
+                "html" => print!("{}", ops::report::render_html(&rows)),
+                _ => print!("{}", ops::report::render_text(&rows)),
+            }
+        }
+        Command::DailySummary { from, to, dry_run } => {
+            let from = parse_date(&from)?;
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            if dry_run {
+                println!("{}", bzl_database::jobs::daily_summary::plan(from, to));
+                return Ok(());
+            }
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "daily-summary needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::daily_summary::run(&db, from, to).await?;
+            println!("daily summary updated for {from}..{to}");
+        }
+        Command::Turnover { from, to, dry_run } => {
+            let from = parse_date(&from)?;
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            if dry_run {
+                println!("{}", bzl_database::jobs::turnover::plan(from, to));
+                return Ok(());
+            }
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "turnover needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::turnover::run(&db, from, to).await?;
+            println!("hourly turnover updated for {from}..{to}");
+        }
+        Command::FinalizeDaily { day } => {
+            const DAY_MS: i64 = 86_400_000;
+            let now = chrono::Utc::now().timestamp_millis();
+            let day_start = match day {
+                Some(d) => parse_date(&d)?,
+                None => now - DAY_MS,
+            };
+            let day_start = day_start - day_start.rem_euclid(DAY_MS);
+            if !bzl_database::jobs::finalize_daily::day_has_closed(day_start, now) {
+                return Err(bzl_database::Error::Config(format!(
+                    "day {day_start} has not closed yet"
+                )));
+            }
+            let job_cfg = cfg.finalize_daily.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "finalize-daily needs a [finalize_daily] config section".to_string(),
+                )
+            })?;
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "finalize-daily needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            let exchange = bzl_database::exchange::binance::Binance::from_config(&cfg.binance);
+            bzl_database::jobs::finalize_daily::run(
+                &db,
+                &exchange,
+                &bzl_database::http::client(),
+                &job_cfg,
+                day_start,
+                now,
+            )
+            .await?;
+            println!("finalized {} symbol(s) for day {day_start}", job_cfg.symbols.len());
+        }
+        Command::UsdRef {
+            interval,
+            from,
+            to,
+            dry_run,
+        } => {
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let from = parse_date(&from)?;
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            let reference = cfg.usd_reference.clone().unwrap_or_default();
+            if dry_run {
+                println!("{}", bzl_database::jobs::usd_reference::plan(&reference, interval, from, to));
+                return Ok(());
+            }
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config("usd-ref needs a [clickhouse] config section".to_string())
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::usd_reference::run(&db, &reference, interval, from, to).await?;
+            println!("usd reference series updated for {from}..{to}");
+        }
+        Command::Redrive { wal_dir } => {
+            let base = wal_dir.unwrap_or_else(|| format!("{}/wal", cfg.data_dir));
+            let base = std::path::PathBuf::from(base);
+            let mut total = 0;
+            // The WAL root itself plus any per-target subdirectories.
+            let mut dirs = vec![base.clone()];
+            if let Ok(entries) = std::fs::read_dir(&base) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.is_dir() && path.file_name().is_some_and(|n| n != "dead-letter") {
+                        dirs.push(path);
+                    }
+                }
+            }
+            for dir in dirs {
+                total += bzl_database::sink::wal::redrive(&dir)?;
+            }
+            println!("redriven {total} segment(s)");
+        }
+        Command::Backfill { action } => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "backfill needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::backfill_queue::ensure_schema(&db).await?;
+            match action {
+                BackfillAction::Enqueue {
+                    symbol,
+                    interval,
+                    from,
+                    to,
+                    priority,
+                } => {
+                    let interval: bzl_database::model::Interval = interval.parse()?;
+                    let from = parse_date(&from)?;
+                    let to = to
+                        .as_deref()
+                        .map(parse_date)
+                        .transpose()?
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+                    let job = bzl_database::jobs::backfill_queue::BackfillJob::new(
+                        &symbol,
+                        interval,
+                        from,
+                        to,
+                        priority,
+                        chrono::Utc::now().timestamp_millis(),
+                    );
+                    bzl_database::jobs::backfill_queue::enqueue(&db, &job).await?;
+                    println!("enqueued {} ({symbol} {interval} {from}..{to})", job.job_id);
+                }
+                BackfillAction::List => {
+                    for job in bzl_database::jobs::backfill_queue::list(&db).await? {
+                        println!(
+                            "{}	{}	{}	{}..{}	prio {}	{}	{}",
+                            job.job_id,
+                            job.symbol,
+                            job.interval,
+                            job.from,
+                            job.to,
+                            job.priority,
+                            job.status,
+                            job.detail
+                        );
+                    }
+                }
+                BackfillAction::Work { max_jobs } => {
+                    let exchange =
+                        bzl_database::exchange::binance::Binance::from_config(&cfg.binance);
+                    let sink = bzl_database::sink::clickhouse::ClickHouseSink::new(db.clone());
+                    sink.ensure_schema().await?;
+                    let audit = bzl_database::audit::Audit::new(Some(db.clone()));
+                    audit.ensure_schema().await?;
+                    let mut sinks: Vec<Box<dyn bzl_database::sink::Sink>> = vec![Box::new(sink)];
+                    let worker = bzl_database::jobs::backfill_queue::worker_id();
+                    let done = bzl_database::jobs::backfill_queue::work(
+                        &db,
+                        &exchange,
+                        &bzl_database::http::client(),
+                        &mut sinks,
+                        max_jobs,
+                        &audit,
+                        &worker,
+                    )
+                    .await?;
+                    println!("completed {done} job(s)");
+                }
+            }
+        }
+        Command::Repair {
+            symbol,
+            interval,
+            from,
+            to,
+        } => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config("repair needs a [clickhouse] config section".to_string())
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let from = parse_date(&from)?;
+            let to = parse_date(&to)?;
+            let exchange = bzl_database::exchange::binance::Binance::from_config(&cfg.binance);
+            let report = ops::repair::repair(
+                &db,
+                &exchange,
+                &bzl_database::http::client(),
+                &symbol,
+                interval,
+                from,
+                to,
+            )
+            .await?;
+            println!(
+                "repaired {symbol} {interval} {from}..{to}: {} downloaded, {} stored{}{}",
+                report.downloaded,
+                report.stored,
+                if report.consistent() { "" } else { " (MISMATCH)" },
+                if report.tagged_known_gap {
+                    " (tagged as a known exchange gap)"
+                } else {
+                    ""
+                }
+            );
+        }
+        Command::Asof {
+            symbol,
+            at,
+            interval,
+        } => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config("asof needs a [clickhouse] config section".to_string())
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            let interval: bzl_database::model::Interval = interval.parse()?;
+            let at = parse_date(&at)?;
+            match ops::asof::kline_asof(&db, &symbol, interval, at).await? {
+                Some(k) => println!(
+                    "candle as of {at}: open_time {} o {} h {} l {} c {}",
+                    k.open_time, k.open, k.high, k.low, k.close
+                ),
+                None => println!("no candle at or before {at}"),
+            }
+            match ops::asof::price_asof(&db, &symbol, at).await? {
+                Some(p) => println!("last trade as of {at}: {} @ {}", p.trade_time, p.price),
+                None => println!("no trade at or before {at}"),
+            }
+        }
+        Command::Loadgen { rate, seconds } => {
+            let mut flows = cfg.build_flows(&bzl_database::http::client())?;
+            if flows.is_empty() {
+                return Err(bzl_database::Error::Config(
+                    "loadgen needs `pipelines` in config".to_string(),
+                ));
+            }
+            let report = ops::loadgen::run(
+                &mut flows,
+                ops::loadgen::LoadgenOptions { rate, seconds },
+            )
+            .await?;
+            println!(
+                "{} events in {:.2}s: {:.0} ev/s, sink write avg {:.1}us max {}us",
+                report.events,
+                report.elapsed.as_secs_f64(),
+                report.throughput,
+                report.write_latency_avg_us,
+                report.write_latency_max_us
+            );
+        }
+        Command::Replay {
+            symbols,
+            from,
+            to,
+            speed,
+        } => {
+            let from = parse_date(&from)?;
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            let mut flows = cfg.build_flows(&bzl_database::http::client())?;
+            if flows.is_empty() {
+                return Err(bzl_database::Error::Config(
+                    "replay needs `pipelines` in config".to_string(),
+                ));
+            }
+            let symbols: Vec<String> = symbols
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_uppercase)
+                .collect();
+            let emitted =
+                ops::replay::replay(&cfg, &mut flows, &symbols, from, to, speed).await?;
+            println!("replayed {emitted} events");
+        }
+        Command::IndexInfo { fapi_url } => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "index-info needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::index_info::ensure_schema(&db).await?;
+            let rows = bzl_database::jobs::index_info::run_once(
+                &bzl_database::http::client(),
+                &db,
+                &fapi_url,
+            )
+            .await?;
+            println!("snapshotted {rows} index components");
+        }
+        Command::Sentiment => {
+            let policy = cfg.sentiment.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "sentiment needs a [sentiment] config section".to_string(),
+                )
+            })?;
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "sentiment needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::sentiment::ensure_schema(&db).await?;
+            let rows =
+                bzl_database::jobs::sentiment::run_once(&bzl_database::http::client(), &db, &policy)
+                    .await?;
+            println!("collected {rows} sentiment rows");
+        }
+        Command::TopPositions { from, to } => {
+            let policy = cfg.sentiment.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "top-positions needs a [sentiment] config section".to_string(),
+                )
+            })?;
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "top-positions needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::jobs::sentiment::ensure_schema(&db).await?;
+            let from = parse_date(&from)?;
+            let to = to
+                .as_deref()
+                .map(parse_date)
+                .transpose()?
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+            let rows = bzl_database::jobs::sentiment::backfill_top_positions(
+                &bzl_database::http::client(),
+                &db,
+                &policy,
+                from,
+                to,
+            )
+            .await?;
+            println!("backfilled {rows} rows for {from}..{to}");
+        }
+        Command::Funding => {
+            let policy = cfg.funding.clone().ok_or_else(|| {
+                bzl_database::Error::Config("funding needs a [funding] config section".to_string())
+            })?;
+            let db = match cfg.clickhouse.clone() {
+                Some(ch) => {
+                    let db = ClickHouse::new(ch, bzl_database::http::client());
+                    bzl_database::jobs::funding::ensure_schema(&db).await?;
+                    Some(db)
+                }
+                None => None,
+            };
+            let now = chrono::Utc::now().timestamp_millis();
+            let alerts = bzl_database::jobs::funding::run_once(
+                &bzl_database::http::client(),
+                db.as_ref(),
+                &policy,
+                now,
+            )
+            .await?;
+            for alert in alerts {
+                println!("[alert] {} {}: {}", alert.symbol, alert.source, alert.message);
+            }
+        }
+        Command::Enrich => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config("enrich needs a [clickhouse] config section".to_string())
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            let policy = cfg.enrich.clone().unwrap_or_default();
+            let rows =
+                bzl_database::jobs::enrich::run(&bzl_database::http::client(), &db, &policy)
+                    .await?;
+            println!("enriched {rows} assets");
+        }
+        Command::SnapshotInfo => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "snapshot-info needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            bzl_database::exchange::symbols::ensure_filters_schema(&db).await?;
+            let exchange = bzl_database::exchange::binance::Binance::from_config(&cfg.binance);
+            let known = bzl_database::exchange::symbols::fetch_exchange_info(
+                &bzl_database::http::client(),
+                exchange.rest_url(),
+            )
+            .await?;
+            let now = chrono::Utc::now().timestamp_millis();
+            let rows =
+                bzl_database::exchange::symbols::snapshot_filters(&db, &known, now).await?;
+            println!("snapshotted {rows} symbols at {now}");
+        }
+        Command::Manifest { data_dir } => {
+            let root = data_dir.unwrap_or_else(|| cfg.data_dir.clone());
+            let entries = ops::manifest::update_manifest(std::path::Path::new(&root))?;
+            println!("manifest updated: {entries} files");
+        }
+        Command::Verify { data_dir } => {
+            let root = data_dir.unwrap_or_else(|| cfg.data_dir.clone());
+            let report = ops::manifest::verify_manifest(std::path::Path::new(&root))?;
+            println!("{} files ok", report.ok);
+            for path in &report.missing {
+                println!("MISSING {path}");
+            }
+            for path in &report.corrupt {
+                println!("CORRUPT {path}");
+            }
+            if !report.missing.is_empty() || !report.corrupt.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Command::Migrate { data_dir } => {
+            let root = data_dir.unwrap_or_else(|| cfg.data_dir.clone());
+            let stats = ops::migrate::migrate_tree(std::path::Path::new(&root))?;
+            println!(
+                "migrated {} files into {}: {} rows in, {} rows out ({} removed)",
+                stats.files_in,
+                stats.files_out,
+                stats.rows_in,
+                stats.rows_out,
+                stats.rows_in - stats.rows_out
+            );
+        }
+        Command::Compact { data_dir } => {
+            let root = data_dir.unwrap_or_else(|| cfg.data_dir.clone());
+            let stats = ops::compact::compact_tree(std::path::Path::new(&root))?;
+            println!(
+                "compacted {} files: {} rows in, {} rows out ({} removed)",
+                stats.files,
+                stats.rows_in,
+                stats.rows_out,
+                stats.rows_in - stats.rows_out
+            );
+        }
+        Command::Retention { dry_run } => {
+            let policy = cfg.retention.clone().unwrap_or_default();
+            let today = chrono::Utc::now().date_naive();
+            let (files, bytes) = bzl_database::jobs::retention::run(
+                std::path::Path::new(&cfg.data_dir),
+                &policy,
+                today,
+                dry_run,
+            )?;
+            println!(
+                "{} {} files ({bytes} bytes)",
+                if dry_run { "would delete" } else { "deleted" },
+                files
+            );
+        }
+        Command::Downsample { dry_run } => {
+            let policy = cfg.downsample.clone().unwrap_or_default();
+            let now = chrono::Utc::now().timestamp_millis();
+            let cutoff = now - i64::from(policy.raw_trades_max_age_days) * 86_400_000;
+            if dry_run {
+                for sql in bzl_database::jobs::downsample::plan(cutoff) {
+                    println!("{sql}");
+                }
+                return Ok(());
+            }
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                bzl_database::Error::Config(
+                    "downsample needs a [clickhouse] config section".to_string(),
+                )
+            })?;
+            let db = ClickHouse::new(ch, bzl_database::http::client());
+            let cutoff = bzl_database::jobs::downsample::run(&db, &policy, now).await?;
+            println!("downsampled raw trades before {cutoff}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,254 @@
+//! Historical kline downloader: pages klines over REST and writes them
+//! through the configured sinks (CSV always, ClickHouse when
+//! configured).
+
+use clap::Parser;
+
+use bzl_database::checkpoint::{Checkpoint, CheckpointStore};
+use bzl_database::config::Config;
+use bzl_database::db::ClickHouse;
+use bzl_database::error::Result;
+use bzl_database::exchange::binance::Binance;
+use bzl_database::exchange::symbols;
+use bzl_database::exchange::Exchange;
+use bzl_database::model::Interval;
+use bzl_database::planner::RangePlan;
+use bzl_database::pipeline::Event;
+use bzl_database::sink::clickhouse::ClickHouseSink;
+use bzl_database::sink::csv::CsvSink;
+use bzl_database::sink::dry_run::DryRunSink;
+use bzl_database::sink::Sink;
+use bzl_database::util::parse_date;
+
+const PAGE_LIMIT: usize = 1000;
+
+#[derive(Debug, Parser)]
+#[command(about = "Download historical klines into the archive")]
+struct Args {
+    #[command(flatten)]
+    verbosity: bzl_database::logging::Verbosity,
+
+    /// Output language for console messages.
+    #[arg(long, value_parser = clap::value_parser!(bzl_database::i18n::Lang))]
+    lang: Option<bzl_database::i18n::Lang>,
+
+    /// Path to the TOML config file.
+    #[arg(long, default_value = "config.toml")]
+    config: String,
+
+    /// Route all traffic to the Binance spot testnet.
+    #[arg(long)]
+    testnet: bool,
+
+    /// Comma-separated symbols, e.g. `BTCUSDT,ETHUSDT`.
+    #[arg(long)]
+    symbols: String,
+
+    /// Comma-separated kline intervals, e.g. `1m,1h,1d`.
+    #[arg(long, default_value = "1m")]
+    interval: String,
+
+    /// Range start (any date form); defaults to resuming from the
+    /// symbol's checkpoint.
+    #[arg(long)]
+    start: Option<String>,
+
+    /// Range end; defaults to now.
+    #[arg(long)]
+    end: Option<String>,
+
+    /// Request and parse everything but write nothing, printing what
+    /// would be written instead.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Record every API response into this fixture directory.
+    #[arg(long, conflicts_with = "replay")]
+    record: Option<String>,
+
+    /// Replay API responses from this fixture directory instead of
+    /// touching the network.
+    #[arg(long)]
+    replay: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut cfg = Config::load_or_default(&args.config)?;
+    cfg.resolve_secrets(&bzl_database::http::client()).await?;
+    let _log_guard = bzl_database::logging::init(&cfg.logging, args.verbosity)?;
+    bzl_database::i18n::set_lang(args.lang.unwrap_or(cfg.output.lang));
+    if args.testnet {
+        cfg.binance.testnet = true;
+    }
+    if let Some(dir) = &args.record {
+        bzl_database::fixtures::set_mode(Some(bzl_database::fixtures::FixtureMode::Record(
+            dir.into(),
+        )))?;
+    } else if let Some(dir) = &args.replay {
+        bzl_database::fixtures::set_mode(Some(bzl_database::fixtures::FixtureMode::Replay(
+            dir.into(),
+        )))?;
+    }
+    let exchange = Binance::from_config(&cfg.binance);
+    let http = bzl_database::http::client();
+
+    let clock = bzl_database::clock::ServerClock::new();
+    if let Err(e) = clock.sync(&http, exchange.rest_url()).await {
+        tracing::warn!(error = %e, "server time sync failed; using local clock");
+    }
+    let start = args.start.as_deref().map(parse_date).transpose()?;
+    let end = match &args.end {
+        Some(end) => parse_date(end)?,
+        // The corrected clock keeps the implicit "now" bound honest on
+        // machines with skewed clocks.
+        None => clock.now_ms(),
+    };
+    let intervals: Vec<Interval> = args
+        .interval
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::parse)
+        .collect::<Result<_>>()?;
+
+    let checkpoints = CheckpointStore::from_config(&cfg, &http)?;
+    if !args.dry_run {
+        checkpoints.ensure_schema().await?;
+    }
+
+    if !args.dry_run {
+        let repaired = bzl_database::sink::csv::repair_tree(std::path::Path::new(&cfg.data_dir))?;
+        if repaired > 0 {
+            tracing::warn!(repaired, "repaired truncated CSV files at startup");
+        }
+    }
+    let mut sinks: Vec<Box<dyn Sink>> = vec![Box::new(CsvSink::new(&cfg.data_dir))];
+    if let Some(ch) = cfg.clickhouse.clone() {
+        let sink = ClickHouseSink::new(ClickHouse::new(ch, http.clone()));
+        if !args.dry_run {
+            sink.ensure_schema().await?;
+        }
+        sinks.push(Box::new(sink));
+    }
+    if args.dry_run {
+        tracing::info!("dry run: nothing will be written");
+        sinks = sinks
+            .iter()
+            .map(|s| Box::new(DryRunSink::new(s.name())) as Box<dyn Sink>)
+            .collect();
+    }
+
+    let symbols: Vec<String> = args
+        .symbols
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+    // Catch typos before committing to a long job; if exchangeInfo is
+    // unreachable (and nothing is cached) the job proceeds and the API
+    // will complain instead.
+    let info_cache = bzl_database::exchange::info_cache::ExchangeInfoCache::new(
+        exchange.rest_url(),
+        http.clone(),
+        &cfg.data_dir,
+        &cfg.exchange_info,
+    );
+    match info_cache.get().await {
+        Ok(known) => symbols::validate_symbols(&known, &symbols)?,
+        Err(e) => tracing::warn!(error = %e, "skipping symbol validation"),
+    }
+
+    let tier_map = bzl_database::tiers::TierMap::new(cfg.tiers.clone());
+    for symbol in symbols {
+        let page_delay = tier_map
+            .get(&symbol)
+            .and_then(|t| t.page_delay_ms)
+            .unwrap_or(300);
+        // Discovering the symbol's first available candle is one extra
+        // request; do it at most once per symbol and share it across
+        // every interval below instead of re-discovering per interval.
+        let mut discovered: Option<i64> = None;
+        for interval in &intervals {
+            let interval = *interval;
+            let step = interval.ms();
+            let mut cursor = match start {
+                Some(start) => start,
+                None => match checkpoints.load(&symbol, interval).await?.and_then(|c| c.last_open_time) {
+                    Some(last_open_time) => last_open_time,
+                    None => match discovered {
+                        Some(t) => t,
+                        None => {
+                            let t = discover_earliest_open_time(&exchange, &http, &symbol).await?;
+                            discovered = Some(t);
+                            t
+                        }
+                    },
+                },
+            };
+            let plan = RangePlan::new(cursor, end, interval, PAGE_LIMIT)?;
+            tracing::info!(
+                symbol,
+                %interval,
+                candles = plan.candles(),
+                requests = plan.requests(),
+                "planned backfill"
+            );
+            let mut total = 0usize;
+            for page_start in plan.pages() {
+                let klines = exchange
+                    .fetch_klines(
+                        &http,
+                        &symbol,
+                        interval.as_str(),
+                        Some(page_start),
+                        Some(end),
+                        PAGE_LIMIT,
+                    )
+                    .await?;
+                if let Some(last) = klines.last() {
+                    cursor = cursor.max(last.open_time + step);
+                }
+                total += klines.len();
+                let events: Vec<Event> = klines.into_iter().map(Event::Kline).collect();
+                for sink in sinks.iter_mut() {
+                    sink.write(&events).await?;
+                }
+                tracing::debug!(symbol, %interval, total, "downloaded page");
+                tokio::time::sleep(std::time::Duration::from_millis(page_delay)).await;
+            }
+            // Record progress so the next run can resume.
+            if !args.dry_run {
+                let mut ckpt = Checkpoint::new(&symbol, interval);
+                ckpt.last_open_time = Some(cursor);
+                checkpoints
+                    .save(&ckpt, chrono::Utc::now().timestamp_millis())
+                    .await?;
+            }
+            tracing::info!(symbol, %interval, total, "interval done");
+        }
+    }
+    for sink in sinks.iter_mut() {
+        sink.flush().await?;
+    }
+    Ok(())
+}
+
+/// Find the open time of a symbol's very first candle, so a fresh
+/// backfill with no checkpoint and no `--start` doesn't need a listing
+/// date supplied by hand. Binance returns the earliest available
+/// candles when queried from time zero, regardless of interval.
+async fn discover_earliest_open_time(
+    exchange: &Binance,
+    http: &reqwest::Client,
+    symbol: &str,
+) -> Result<i64> {
+    let klines = exchange
+        .fetch_klines(http, symbol, Interval::M1.as_str(), Some(0), None, 1)
+        .await?;
+    klines.first().map(|k| k.open_time).ok_or_else(|| {
+        bzl_database::Error::Config(format!("no candles found for {symbol}"))
+    })
+}
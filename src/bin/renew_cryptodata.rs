@@ -0,0 +1,845 @@
+//! Live stream collector: subscribes the configured pipelines' sources
+//! on the Binance WebSocket and feeds events through each flow.
+
+use clap::Parser;
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::Message;
+
+use std::collections::HashMap;
+
+use bzl_database::admin::{AdminCommand, AdminRequest};
+use bzl_database::checkpoint::{Checkpoint, CheckpointStore, TradeCheckpoint};
+use bzl_database::config::Config;
+use bzl_database::error::{Error, Result};
+use bzl_database::exchange::binance::Binance;
+use bzl_database::exchange::Exchange;
+use bzl_database::pipeline::spec::Flow;
+use bzl_database::model::Interval;
+use bzl_database::pipeline::Event;
+use bzl_database::jobs::listings::ListingWatcher;
+use bzl_database::queue::EventQueue;
+
+#[derive(Debug, Parser)]
+#[command(about = "Collect live market data via WebSocket streams")]
+struct Args {
+    #[command(flatten)]
+    verbosity: bzl_database::logging::Verbosity,
+
+    /// Output language for console messages.
+    #[arg(long, value_parser = clap::value_parser!(bzl_database::i18n::Lang))]
+    lang: Option<bzl_database::i18n::Lang>,
+
+    /// Path to the TOML config file.
+    #[arg(long, default_value = "config.toml")]
+    config: String,
+
+    /// Route all traffic to the Binance spot testnet.
+    #[arg(long)]
+    testnet: bool,
+
+    /// Subscribe and parse everything but write nothing, printing what
+    /// would be written instead.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let mut cfg = Config::load(&args.config)?;
+    cfg.resolve_secrets(&bzl_database::http::client()).await?;
+    let _log_guard = bzl_database::logging::init(&cfg.logging, args.verbosity)?;
+    bzl_database::i18n::set_lang(args.lang.unwrap_or(cfg.output.lang));
+    if args.testnet {
+        cfg.binance.testnet = true;
+    }
+    if let Some(chaos) = cfg.chaos.clone() {
+        tracing::warn!("chaos mode armed: faults will be injected");
+        bzl_database::chaos::set(Some(chaos));
+    }
+    let exchange = Binance::from_config(&cfg.binance);
+    let http = bzl_database::http::client();
+    let clock = bzl_database::clock::ServerClock::new();
+    match clock.sync(&http, exchange.rest_url()).await {
+        Ok(offset) => tracing::info!(offset_ms = offset, "server time synced"),
+        Err(e) => tracing::warn!(error = %e, "server time sync failed; using local clock"),
+    }
+    clock.spawn_periodic(
+        http.clone(),
+        exchange.rest_url().to_string(),
+        std::time::Duration::from_secs(900),
+    );
+    let mut flows = cfg.build_flows(&http)?;
+    if flows.is_empty() {
+        return Err(Error::Config("no `pipelines` defined in config".to_string()));
+    }
+    // Restore pipelines that were added at runtime before the last
+    // restart, so the collector resubscribes to exactly what it was
+    // collecting.
+    let subscription_store = bzl_database::subscriptions::SubscriptionSet::new(&cfg.data_dir);
+    let mut dynamic_defs: Vec<String> = Vec::new();
+    for def in subscription_store.load() {
+        match bzl_database::pipeline::spec::PipelineSpec::parse(&def)
+            .and_then(|spec| bzl_database::pipeline::spec::build_flow(&cfg, &spec, &http))
+        {
+            Ok(flow) => {
+                if flows.iter().any(|f| f.source == flow.source) {
+                    continue;
+                }
+                tracing::info!(def, "restored dynamic subscription");
+                flows.push(flow);
+                dynamic_defs.push(def);
+            }
+            Err(e) => tracing::warn!(def, error = %e, "cannot restore subscription"),
+        }
+    }
+    if args.dry_run {
+        tracing::info!("dry run: nothing will be written");
+        bzl_database::sink::dry_run::make_flows_dry(&mut flows);
+    }
+    let sources: Vec<_> = flows.iter().map(|f| f.source.clone()).collect();
+
+    if !args.dry_run {
+        let repaired = bzl_database::sink::csv::repair_tree(std::path::Path::new(&cfg.data_dir))?;
+        if repaired > 0 {
+            tracing::warn!(repaired, "repaired truncated CSV files at startup");
+        }
+    }
+    let checkpoints = CheckpointStore::from_config(&cfg, &http)?;
+    if !args.dry_run {
+        checkpoints.ensure_schema().await?;
+    }
+    // Last processed trade id per symbol; used both to backfill the
+    // reconnect window over REST and to drop overlapping live trades.
+    let mut last_ids: HashMap<String, (i64, i64)> = HashMap::new();
+    for source in &sources {
+        if source.stream != "trade" && source.stream != "aggtrade" {
+            continue;
+        }
+        let symbol = source.symbol.to_uppercase();
+        let Some(ckpt) = checkpoints.load_trades(&symbol).await? else {
+            continue;
+        };
+        last_ids.insert(symbol.clone(), (ckpt.last_trade_id, ckpt.last_trade_time));
+        tracing::info!(symbol, from_id = ckpt.last_trade_id, "backfilling reconnect window");
+        loop {
+            let from_id = last_ids[&symbol].0 + 1;
+            let trades = match exchange.fetch_agg_trades(&http, &symbol, from_id, 1000).await {
+                Ok(trades) => trades,
+                Err(e) => {
+                    tracing::warn!(symbol, error = %e, "reconnect backfill failed; continuing live");
+                    break;
+                }
+            };
+            let done = trades.len() < 1000;
+            for trade in trades {
+                last_ids.insert(symbol.clone(), (trade.trade_id, trade.trade_time));
+                dispatch(&mut flows, bzl_database::pipeline::Event::Trade(trade), true).await;
+            }
+            if done {
+                break;
+            }
+        }
+    }
+
+    // Kline streams: backfill from the checkpoint over REST and prime
+    // a stitcher so the live stream's overlap window deduplicates.
+    let mut kline_last: HashMap<(String, Interval), i64> = HashMap::new();
+    for flow in flows.iter_mut() {
+        let Some(interval_str) = flow.source.stream.strip_prefix("kline_") else {
+            continue;
+        };
+        let Ok(interval) = interval_str.parse::<Interval>() else {
+            tracing::warn!(stream = flow.source.stream, "unknown kline interval; not stitching");
+            continue;
+        };
+        let symbol = flow.source.symbol.to_uppercase();
+        let mut stitcher = bzl_database::pipeline::stitch::KlineStitcher::new();
+        if let Some(ckpt) = checkpoints.load(&symbol, interval).await? {
+            if let Some(mut cursor) = ckpt.last_open_time {
+                tracing::info!(symbol, %interval, from = cursor, "backfilling kline gap over REST");
+                for _ in 0..1000 {
+                    let klines = exchange
+                        .fetch_klines(&http, &symbol, interval.as_str(), Some(cursor + interval.ms()), None, 1000)
+                        .await
+                        .unwrap_or_default();
+                    let done = klines.len() < 1000;
+                    for kline in klines {
+                        cursor = cursor.max(kline.open_time);
+                        let out = flow.pipeline.run(Event::Kline(kline));
+                        for sink in flow.sinks.iter_mut() {
+                            if let Err(e) = sink.write(&out).await {
+                                tracing::error!(sink = sink.name(), error = %e, "backfill write failed");
+                            }
+                        }
+                    }
+                    if done {
+                        break;
+                    }
+                }
+                stitcher.prime(&symbol, interval.as_str(), cursor);
+                kline_last.insert((symbol.clone(), interval), cursor);
+            }
+        }
+        flow.pipeline.push(Box::new(stitcher));
+    }
+
+    let ws = connect_with_failover(&exchange).await?;
+    let (write, mut read) = ws.split();
+    // Shared so both the ping responder and runtime subscription
+    // changes (new listings) can send frames.
+    let write = std::sync::Arc::new(tokio::sync::Mutex::new(write));
+    for payload in exchange.ws_subscribe(&sources) {
+        write
+            .lock()
+            .await
+            .send(Message::Text(payload))
+            .await
+            .map_err(|e| Error::Exchange(format!("subscribe failed: {e}")))?;
+    }
+
+    let queue: EventQueue<String> = EventQueue::new(&cfg.channel)?;
+    let tx = queue.clone();
+    let pong_write = write.clone();
+    tokio::spawn(async move {
+        while let Some(frame) = read.next().await {
+            match frame {
+                Ok(Message::Text(text)) => {
+                    if bzl_database::chaos::drop_connection() {
+                        tracing::warn!("chaos: dropping websocket connection");
+                        break;
+                    }
+                    let text = bzl_database::chaos::garble(text.to_string());
+                    if tx.send(text).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(Message::Ping(payload)) => {
+                    if pong_write.lock().await.send(Message::Pong(payload)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::error!(error = %e, "websocket read failed");
+                    break;
+                }
+            }
+        }
+        tx.close();
+    });
+
+    // Flush on a timer so micro-batches cannot strand on a quiet
+    // stream.
+    let mut flush_tick = tokio::time::interval(std::time::Duration::from_millis(500));
+    let coordinator = match (&cfg.sharding, &cfg.clickhouse) {
+        (Some(sharding), Some(ch)) => {
+            let coordinator = bzl_database::ops::sharding::Coordinator::new(
+                bzl_database::db::ClickHouse::new(ch.clone(), http.clone()),
+                sharding,
+            );
+            coordinator.ensure_schema().await?;
+            Some(coordinator)
+        }
+        (Some(_), None) => {
+            return Err(Error::Config(
+                "[sharding] needs a [clickhouse] config section".to_string(),
+            ))
+        }
+        _ => None,
+    };
+    let universe: Vec<String> = sources.iter().map(|s| s.symbol.to_uppercase()).collect();
+    // With sharding, only symbols this host holds a lease on are
+    // collected; without it, everything is.
+    let mut active_symbols: std::collections::HashSet<String> = match &coordinator {
+        Some(coordinator) => {
+            let mine = coordinator.claim_and_renew(&universe, clock.now_ms()).await?;
+            tracing::info!(host = coordinator.host_id(), claimed = mine.len(), total = universe.len(), "shard claimed");
+            mine.into_iter().collect()
+        }
+        None => universe.iter().cloned().collect(),
+    };
+    let leadership = match (&cfg.leadership, &cfg.clickhouse) {
+        (Some(lcfg), Some(ch)) => {
+            let lock = bzl_database::ops::leadership::Leadership::new(
+                bzl_database::db::ClickHouse::new(ch.clone(), http.clone()),
+                lcfg,
+            );
+            lock.ensure_schema().await?;
+            Some(lock)
+        }
+        (Some(_), None) => {
+            return Err(Error::Config(
+                "[leadership] needs a [clickhouse] config section".to_string(),
+            ))
+        }
+        _ => None,
+    };
+    // Without a leadership section every instance persists; with one,
+    // only the lock holder does — the standby stays warm but silent.
+    let mut is_leader = match &leadership {
+        Some(lock) => {
+            let leading = lock.heartbeat(clock.now_ms()).await?;
+            tracing::info!(host = lock.host_id(), leading, "leadership heartbeat");
+            leading
+        }
+        None => true,
+    };
+    let audit = bzl_database::audit::Audit::new(
+        cfg.clickhouse
+            .clone()
+            .map(|c| bzl_database::db::ClickHouse::new(c, http.clone())),
+    );
+    if !args.dry_run {
+        audit.ensure_schema().await?;
+    }
+    match (&cfg.grafana, &cfg.clickhouse) {
+        (Some(gcfg), Some(ch)) if !args.dry_run => {
+            let db = bzl_database::db::ClickHouse::new(ch.clone(), http.clone());
+            bzl_database::grafana::ensure_schema(&db, gcfg).await?;
+        }
+        (Some(_), None) => {
+            return Err(Error::Config(
+                "[grafana] needs a [clickhouse] config section".to_string(),
+            ))
+        }
+        _ => {}
+    }
+    audit.record("collector_started", "", &format!("{} flow(s)", flows.len())).await;
+    let (admin_tx, mut admin_rx) = tokio::sync::mpsc::channel::<AdminRequest>(16);
+    if let Some(admin) = &cfg.admin {
+        let socket = admin
+            .socket
+            .clone()
+            .unwrap_or_else(|| format!("{}/admin.sock", cfg.data_dir));
+        bzl_database::admin::spawn(socket, admin_tx.clone())?;
+    }
+    let mut paused_symbols: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut listing_watcher = ListingWatcher::new();
+    let mut prev_universe: Option<Vec<bzl_database::exchange::symbols::SymbolInfo>> = None;
+    let status_db = cfg
+        .clickhouse
+        .clone()
+        .map(|c| bzl_database::db::ClickHouse::new(c, http.clone()));
+    if let (Some(db), false) = (&status_db, args.dry_run) {
+        if let Err(e) = bzl_database::exchange::symbols::ensure_changes_schema(db).await {
+            tracing::warn!(error = %e, "cannot create symbol_status_changes table");
+        }
+    }
+    let listing_poll = cfg.listings.as_ref().map(|l| l.poll_secs).unwrap_or(3_600);
+    let mut listing_tick =
+        tokio::time::interval(std::time::Duration::from_secs(listing_poll.max(1)));
+    loop {
+        tokio::select! {
+            _ = listing_tick.tick(), if cfg.listings.is_some() => {
+                let lcfg = cfg.listings.as_ref().expect("guarded by select condition");
+                let universe = match bzl_database::exchange::symbols::fetch_exchange_info(
+                    &http,
+                    exchange.rest_url(),
+                )
+                .await
+                {
+                    Ok(universe) => universe,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "listing poll failed");
+                        continue;
+                    }
+                };
+                match onboard_new_listings(
+                    &cfg,
+                    lcfg,
+                    &mut listing_watcher,
+                    &universe,
+                    &exchange,
+                    &http,
+                    &mut flows,
+                    &write,
+                    args.dry_run,
+                    &audit,
+                    &subscription_store,
+                    &mut dynamic_defs,
+                )
+                .await
+                {
+                    Ok(0) => {}
+                    Ok(n) => tracing::info!(new_symbols = n, "onboarded new listings"),
+                    Err(e) => tracing::warn!(error = %e, "listing onboarding failed"),
+                }
+                let now = chrono::Utc::now().timestamp_millis();
+                if let Some(prev) = &prev_universe {
+                    let changes =
+                        bzl_database::exchange::symbols::diff_universe(prev, &universe, now);
+                    for change in &changes {
+                        tracing::info!(
+                            symbol = change.symbol,
+                            field = change.field,
+                            old = change.old,
+                            new = change.new,
+                            "symbol changed"
+                        );
+                        dispatch(&mut flows, Event::Alert(change.to_alert()), true).await;
+                    }
+                    if let (Some(db), false) = (&status_db, args.dry_run) {
+                        if let Err(e) =
+                            bzl_database::exchange::symbols::record_changes(db, &changes).await
+                        {
+                            tracing::warn!(error = %e, "cannot record symbol changes");
+                        }
+                    }
+                }
+                prev_universe = Some(universe.clone());
+                if let Err(e) = retire_halted_symbols(
+                    &cfg,
+                    &universe,
+                    &exchange,
+                    &mut listing_watcher,
+                    &mut flows,
+                    &write,
+                    &audit,
+                    &subscription_store,
+                    &mut dynamic_defs,
+                )
+                .await
+                {
+                    tracing::warn!(error = %e, "delisting sweep failed");
+                }
+            }
+            maybe = queue.recv() => {
+                let text = match maybe {
+                    Some(text) => text,
+                    None => break,
+                };
+                let events = match exchange.parse_ws_message(&text) {
+                    Ok(events) => events,
+                    Err(e) => {
+                        tracing::warn!(error = %e, "unparseable frame");
+                        continue;
+                    }
+                };
+                for event in events {
+                    if let Event::Kline(k) = &event {
+                        if let Ok(interval) = k.interval.parse::<Interval>() {
+                            let entry = kline_last
+                                .entry((k.symbol.to_uppercase(), interval))
+                                .or_insert(i64::MIN);
+                            *entry = (*entry).max(k.open_time);
+                        }
+                    }
+                    if let Event::Trade(t) = &event {
+                        let key = t.symbol.to_uppercase();
+                        // Drop live trades the backfill already covered.
+                        if let Some(&(last, _)) = last_ids.get(&key) {
+                            if t.trade_id != 0 && t.trade_id <= last {
+                                continue;
+                            }
+                        }
+                        if t.trade_id != 0 {
+                            last_ids.insert(key, (t.trade_id, t.trade_time));
+                        }
+                    }
+                    let symbol = event.symbol().to_uppercase();
+                    if !active_symbols.contains(&symbol) || paused_symbols.contains(&symbol) {
+                        continue;
+                    }
+                    dispatch(&mut flows, event, is_leader).await;
+                }
+            }
+            Some(request) = admin_rx.recv() => {
+                let response = match request.command {
+                    AdminCommand::Pause(symbol) => {
+                        paused_symbols.insert(symbol.clone());
+                        format!("paused {symbol}")
+                    }
+                    AdminCommand::Resume(symbol) => {
+                        if paused_symbols.remove(&symbol) {
+                            format!("resumed {symbol}")
+                        } else {
+                            format!("{symbol} was not paused")
+                        }
+                    }
+                    AdminCommand::Flush => {
+                        let mut failures = 0;
+                        for flow in flows.iter_mut() {
+                            for sink in flow.sinks.iter_mut() {
+                                if sink.flush().await.is_err() {
+                                    failures += 1;
+                                }
+                            }
+                        }
+                        if failures == 0 {
+                            "flushed".to_string()
+                        } else {
+                            format!("flushed with {failures} sink error(s)")
+                        }
+                    }
+                    AdminCommand::Status => {
+                        let metrics = queue.metrics();
+                        let mut active: Vec<&String> = active_symbols.iter().collect();
+                        active.sort();
+                        format!(
+                            "flows={} active={:?} paused={:?} backlog={} dropped={} spilled={}",
+                            flows.len(),
+                            active,
+                            paused_symbols,
+                            metrics.backlog,
+                            metrics.dropped,
+                            metrics.spilled
+                        )
+                    }
+                    AdminCommand::Handover => match &leadership {
+                        None => {
+                            "error: handover needs a [leadership] config section".to_string()
+                        }
+                        Some(lock) => {
+                            // Deploy protocol: the new process is already
+                            // subscribed and warm in standby. Flush, then
+                            // release the lock so it takes over on its next
+                            // heartbeat; overlapping rows deduplicate.
+                            for flow in flows.iter_mut() {
+                                for sink in flow.sinks.iter_mut() {
+                                    let _ = sink.flush().await;
+                                }
+                            }
+                            match lock.release(clock.now_ms()).await {
+                                Ok(()) => {
+                                    is_leader = false;
+                                    audit.record("handover", lock.host_id(), "leadership released").await;
+                                    tracing::warn!("leadership released for handover; standing by");
+                                    "handover complete; standing by".to_string()
+                                }
+                                Err(e) => format!("error: release failed: {e}"),
+                            }
+                        }
+                    },
+                    AdminCommand::AddSymbol(symbol) => match cfg.listings.as_ref() {
+                        None => "error: add-symbol needs a [listings] pipeline template".to_string(),
+                        Some(lcfg) => {
+                            let def = lcfg.pipeline.replace("{symbol}", &symbol.to_lowercase());
+                            match bzl_database::pipeline::spec::PipelineSpec::parse(&def)
+                                .and_then(|spec| {
+                                    bzl_database::pipeline::spec::build_flow(&cfg, &spec, &http)
+                                }) {
+                                Err(e) => format!("error: {e}"),
+                                Ok(flow) => {
+                                    let mut subscribed = true;
+                                    for payload in
+                                        exchange.ws_subscribe(std::slice::from_ref(&flow.source))
+                                    {
+                                        if write
+                                            .lock()
+                                            .await
+                                            .send(Message::Text(payload))
+                                            .await
+                                            .is_err()
+                                        {
+                                            subscribed = false;
+                                        }
+                                    }
+                                    active_symbols.insert(symbol.clone());
+                                    flows.push(flow);
+                                    dynamic_defs.push(def.clone());
+                                    if let Err(e) = subscription_store.save(&dynamic_defs) {
+                                        tracing::warn!(error = %e, "cannot persist subscriptions");
+                                    }
+                                    if subscribed {
+                                        format!("added {symbol}")
+                                    } else {
+                                        format!("added {symbol} (subscribe failed)")
+                                    }
+                                }
+                            }
+                        }
+                    },
+                };
+                let _ = request.reply.send(response);
+            }
+            _ = flush_tick.tick() => {
+                if let Some(lock) = &leadership {
+                    match lock.heartbeat(clock.now_ms()).await {
+                        Ok(leading) => {
+                            if leading != is_leader {
+                                if leading {
+                                    tracing::warn!(host = lock.host_id(), "acquired leadership; persisting");
+                                } else {
+                                    tracing::warn!(host = lock.host_id(), "lost leadership; standing by");
+                                }
+                                is_leader = leading;
+                            }
+                        }
+                        Err(e) => tracing::warn!(error = %e, "leadership heartbeat failed"),
+                    }
+                }
+                if let Some(coordinator) = &coordinator {
+                    match coordinator.claim_and_renew(&universe, clock.now_ms()).await {
+                        Ok(mine) => {
+                            let fresh: std::collections::HashSet<String> = mine.into_iter().collect();
+                            if fresh != active_symbols {
+                                tracing::info!(
+                                    held = fresh.len(),
+                                    "shard rebalanced"
+                                );
+                                active_symbols = fresh;
+                            }
+                        }
+                        Err(e) => tracing::warn!(error = %e, "lease renewal failed"),
+                    }
+                }
+                let metrics = queue.metrics();
+                if metrics.backlog > 0 || metrics.dropped > 0 || metrics.spilled > 0 {
+                    tracing::info!(
+                        backlog = metrics.backlog,
+                        dropped = metrics.dropped,
+                        spilled = metrics.spilled,
+                        "intake queue"
+                    );
+                }
+                for flow in flows.iter_mut() {
+                    for sink in flow.sinks.iter_mut() {
+                        if let Err(e) = sink.flush().await {
+                            tracing::error!(sink = sink.name(), error = %e, "sink flush failed");
+                        }
+                    }
+                }
+                if !args.dry_run {
+                    let now = clock.now_ms();
+                    for (symbol, &(id, time)) in &last_ids {
+                        let ckpt = TradeCheckpoint::new(symbol, id, time);
+                        if let Err(e) = checkpoints.save_trades(&ckpt, now).await {
+                            tracing::error!(symbol, error = %e, "checkpoint save failed");
+                        }
+                    }
+                    for ((symbol, interval), &open_time) in &kline_last {
+                        let mut ckpt = Checkpoint::new(symbol, *interval);
+                        ckpt.last_open_time = Some(open_time);
+                        if let Err(e) = checkpoints.save(&ckpt, now).await {
+                            tracing::error!(symbol, error = %e, "checkpoint save failed");
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn matches_flow(flow: &Flow, symbol: &str) -> bool {
+    flow.source.symbol.eq_ignore_ascii_case(symbol)
+}
+
+type SharedWrite = std::sync::Arc<
+    tokio::sync::Mutex<
+        futures_util::stream::SplitSink<WsStream, Message>,
+    >,
+>;
+
+/// Poll exchangeInfo and onboard any newly listed symbol matching the
+/// configured filter: build its pipeline from the template, backfill
+/// 1m history from the first candle, then subscribe its live stream.
+#[allow(clippy::too_many_arguments)]
+async fn onboard_new_listings(
+    cfg: &Config,
+    lcfg: &bzl_database::jobs::listings::ListingsConfig,
+    watcher: &mut ListingWatcher,
+    universe: &[bzl_database::exchange::symbols::SymbolInfo],
+    exchange: &Binance,
+    http: &reqwest::Client,
+    flows: &mut Vec<Flow>,
+    write: &SharedWrite,
+    dry_run: bool,
+    audit: &bzl_database::audit::Audit,
+    subscriptions: &bzl_database::subscriptions::SubscriptionSet,
+    dynamic_defs: &mut Vec<String>,
+) -> Result<usize> {
+    let fresh: Vec<String> = watcher
+        .diff(universe, &lcfg.filter)?
+        .into_iter()
+        .map(|s| s.symbol.clone())
+        .collect();
+    let count = fresh.len();
+    for symbol in fresh {
+        let def = lcfg.pipeline.replace("{symbol}", &symbol.to_lowercase());
+        let spec = bzl_database::pipeline::spec::PipelineSpec::parse(&def)?;
+        let mut flow = bzl_database::pipeline::spec::build_flow(cfg, &spec, http)?;
+        if dry_run {
+            flow.sinks = flow
+                .sinks
+                .iter()
+                .map(|s| {
+                    Box::new(bzl_database::sink::dry_run::DryRunSink::new(s.name()))
+                        as Box<dyn bzl_database::sink::Sink>
+                })
+                .collect();
+        }
+        tracing::info!(symbol, "new listing: backfilling from first candle");
+        let mut cursor = 0i64;
+        for _ in 0..1000 {
+            let klines = exchange
+                .fetch_klines(http, &symbol, "1m", Some(cursor), None, 1000)
+                .await?;
+            let done = klines.len() < 1000;
+            if let Some(last) = klines.last() {
+                cursor = last.open_time + 60_000;
+            }
+            let events: Vec<Event> = klines.into_iter().map(Event::Kline).collect();
+            for sink in flow.sinks.iter_mut() {
+                if let Err(e) = sink.write(&events).await {
+                    tracing::error!(sink = sink.name(), error = %e, "backfill write failed");
+                }
+            }
+            if done {
+                break;
+            }
+        }
+        for payload in exchange.ws_subscribe(&[flow.source.clone()]) {
+            write
+                .lock()
+                .await
+                .send(Message::Text(payload))
+                .await
+                .map_err(|e| Error::Exchange(format!("subscribe failed: {e}")))?;
+        }
+        tracing::info!(symbol, "subscribed live stream");
+        audit.record("symbol_onboarded", &symbol, "new listing").await;
+        flows.push(flow);
+        dynamic_defs.push(def);
+        if let Err(e) = subscriptions.save(dynamic_defs) {
+            tracing::warn!(error = %e, "cannot persist subscriptions");
+        }
+    }
+    Ok(count)
+}
+
+/// Stop collection for symbols that went BREAK/HALT or vanished from
+/// exchangeInfo: alert, flush and drop their flows, unsubscribe, and
+/// mark the series closed so nothing loops on empty responses forever.
+#[allow(clippy::too_many_arguments)]
+async fn retire_halted_symbols(
+    cfg: &Config,
+    universe: &[bzl_database::exchange::symbols::SymbolInfo],
+    exchange: &Binance,
+    watcher: &mut ListingWatcher,
+    flows: &mut Vec<Flow>,
+    write: &SharedWrite,
+    audit: &bzl_database::audit::Audit,
+    subscription_store: &bzl_database::subscriptions::SubscriptionSet,
+    dynamic_defs: &mut Vec<String>,
+) -> Result<()> {
+    let mut retired: Vec<Flow> = Vec::new();
+    let mut keep: Vec<Flow> = Vec::new();
+    for flow in flows.drain(..) {
+        let symbol = flow.source.symbol.to_uppercase();
+        let status = universe
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .map(|s| s.status.as_str());
+        match status {
+            Some("TRADING") => keep.push(flow),
+            status => {
+                let reason = status.unwrap_or("DELISTED").to_string();
+                audit.record("symbol_retired", &symbol, &reason).await;
+                let lowered = symbol.to_lowercase();
+                dynamic_defs.retain(|def| !def.starts_with(&format!("{lowered}@")));
+                if let Err(e) = subscription_store.save(dynamic_defs) {
+                    tracing::warn!(error = %e, "cannot persist subscriptions");
+                }
+                tracing::warn!(symbol, reason, "symbol halted; stopping collection");
+                retired.push(flow);
+                let mut marker = std::path::PathBuf::from(&cfg.data_dir);
+                std::fs::create_dir_all(&marker)?;
+                marker.push(format!("{symbol}.closed"));
+                std::fs::write(
+                    &marker,
+                    serde_json::json!({
+                        "symbol": symbol,
+                        "reason": reason,
+                        "closed_at": chrono::Utc::now().timestamp_millis(),
+                    })
+                    .to_string(),
+                )?;
+                watcher.forget(&symbol);
+            }
+        }
+    }
+    *flows = keep;
+    for mut flow in retired {
+        let symbol = flow.source.symbol.to_uppercase();
+        let alert = Event::Alert(bzl_database::pipeline::Alert {
+            symbol: symbol.clone(),
+            source: "delisting".to_string(),
+            message: "symbol halted or delisted; series closed".to_string(),
+            time: chrono::Utc::now().timestamp_millis(),
+        });
+        for sink in flow.sinks.iter_mut() {
+            let _ = sink.write(std::slice::from_ref(&alert)).await;
+            let _ = sink.flush().await;
+        }
+        for payload in exchange.ws_unsubscribe(&[flow.source.clone()]) {
+            if let Err(e) = write.lock().await.send(Message::Text(payload)).await {
+                tracing::warn!(symbol, error = %e, "unsubscribe failed");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run one event through every matching flow and its sinks. A strict
+/// validation violation is fatal: the batch is rejected and the
+/// collector stops rather than archiving around bad data. Standby
+/// instances (`persist = false`) run the pipeline to stay warm but
+/// write nothing.
+async fn dispatch(flows: &mut [Flow], event: Event, persist: bool) {
+    for flow in flows.iter_mut() {
+        if !matches_flow(flow, event.symbol()) {
+            continue;
+        }
+        let out = flow.pipeline.run(event.clone());
+        if out.is_empty() {
+            continue;
+        }
+        for ev in &out {
+            if let Event::Quarantined(q) = ev {
+                if q.strict {
+                    tracing::error!(
+                        symbol = q.symbol,
+                        reason = q.reason,
+                        "strict validation failure; aborting"
+                    );
+                    std::process::exit(2);
+                }
+            }
+        }
+        if !persist {
+            continue;
+        }
+        for sink in flow.sinks.iter_mut() {
+            if let Err(e) = sink.write(&out).await {
+                tracing::error!(sink = sink.name(), error = %e, "sink write failed");
+            }
+        }
+    }
+}
+
+type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Try every configured WebSocket endpoint once before giving up.
+async fn connect_with_failover(exchange: &Binance) -> Result<WsStream> {
+    let mut url = exchange.ws_url();
+    let mut last_error = String::new();
+    for _ in 0..4 {
+        match tokio_tungstenite::connect_async(&url).await {
+            Ok((ws, _)) => {
+                tracing::info!(%url, "connected");
+                return Ok(ws);
+            }
+            Err(e) => {
+                tracing::warn!(%url, error = %e, "connect failed, trying next endpoint");
+                last_error = e.to_string();
+                url = exchange.next_ws_url();
+            }
+        }
+    }
+    Err(Error::Exchange(format!(
+        "all websocket endpoints failed, last error: {last_error}"
+    )))
+}
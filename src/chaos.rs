@@ -0,0 +1,181 @@
+//! Fault injection for resilience testing.
+//!
+//! With a `[chaos]` section in config, the client layer randomly
+//! injects WebSocket disconnects, slow responses, 429 rejections and
+//! malformed payloads — so reconnection, rate limiting and quarantine
+//! logic can be exercised on demand rather than discovered in
+//! production. A fixed `seed` makes a chaos run reproducible.
+
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// The `[chaos]` config section; all probabilities are percentages
+/// applied independently per operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChaosConfig {
+    /// Chance a REST request is rejected with an injected 429.
+    #[serde(default)]
+    pub http_429_pct: f64,
+    /// Chance a REST request is delayed by `slow_ms`.
+    #[serde(default)]
+    pub slow_pct: f64,
+    /// Injected delay for slow responses.
+    #[serde(default = "default_slow_ms")]
+    pub slow_ms: u64,
+    /// Chance a received WebSocket frame is garbled.
+    #[serde(default)]
+    pub garble_pct: f64,
+    /// Chance (checked per frame) that the WebSocket is dropped.
+    #[serde(default)]
+    pub disconnect_pct: f64,
+    /// PRNG seed for reproducible runs.
+    #[serde(default = "default_seed")]
+    pub seed: u64,
+}
+
+fn default_slow_ms() -> u64 {
+    2_000
+}
+
+fn default_seed() -> u64 {
+    0x5eed_cafe
+}
+
+struct ChaosState {
+    config: ChaosConfig,
+    rng: u64,
+}
+
+static STATE: Mutex<Option<ChaosState>> = Mutex::new(None);
+
+/// Arm (or disarm) chaos mode process-wide.
+pub fn set(config: Option<ChaosConfig>) {
+    *STATE.lock().expect("chaos state poisoned") = config.map(|config| ChaosState {
+        rng: config.seed.max(1),
+        config,
+    });
+}
+
+/// Whether chaos mode is armed.
+pub fn armed() -> bool {
+    STATE.lock().expect("chaos state poisoned").is_some()
+}
+
+/// Roll a percentage using the seeded xorshift generator.
+fn roll(pct: f64) -> bool {
+    if pct <= 0.0 {
+        return false;
+    }
+    let mut guard = STATE.lock().expect("chaos state poisoned");
+    let Some(state) = guard.as_mut() else {
+        return false;
+    };
+    // xorshift64: plenty for fault scheduling.
+    let mut x = state.rng;
+    x ^= x << 13;
+    x ^= x >> 7;
+    x ^= x << 17;
+    state.rng = x;
+    ((x % 10_000) as f64) < pct * 100.0
+}
+
+/// A fault chosen for one REST request.
+#[derive(Debug, PartialEq, Eq)]
+pub enum RestFault {
+    /// Delay the request by this many milliseconds.
+    Slow(u64),
+    /// Reject with an injected 429.
+    Http429,
+}
+
+/// Decide the fate of one REST request.
+pub fn rest_fault() -> Option<RestFault> {
+    let (http_429_pct, slow_pct, slow_ms) = {
+        let guard = STATE.lock().expect("chaos state poisoned");
+        let state = guard.as_ref()?;
+        (
+            state.config.http_429_pct,
+            state.config.slow_pct,
+            state.config.slow_ms,
+        )
+    };
+    if roll(http_429_pct) {
+        return Some(RestFault::Http429);
+    }
+    if roll(slow_pct) {
+        return Some(RestFault::Slow(slow_ms));
+    }
+    None
+}
+
+/// Whether to drop the WebSocket connection now.
+pub fn drop_connection() -> bool {
+    let pct = {
+        let guard = STATE.lock().expect("chaos state poisoned");
+        match guard.as_ref() {
+            Some(state) => state.config.disconnect_pct,
+            None => return false,
+        }
+    };
+    roll(pct)
+}
+
+/// Possibly garble one WebSocket frame.
+pub fn garble(text: String) -> String {
+    let pct = {
+        let guard = STATE.lock().expect("chaos state poisoned");
+        match guard.as_ref() {
+            Some(state) => state.config.garble_pct,
+            None => return text,
+        }
+    };
+    if roll(pct) {
+        // Truncate mid-structure: a classic malformed payload.
+        let cut = text.len() / 2;
+        format!("{}<garbled>", &text[..cut])
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(garble_pct: f64, http_429_pct: f64) -> ChaosConfig {
+        ChaosConfig {
+            http_429_pct,
+            slow_pct: 0.0,
+            slow_ms: 10,
+            garble_pct,
+            disconnect_pct: 0.0,
+            seed: 42,
+        }
+    }
+
+    // One test: the state is process-global, so parallel tests would
+    // race on arming and disarming it.
+    #[test]
+    fn chaos_arming_injection_and_determinism() {
+        set(None);
+        assert!(!armed());
+        assert!(rest_fault().is_none());
+        assert!(!drop_connection());
+        assert_eq!(garble("{}".to_string()), "{}");
+
+        set(Some(config(100.0, 0.0)));
+        assert!(armed());
+        let garbled = garble(r#"{"e":"trade"}"#.to_string());
+        assert!(garbled.contains("<garbled>"));
+        set(Some(config(0.0, 100.0)));
+        assert_eq!(rest_fault(), Some(RestFault::Http429));
+        // Same seed, same sequence.
+        set(Some(config(50.0, 0.0)));
+        let a: Vec<bool> = (0..16).map(|_| garble("xx".into()).len() > 2).collect();
+        set(Some(config(50.0, 0.0)));
+        let b: Vec<bool> = (0..16).map(|_| garble("xx".into()).len() > 2).collect();
+        assert_eq!(a, b);
+        set(None);
+    }
+}
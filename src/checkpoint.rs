@@ -0,0 +1,428 @@
+//! Collector checkpoints.
+//!
+//! The old `.state` files were bare timestamps written non-atomically
+//! and carried no interval, so switching intervals silently corrupted
+//! resume logic. Checkpoints are now JSON with a schema version,
+//! written via temp-file-and-rename, and validated against the job
+//! they are resuming.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::model::Interval;
+
+/// Bumped whenever the checkpoint layout changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Progress of one symbol/interval collection job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Layout version; mismatches are rejected on load.
+    pub schema_version: u32,
+    /// Symbol the checkpoint belongs to.
+    pub symbol: String,
+    /// Interval the checkpoint belongs to.
+    pub interval: Interval,
+    /// Open time of the next candle to fetch, if kline collection.
+    #[serde(default)]
+    pub last_open_time: Option<i64>,
+    /// Last processed trade id, if trade collection.
+    #[serde(default)]
+    pub last_trade_id: Option<i64>,
+}
+
+impl Checkpoint {
+    /// A fresh checkpoint for a job.
+    pub fn new(symbol: &str, interval: Interval) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            symbol: symbol.to_uppercase(),
+            interval,
+            last_open_time: None,
+            last_trade_id: None,
+        }
+    }
+
+    /// File path for a job's checkpoint under `dir`.
+    pub fn path(dir: impl AsRef<Path>, symbol: &str, interval: Interval) -> PathBuf {
+        dir.as_ref()
+            .join(format!("{}-{}.state", symbol.to_uppercase(), interval))
+    }
+
+    /// Write atomically: serialize to a sibling temp file, then rename
+    /// over the target so readers never observe a partial file.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let path = Self::path(dir, &self.symbol, self.interval);
+        let tmp = path.with_extension("state.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// Load and validate a job's checkpoint. Returns `Ok(None)` when no
+    /// checkpoint exists; legacy bare-timestamp files are migrated.
+    pub fn load(
+        dir: impl AsRef<Path>,
+        symbol: &str,
+        interval: Interval,
+    ) -> Result<Option<Checkpoint>> {
+        let dir = dir.as_ref();
+        let path = Self::path(dir, symbol, interval);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Self::load_legacy(dir, symbol, interval)
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let checkpoint: Checkpoint = serde_json::from_str(&text)
+            .map_err(|e| Error::Config(format!("corrupt checkpoint {}: {e}", path.display())))?;
+        if checkpoint.schema_version != SCHEMA_VERSION {
+            return Err(Error::Config(format!(
+                "checkpoint {} has schema {} (expected {SCHEMA_VERSION})",
+                path.display(),
+                checkpoint.schema_version
+            )));
+        }
+        if !checkpoint.symbol.eq_ignore_ascii_case(symbol) || checkpoint.interval != interval {
+            return Err(Error::Config(format!(
+                "checkpoint {} is for {}/{}, not {}/{interval}",
+                path.display(),
+                checkpoint.symbol,
+                checkpoint.interval,
+                symbol.to_uppercase()
+            )));
+        }
+        Ok(Some(checkpoint))
+    }
+
+    /// Migrate an old `<SYMBOL>.state` bare-timestamp file. The legacy
+    /// format never recorded an interval, so it is trusted only for
+    /// the job that asks.
+    fn load_legacy(dir: &Path, symbol: &str, interval: Interval) -> Result<Option<Checkpoint>> {
+        let legacy = dir.join(format!("{}.state", symbol.to_uppercase()));
+        let Ok(text) = std::fs::read_to_string(&legacy) else {
+            return Ok(None);
+        };
+        let Ok(timestamp) = text.trim().parse::<i64>() else {
+            return Err(Error::Config(format!(
+                "corrupt legacy checkpoint {}",
+                legacy.display()
+            )));
+        };
+        let mut checkpoint = Checkpoint::new(symbol, interval);
+        checkpoint.last_open_time = Some(timestamp);
+        Ok(Some(checkpoint))
+    }
+}
+
+/// The `[checkpoints]` config section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CheckpointConfig {
+    /// Where checkpoints live.
+    #[serde(default)]
+    pub backend: CheckpointBackend,
+}
+
+/// Checkpoint storage backends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointBackend {
+    /// Per-job `.state` files under the data directory.
+    #[default]
+    Files,
+    /// A shared `checkpoints` table in ClickHouse, so several collector
+    /// hosts and the audit tooling see one consistent view.
+    #[serde(rename = "clickhouse")]
+    ClickHouse,
+}
+
+/// Row shape of the `checkpoints` table. Trade-stream checkpoints use
+/// the pseudo-interval `trades`.
+#[derive(Debug, Serialize, Deserialize)]
+struct CheckpointRow {
+    symbol: String,
+    interval: String,
+    schema_version: u32,
+    last_open_time: Option<i64>,
+    last_trade_id: Option<i64>,
+    updated_at: i64,
+}
+
+/// Progress of one symbol's live trade stream, keyed by trade id so a
+/// restart can backfill the reconnect window over REST before resuming.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TradeCheckpoint {
+    /// Layout version; mismatches are rejected on load.
+    pub schema_version: u32,
+    /// Symbol the checkpoint belongs to.
+    pub symbol: String,
+    /// Last processed trade (or aggTrade) id.
+    pub last_trade_id: i64,
+    /// Timestamp of that trade in epoch milliseconds.
+    pub last_trade_time: i64,
+}
+
+impl TradeCheckpoint {
+    /// A checkpoint at the given trade.
+    pub fn new(symbol: &str, last_trade_id: i64, last_trade_time: i64) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            symbol: symbol.to_uppercase(),
+            last_trade_id,
+            last_trade_time,
+        }
+    }
+
+    fn path(dir: &Path, symbol: &str) -> PathBuf {
+        dir.join(format!("{}-trades.state", symbol.to_uppercase()))
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        std::fs::create_dir_all(dir)?;
+        let path = Self::path(dir, &self.symbol);
+        let tmp = path.with_extension("state.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn load(dir: &Path, symbol: &str) -> Result<Option<Self>> {
+        let path = Self::path(dir, symbol);
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let ckpt: TradeCheckpoint = serde_json::from_str(&text)
+            .map_err(|e| Error::Config(format!("corrupt checkpoint {}: {e}", path.display())))?;
+        if ckpt.schema_version != SCHEMA_VERSION {
+            return Err(Error::Config(format!(
+                "checkpoint {} has schema {} (expected {SCHEMA_VERSION})",
+                path.display(),
+                ckpt.schema_version
+            )));
+        }
+        Ok(Some(ckpt))
+    }
+}
+
+/// Where collector progress is persisted.
+pub enum CheckpointStore {
+    /// `.state` files under a directory.
+    Files {
+        /// Directory holding the files.
+        dir: PathBuf,
+    },
+    /// The shared ClickHouse table.
+    ClickHouse(crate::db::ClickHouse),
+}
+
+impl CheckpointStore {
+    /// Build the store selected by config.
+    pub fn from_config(cfg: &crate::config::Config, http: &reqwest::Client) -> Result<Self> {
+        match cfg.checkpoints.backend {
+            CheckpointBackend::Files => Ok(CheckpointStore::Files {
+                dir: PathBuf::from(&cfg.data_dir),
+            }),
+            CheckpointBackend::ClickHouse => {
+                let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                    Error::Config(
+                        "checkpoints backend `clickhouse` needs a [clickhouse] section".to_string(),
+                    )
+                })?;
+                Ok(CheckpointStore::ClickHouse(crate::db::ClickHouse::new(
+                    ch,
+                    http.clone(),
+                )))
+            }
+        }
+    }
+
+    /// Create the backing table when using ClickHouse.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        if let CheckpointStore::ClickHouse(db) = self {
+            db.execute(
+                "CREATE TABLE IF NOT EXISTS checkpoints (\
+                 symbol String, interval String, schema_version UInt32, \
+                 last_open_time Nullable(Int64), last_trade_id Nullable(Int64), \
+                 updated_at Int64) \
+                 ENGINE = ReplacingMergeTree(updated_at) ORDER BY (symbol, interval)",
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Load a job's checkpoint.
+    pub async fn load(&self, symbol: &str, interval: Interval) -> Result<Option<Checkpoint>> {
+        match self {
+            CheckpointStore::Files { dir } => Checkpoint::load(dir, symbol, interval),
+            CheckpointStore::ClickHouse(db) => {
+                let sql = format!(
+                    "SELECT symbol, interval, schema_version, last_open_time, \
+                     last_trade_id, updated_at FROM checkpoints \
+                     WHERE symbol = '{}' AND interval = '{interval}' \
+                     ORDER BY updated_at DESC LIMIT 1",
+                    symbol.to_uppercase()
+                );
+                let rows: Vec<CheckpointRow> = db.query_rows(&sql).await?;
+                rows.into_iter()
+                    .next()
+                    .map(|r| {
+                        Ok(Checkpoint {
+                            schema_version: r.schema_version,
+                            symbol: r.symbol,
+                            interval: r.interval.parse()?,
+                            last_open_time: r.last_open_time,
+                            last_trade_id: r.last_trade_id,
+                        })
+                    })
+                    .transpose()
+            }
+        }
+    }
+
+    /// Persist a checkpoint.
+    pub async fn save(&self, checkpoint: &Checkpoint, now_ms: i64) -> Result<()> {
+        match self {
+            CheckpointStore::Files { dir } => checkpoint.save(dir),
+            CheckpointStore::ClickHouse(db) => {
+                let row = CheckpointRow {
+                    symbol: checkpoint.symbol.clone(),
+                    interval: checkpoint.interval.to_string(),
+                    schema_version: checkpoint.schema_version,
+                    last_open_time: checkpoint.last_open_time,
+                    last_trade_id: checkpoint.last_trade_id,
+                    updated_at: now_ms,
+                };
+                db.insert_rows("checkpoints", &[row]).await
+            }
+        }
+    }
+
+    /// Load a symbol's live trade-stream checkpoint.
+    pub async fn load_trades(&self, symbol: &str) -> Result<Option<TradeCheckpoint>> {
+        match self {
+            CheckpointStore::Files { dir } => TradeCheckpoint::load(dir, symbol),
+            CheckpointStore::ClickHouse(db) => {
+                let sql = format!(
+                    "SELECT symbol, interval, schema_version, last_open_time, \
+                     last_trade_id, updated_at FROM checkpoints \
+                     WHERE symbol = '{}' AND interval = 'trades' \
+                     ORDER BY updated_at DESC LIMIT 1",
+                    symbol.to_uppercase()
+                );
+                let rows: Vec<CheckpointRow> = db.query_rows(&sql).await?;
+                Ok(rows.into_iter().next().and_then(|r| {
+                    r.last_trade_id.map(|id| TradeCheckpoint {
+                        schema_version: r.schema_version,
+                        symbol: r.symbol,
+                        last_trade_id: id,
+                        last_trade_time: r.last_open_time.unwrap_or(0),
+                    })
+                }))
+            }
+        }
+    }
+
+    /// Persist a symbol's live trade-stream checkpoint.
+    pub async fn save_trades(&self, checkpoint: &TradeCheckpoint, now_ms: i64) -> Result<()> {
+        match self {
+            CheckpointStore::Files { dir } => checkpoint.save(dir),
+            CheckpointStore::ClickHouse(db) => {
+                let row = CheckpointRow {
+                    symbol: checkpoint.symbol.clone(),
+                    interval: "trades".to_string(),
+                    schema_version: checkpoint.schema_version,
+                    last_open_time: Some(checkpoint.last_trade_time),
+                    last_trade_id: Some(checkpoint.last_trade_id),
+                    updated_at: now_ms,
+                };
+                db.insert_rows("checkpoints", &[row]).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bzl-ckpt-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn round_trips_atomically() {
+        let dir = temp_dir("roundtrip");
+        let mut ckpt = Checkpoint::new("btcusdt", Interval::M1);
+        ckpt.last_open_time = Some(1_700_000_000_000);
+        ckpt.last_trade_id = Some(42);
+        ckpt.save(&dir).unwrap();
+        assert!(dir.join("BTCUSDT-1m.state").exists());
+        assert!(!dir.join("BTCUSDT-1m.state.tmp").exists());
+        let loaded = Checkpoint::load(&dir, "BTCUSDT", Interval::M1).unwrap().unwrap();
+        assert_eq!(loaded, ckpt);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn interval_mismatch_is_rejected() {
+        let dir = temp_dir("mismatch");
+        Checkpoint::new("BTCUSDT", Interval::M1).save(&dir).unwrap();
+        assert!(Checkpoint::load(&dir, "BTCUSDT", Interval::M1).unwrap().is_some());
+        // A different interval has its own file, so no cross-talk.
+        assert!(Checkpoint::load(&dir, "BTCUSDT", Interval::H1).unwrap().is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn legacy_bare_timestamp_is_migrated() {
+        let dir = temp_dir("legacy");
+        std::fs::write(dir.join("BTCUSDT.state"), "1700000000000").unwrap();
+        let ckpt = Checkpoint::load(&dir, "BTCUSDT", Interval::M1).unwrap().unwrap();
+        assert_eq!(ckpt.last_open_time, Some(1_700_000_000_000));
+        std::fs::write(dir.join("ETHUSDT.state"), "garbage").unwrap();
+        assert!(Checkpoint::load(&dir, "ETHUSDT", Interval::M1).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn files_store_round_trips() {
+        let dir = temp_dir("store");
+        let store = CheckpointStore::Files { dir: dir.clone() };
+        let mut ckpt = Checkpoint::new("BTCUSDT", Interval::M1);
+        ckpt.last_open_time = Some(123);
+        store.save(&ckpt, 0).await.unwrap();
+        let loaded = store.load("BTCUSDT", Interval::M1).await.unwrap().unwrap();
+        assert_eq!(loaded, ckpt);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn clickhouse_backend_requires_section() {
+        let cfg = crate::config::Config {
+            checkpoints: CheckpointConfig {
+                backend: CheckpointBackend::ClickHouse,
+            },
+            ..Default::default()
+        };
+        assert!(CheckpointStore::from_config(&cfg, &reqwest::Client::new()).is_err());
+    }
+
+    #[test]
+    fn corrupt_json_is_an_error() {
+        let dir = temp_dir("corrupt");
+        std::fs::write(dir.join("BTCUSDT-1m.state"), "{not json").unwrap();
+        assert!(Checkpoint::load(&dir, "BTCUSDT", Interval::M1).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
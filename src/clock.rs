@@ -0,0 +1,118 @@
+//! Server time synchronization.
+//!
+//! Wait-time arithmetic and signed-request timestamps break badly on
+//! machines with skewed clocks. [`ServerClock`] measures the offset
+//! against `/api/v3/time` (midpoint-corrected for round-trip time) and
+//! hands out a corrected `now`.
+
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// A drift-corrected clock. Cheap to clone; clones share the offset.
+#[derive(Clone, Default)]
+pub struct ServerClock {
+    // server_time - local_time, in milliseconds.
+    offset_ms: Arc<AtomicI64>,
+}
+
+impl ServerClock {
+    /// A clock with zero offset (until the first sync).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Corrected current time in epoch milliseconds.
+    pub fn now_ms(&self) -> i64 {
+        chrono::Utc::now().timestamp_millis() + self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// The measured offset (server minus local).
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Set the offset directly (tests, alternative sources).
+    pub fn set_offset_ms(&self, offset: i64) {
+        self.offset_ms.store(offset, Ordering::Relaxed);
+    }
+
+    /// Measure drift against `/api/v3/time` on the given REST host and
+    /// store it. Returns the new offset.
+    pub async fn sync(&self, http: &reqwest::Client, rest_url: &str) -> Result<i64> {
+        let before = chrono::Utc::now().timestamp_millis();
+        let body: Value = http
+            .get(format!("{rest_url}/api/v3/time"))
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("time request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("time response invalid: {e}")))?;
+        let after = chrono::Utc::now().timestamp_millis();
+        let server = body
+            .get("serverTime")
+            .and_then(Value::as_i64)
+            .ok_or_else(|| Error::Exchange(format!("time response without serverTime: {body}")))?;
+        // Assume the response was generated mid round trip.
+        let offset = server - (before + after) / 2;
+        self.offset_ms.store(offset, Ordering::Relaxed);
+        if offset.abs() > 1_000 {
+            tracing::warn!(offset_ms = offset, "local clock drifts from server time");
+        }
+        Ok(offset)
+    }
+
+    /// Spawn a task re-syncing every `period`; errors are logged and
+    /// the previous offset stays in effect.
+    pub fn spawn_periodic(
+        &self,
+        http: reqwest::Client,
+        rest_url: String,
+        period: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let clock = self.clone();
+        tokio::spawn(async move {
+            let mut tick = tokio::time::interval(period);
+            loop {
+                tick.tick().await;
+                if let Err(e) = clock.sync(&http, &rest_url).await {
+                    tracing::warn!(error = %e, "server time sync failed");
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corrected_now_applies_offset() {
+        let clock = ServerClock::new();
+        assert_eq!(clock.offset_ms(), 0);
+        clock.set_offset_ms(5_000);
+        let skew = clock.now_ms() - chrono::Utc::now().timestamp_millis();
+        assert!((4_900..=5_100).contains(&skew), "skew was {skew}");
+        // Clones share the offset.
+        let clone = clock.clone();
+        clone.set_offset_ms(-2_000);
+        assert_eq!(clock.offset_ms(), -2_000);
+    }
+
+    #[tokio::test]
+    async fn sync_measures_offset_against_mock() {
+        let mock = crate::testing::MockExchange::start().await.unwrap();
+        // The mock replies with a server time far in the future.
+        let server_time = chrono::Utc::now().timestamp_millis() + 30_000;
+        mock.set_response("/api/v3/time", serde_json::json!({"serverTime": server_time}));
+        let clock = ServerClock::new();
+        let offset = clock.sync(&reqwest::Client::new(), &mock.rest_url).await.unwrap();
+        assert!((29_000..=31_000).contains(&offset), "offset was {offset}");
+        assert!(clock.now_ms() > chrono::Utc::now().timestamp_millis() + 25_000);
+    }
+}
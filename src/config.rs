@@ -0,0 +1,275 @@
+//! Configuration file loading.
+//!
+//! The config is a single TOML file; every subsystem keeps its own
+//! section so new features stay additive.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::pipeline::script::ScriptConfig;
+use crate::pipeline::Pipeline;
+
+/// Top-level configuration.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    /// Root directory for file output (CSV archives, state).
+    #[serde(default = "default_data_dir")]
+    pub data_dir: String,
+
+    /// Binance-specific settings.
+    #[serde(default)]
+    pub binance: crate::exchange::binance::BinanceConfig,
+
+    /// ClickHouse connection, if the database sinks are used.
+    #[serde(default)]
+    pub clickhouse: Option<crate::db::ClickHouseConfig>,
+
+    /// Declarative pipeline definitions, e.g.
+    /// `"btcusdt@trade -> candle_builder(1m) -> [clickhouse, csv]"`.
+    #[serde(default)]
+    pub pipelines: Vec<String>,
+
+    /// Intake queue between the stream reader and the processing loop.
+    #[serde(default)]
+    pub channel: crate::queue::ChannelConfig,
+
+    /// Micro-batching between stream intake and sinks; enabled
+    /// whenever the section is present.
+    #[serde(default)]
+    pub batch: Option<crate::sink::batch::BatchConfig>,
+
+    /// Write-ahead buffer for database outages; enabled whenever the
+    /// section is present.
+    #[serde(default)]
+    pub wal: Option<crate::sink::wal::WalConfig>,
+
+    /// Where collector checkpoints are persisted.
+    #[serde(default)]
+    pub checkpoints: crate::checkpoint::CheckpointConfig,
+
+    /// Console and optional rotating-file logging.
+    #[serde(default)]
+    pub logging: crate::logging::LoggingConfig,
+
+    /// Console and file timestamp rendering.
+    #[serde(default)]
+    pub output: crate::util::OutputConfig,
+
+    /// exchangeInfo cache tuning.
+    #[serde(default)]
+    pub exchange_info: crate::exchange::info_cache::InfoCacheConfig,
+
+    /// New-listing auto-onboarding; enabled whenever the section is
+    /// present.
+    #[serde(default)]
+    pub listings: Option<crate::jobs::listings::ListingsConfig>,
+
+    /// Asset metadata enrichment; enabled whenever the section is
+    /// present.
+    #[serde(default)]
+    pub enrich: Option<crate::jobs::enrich::EnrichConfig>,
+
+    /// USD reference conversion for derived series.
+    #[serde(default)]
+    pub usd_reference: Option<crate::jobs::usd_reference::UsdReference>,
+
+    /// Admin control socket; enabled whenever the section is present.
+    #[serde(default)]
+    pub admin: Option<crate::admin::AdminConfig>,
+
+    /// Fault injection for resilience testing; armed whenever the
+    /// section is present.
+    #[serde(default)]
+    pub chaos: Option<crate::chaos::ChaosConfig>,
+
+    /// Active/standby leadership; enabled whenever the section is
+    /// present (requires ClickHouse).
+    #[serde(default)]
+    pub leadership: Option<crate::ops::leadership::LeadershipConfig>,
+
+    /// Multi-host work sharding; enabled whenever the section is
+    /// present (requires ClickHouse).
+    #[serde(default)]
+    pub sharding: Option<crate::ops::sharding::ShardingConfig>,
+
+    /// Funding schedule tracking for perp symbols.
+    #[serde(default)]
+    pub funding: Option<crate::jobs::funding::FundingConfig>,
+
+    /// Futures sentiment ratio collection.
+    #[serde(default)]
+    pub sentiment: Option<crate::jobs::sentiment::SentimentConfig>,
+
+    /// Retention policy for local daily files.
+    #[serde(default)]
+    pub retention: Option<crate::jobs::retention::RetentionConfig>,
+
+    /// Downsampling policy for aged raw data.
+    #[serde(default)]
+    pub downsample: Option<crate::jobs::downsample::DownsampleConfig>,
+
+    /// End-of-day candle finalization; enabled whenever the section is
+    /// present (requires ClickHouse).
+    #[serde(default)]
+    pub finalize_daily: Option<crate::jobs::finalize_daily::FinalizeDailyConfig>,
+
+    /// Anomaly detection on the ingest path; enabled whenever the
+    /// section is present.
+    #[serde(default)]
+    pub anomaly: Option<crate::pipeline::anomaly::AnomalyConfig>,
+
+    /// Grafana-friendly views and annotations; enabled whenever the
+    /// section is present (requires ClickHouse).
+    #[serde(default)]
+    pub grafana: Option<crate::grafana::GrafanaConfig>,
+
+    /// Per-symbol priority tiers.
+    #[serde(default, rename = "tier")]
+    pub tiers: Vec<crate::tiers::TierConfig>,
+
+    /// Large-trade tagging thresholds.
+    #[serde(default, rename = "whale")]
+    pub whale: Vec<crate::pipeline::whale::WhaleRule>,
+
+    /// Price watchlist rules evaluated against the live stream.
+    #[serde(default, rename = "watch")]
+    pub watch: Vec<crate::pipeline::watchlist::WatchRule>,
+
+    /// User scripts run in the event pipeline, in file order.
+    #[serde(default, rename = "script")]
+    pub scripts: Vec<ScriptConfig>,
+
+    /// WASM plugins run in the event pipeline, after scripts.
+    #[cfg(feature = "wasm-plugins")]
+    #[serde(default, rename = "wasm_plugin")]
+    pub wasm_plugins: Vec<crate::pipeline::wasm::WasmPluginConfig>,
+}
+
+fn default_data_dir() -> String {
+    "data".to_string()
+}
+
+impl Config {
+    /// Load and parse a TOML config file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| Error::Config(format!("{}: {e}", path.display())))?;
+        Self::parse(&text)
+    }
+
+    /// Like [`Config::load`], but a *missing* file yields the defaults.
+    /// Parse errors still fail: a malformed config must never silently
+    /// run with defaults.
+    pub fn load_or_default(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        match std::fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(Error::Config(format!("{}: {e}", path.display()))),
+        }
+    }
+
+    /// Parse config from TOML text.
+    pub fn parse(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| Error::Config(e.to_string()))
+    }
+
+    /// Resolve every credential field (`clickhouse.password`, each
+    /// `binance.api_key`'s `key`/`secret`) through [`crate::secrets`],
+    /// in place. Call once after loading, before the credentials are
+    /// used, so a config file can hold `env:`/`vault:`/... references
+    /// instead of the credential itself.
+    pub async fn resolve_secrets(&mut self, http: &reqwest::Client) -> Result<()> {
+        if let Some(ch) = &mut self.clickhouse {
+            if let Some(password) = &ch.password {
+                ch.password = Some(crate::secrets::resolve(password, http).await?);
+            }
+        }
+        for key in &mut self.binance.api_keys {
+            key.key = crate::secrets::resolve(&key.key, http).await?;
+            if let Some(secret) = &key.secret {
+                key.secret = Some(crate::secrets::resolve(secret, http).await?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the event pipeline described by this config. Scripts are
+    /// compiled eagerly so a typo fails at startup, not mid-stream.
+    pub fn build_pipeline(&self) -> Result<Pipeline> {
+        let mut pipeline = Pipeline::new();
+        if let Some(anomaly) = &self.anomaly {
+            pipeline.push(Box::new(crate::pipeline::anomaly::AnomalyDetector::new(
+                anomaly.clone(),
+            )));
+        }
+        if !self.whale.is_empty() {
+            pipeline.push(Box::new(crate::pipeline::whale::WhaleTagger::new(
+                self.whale.clone(),
+            )));
+        }
+        if !self.watch.is_empty() {
+            pipeline.push(Box::new(crate::pipeline::watchlist::WatchlistProcessor::new(
+                self.watch.clone(),
+            )?));
+        }
+        for script in &self.scripts {
+            pipeline.push(Box::new(crate::pipeline::script::ScriptProcessor::compile(script)?));
+        }
+        #[cfg(feature = "wasm-plugins")]
+        for plugin in &self.wasm_plugins {
+            pipeline.push(Box::new(crate::pipeline::wasm::WasmProcessor::load(plugin)?));
+        }
+        Ok(pipeline)
+    }
+
+    /// Parse and wire every `pipelines` entry into a runnable
+    /// [`Flow`](crate::pipeline::spec::Flow).
+    pub fn build_flows(&self, http: &reqwest::Client) -> Result<Vec<crate::pipeline::spec::Flow>> {
+        self.pipelines
+            .iter()
+            .map(|def| {
+                let spec = crate::pipeline::spec::PipelineSpec::parse(def)?;
+                crate::pipeline::spec::build_flow(self, &spec, http)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scripts_section() {
+        let cfg = Config::parse(
+            r#"
+            [[script]]
+            name = "big-trades"
+            action = "filter"
+            on = "trade"
+            code = "notional >= 100000.0"
+
+            [[script]]
+            name = "spike"
+            action = "alert"
+            code = "false"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.scripts.len(), 2);
+        assert_eq!(cfg.scripts[0].name, "big-trades");
+        let pipeline = cfg.build_pipeline().unwrap();
+        assert_eq!(pipeline.len(), 2);
+    }
+
+    #[test]
+    fn empty_config_is_valid() {
+        let cfg = Config::parse("").unwrap();
+        assert!(cfg.scripts.is_empty());
+        assert!(cfg.build_pipeline().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,229 @@
+//! Thin ClickHouse client over the HTTP interface.
+//!
+//! We deliberately speak plain HTTP (`JSONEachRow` for inserts,
+//! `JSONEachRow`/`TabSeparated` for reads) instead of pulling in a
+//! native-protocol driver: the collector's query surface is tiny and
+//! the HTTP interface is stable across ClickHouse versions.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// Connection settings for one ClickHouse endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClickHouseConfig {
+    /// HTTP endpoint, e.g. `http://localhost:8123`.
+    #[serde(default = "default_url")]
+    pub url: String,
+    /// Target database.
+    #[serde(default = "default_database")]
+    pub database: String,
+    /// User name; `default` if unset.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Password, if the server requires one.
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Also keep klines bi-temporally (`klines_bitemporal` plus the
+    /// `klines_latest`/`klines_original` views).
+    #[serde(default)]
+    pub bitemporal: bool,
+    /// Additional clusters every batch is also written to, each with
+    /// independent retry/WAL state.
+    #[serde(default)]
+    pub replicas: Vec<ClickHouseReplica>,
+}
+
+/// Connection settings for one replica cluster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClickHouseReplica {
+    /// HTTP endpoint of the replica.
+    pub url: String,
+    /// Target database; defaults to the primary's.
+    #[serde(default)]
+    pub database: Option<String>,
+    /// User name.
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Password.
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl ClickHouseConfig {
+    /// Materialise the replica list as full configs.
+    pub fn replica_configs(&self) -> Vec<ClickHouseConfig> {
+        self.replicas
+            .iter()
+            .map(|r| ClickHouseConfig {
+                url: r.url.clone(),
+                database: r.database.clone().unwrap_or_else(|| self.database.clone()),
+                user: r.user.clone(),
+                password: r.password.clone(),
+                bitemporal: self.bitemporal,
+                replicas: Vec::new(),
+            })
+            .collect()
+    }
+}
+
+fn default_url() -> String {
+    "http://localhost:8123".to_string()
+}
+
+fn default_database() -> String {
+    "default".to_string()
+}
+
+impl Default for ClickHouseConfig {
+    fn default() -> Self {
+        Self {
+            url: default_url(),
+            database: default_database(),
+            user: None,
+            password: None,
+            bitemporal: false,
+            replicas: Vec::new(),
+        }
+    }
+}
+
+/// Serialize epoch-millisecond timestamps as decimal unix seconds,
+/// the JSON input form ClickHouse accepts for `DateTime64(3)` columns.
+pub mod dt64 {
+    use serde::Serializer;
+
+    /// Serialize `ms` as fractional seconds.
+    pub fn serialize<S: Serializer>(ms: &i64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(*ms as f64 / 1000.0)
+    }
+}
+
+/// SQL literal converting epoch milliseconds to `DateTime64(3)`.
+pub fn dt64_literal(ms: i64) -> String {
+    format!("fromUnixTimestamp64Milli({ms})")
+}
+
+/// A ClickHouse connection handle. Cheap to clone; the underlying HTTP
+/// client pools connections.
+#[derive(Debug, Clone)]
+pub struct ClickHouse {
+    config: ClickHouseConfig,
+    http: reqwest::Client,
+}
+
+impl ClickHouse {
+    /// Build a client from config, reusing the given HTTP client.
+    pub fn new(config: ClickHouseConfig, http: reqwest::Client) -> Self {
+        Self { config, http }
+    }
+
+    /// The configured database name.
+    pub fn database(&self) -> &str {
+        &self.config.database
+    }
+
+    /// Whether bi-temporal kline storage is enabled.
+    pub fn bitemporal(&self) -> bool {
+        self.config.bitemporal
+    }
+
+    fn request(&self, sql: &str) -> reqwest::RequestBuilder {
+        let mut req = self
+            .http
+            .post(&self.config.url)
+            .query(&[("database", self.config.database.as_str()), ("query", sql)]);
+        if let Some(user) = &self.config.user {
+            req = req.basic_auth(user, self.config.password.as_deref());
+        }
+        req
+    }
+
+    async fn send(&self, sql: &str, body: Option<Vec<u8>>) -> Result<String> {
+        let req = match body {
+            Some(body) => self.request(sql).body(body),
+            None => self.request(sql),
+        };
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| Error::Database(format!("clickhouse request failed: {e}")))?;
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .map_err(|e| Error::Database(format!("clickhouse response read failed: {e}")))?;
+        if !status.is_success() {
+            return Err(Error::Database(format!(
+                "clickhouse returned {status}: {}",
+                text.trim()
+            )));
+        }
+        Ok(text)
+    }
+
+    /// Run a statement that returns no rows (DDL, `INSERT ... SELECT`).
+    pub async fn execute(&self, sql: &str) -> Result<()> {
+        self.send(sql, None).await.map(|_| ())
+    }
+
+    /// Insert rows into `table` via `JSONEachRow`.
+    pub async fn insert_rows<T: Serialize>(&self, table: &str, rows: &[T]) -> Result<()> {
+        self.insert_rows_dedup(table, rows, None).await
+    }
+
+    /// Insert rows with an optional deterministic deduplication token,
+    /// so replayed batches (retries, WAL recovery) never duplicate even
+    /// on non-Replacing engines.
+    pub async fn insert_rows_dedup<T: Serialize>(
+        &self,
+        table: &str,
+        rows: &[T],
+        dedup_token: Option<&str>,
+    ) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut body = Vec::with_capacity(rows.len() * 128);
+        for row in rows {
+            serde_json::to_writer(&mut body, row)?;
+            body.push(b'\n');
+        }
+        let sql = format!("INSERT INTO {table} FORMAT JSONEachRow");
+        let mut req = self.request(&sql).body(body);
+        if let Some(token) = dedup_token {
+            req = req.query(&[
+                ("insert_deduplicate", "1"),
+                ("insert_deduplication_token", token),
+            ]);
+        }
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| Error::Database(format!("clickhouse request failed: {e}")))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(Error::Database(format!(
+                "clickhouse returned {status}: {}",
+                text.trim()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run a query returning `JSONEachRow` rows deserialized into `T`.
+    pub async fn query_rows<T: for<'de> Deserialize<'de>>(&self, sql: &str) -> Result<Vec<T>> {
+        let text = self.send(&format!("{sql} FORMAT JSONEachRow"), None).await?;
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l).map_err(Error::from))
+            .collect()
+    }
+
+    /// Run a query returning a single scalar rendered as text.
+    pub async fn query_scalar(&self, sql: &str) -> Result<String> {
+        let text = self.send(&format!("{sql} FORMAT TabSeparated"), None).await?;
+        Ok(text.trim_end_matches('\n').to_string())
+    }
+}
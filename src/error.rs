@@ -0,0 +1,47 @@
+//! Crate-wide error type.
+
+use thiserror::Error;
+
+/// Errors produced by collection, processing and persistence.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Configuration file could not be read or parsed.
+    #[error("config error: {0}")]
+    Config(String),
+
+    /// A user script failed to compile or raised at runtime.
+    #[error("script `{name}`: {message}")]
+    Script {
+        /// Name of the script as given in the config.
+        name: String,
+        /// Compile or runtime error text.
+        message: String,
+    },
+
+    /// An exchange API returned something unexpected.
+    #[error("exchange error: {0}")]
+    Exchange(String),
+
+    /// ClickHouse request or server-side failure.
+    #[error("database error: {0}")]
+    Database(String),
+
+    /// A pipeline definition could not be parsed or wired up.
+    #[error("pipeline error: {0}")]
+    Pipeline(String),
+
+    /// A secret reference could not be resolved to its credential.
+    #[error("secret error: {0}")]
+    Secret(String),
+
+    /// Underlying I/O failure.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// JSON (de)serialization failure.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// Convenience alias used throughout the crate.
+pub type Result<T> = std::result::Result<T, Error>;
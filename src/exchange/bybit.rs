@@ -0,0 +1,263 @@
+//! Bybit v5: REST klines and public trade WebSocket, for spot and
+//! linear perps. Bybit shares Binance's concatenated symbol naming, so
+//! only intervals and payload shapes need mapping.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::Exchange;
+use crate::error::{Error, Result};
+use crate::model::{interval_ms, Kline, Trade};
+use crate::pipeline::spec::StreamSource;
+use crate::pipeline::Event;
+
+const REST_URL: &str = "https://api.bybit.com";
+
+/// Which Bybit v5 category to collect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BybitMarket {
+    /// Spot pairs.
+    #[default]
+    Spot,
+    /// USDT-margined linear perps.
+    Linear,
+}
+
+impl BybitMarket {
+    fn category(self) -> &'static str {
+        match self {
+            BybitMarket::Spot => "spot",
+            BybitMarket::Linear => "linear",
+        }
+    }
+}
+
+/// Bybit venue handle.
+#[derive(Debug, Clone, Default)]
+pub struct Bybit {
+    market: BybitMarket,
+}
+
+impl Bybit {
+    /// Create a handle for the given market.
+    pub fn new(market: BybitMarket) -> Self {
+        Self { market }
+    }
+
+    /// Map the crate's interval strings onto Bybit's numeric minutes
+    /// (plus `D`/`W`/`M`).
+    fn rest_interval(interval: &str) -> Result<String> {
+        Ok(match interval {
+            "1d" => "D".to_string(),
+            "1w" => "W".to_string(),
+            "1M" => "M".to_string(),
+            other => {
+                let ms = interval_ms(other)
+                    .ok_or_else(|| Error::Exchange(format!("unknown interval `{other}`")))?;
+                (ms / 60_000).to_string()
+            }
+        })
+    }
+
+    fn kline_from_row(symbol: &str, interval: &str, row: &Value) -> Result<Kline> {
+        let num = |i: usize| -> Result<f64> {
+            row.get(i)
+                .and_then(Value::as_str)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| Error::Exchange(format!("bad kline field {i} in {row}")))
+        };
+        let open_time = num(0)? as i64;
+        let close_time = interval_ms(interval)
+            .map(|ms| open_time + ms - 1)
+            .unwrap_or(open_time);
+        Ok(Kline {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            open_time,
+            close_time,
+            open: num(1)?,
+            high: num(2)?,
+            low: num(3)?,
+            close: num(4)?,
+            volume: num(5)?,
+            quote_volume: num(6)?,
+            trade_count: 0,
+        })
+    }
+
+    fn trade_from_entry(entry: &Value) -> Result<Trade> {
+        let str_field = |key: &str| -> Result<&str> {
+            entry
+                .get(key)
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::Exchange(format!("bad trade field `{key}`: {entry}")))
+        };
+        let num = |key: &str| -> Result<f64> {
+            str_field(key)?
+                .parse()
+                .map_err(|_| Error::Exchange(format!("bad trade field `{key}`: {entry}")))
+        };
+        Ok(Trade {
+            symbol: str_field("s")?.to_string(),
+            // Spot trade ids are UUIDs; keep the numeric ones and fall
+            // back to 0 rather than failing the stream.
+            trade_id: str_field("i")?.parse().unwrap_or(0),
+            price: num("p")?,
+            qty: num("v")?,
+            trade_time: entry.get("T").and_then(Value::as_i64).unwrap_or(0),
+            // `S` is the taker side; a taker sell leaves the buyer as
+            // the maker.
+            is_buyer_maker: str_field("S")? == "Sell",
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for Bybit {
+    fn name(&self) -> &'static str {
+        "bybit"
+    }
+
+    fn instrument(&self, symbol: &str) -> String {
+        symbol.to_uppercase()
+    }
+
+    fn unified_symbol(&self, instrument: &str) -> String {
+        instrument.to_uppercase()
+    }
+
+    async fn fetch_klines(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        interval: &str,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Kline>> {
+        let mut query: Vec<(&str, String)> = vec![
+            ("category", self.market.category().to_string()),
+            ("symbol", self.instrument(symbol)),
+            ("interval", Self::rest_interval(interval)?),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(start) = start_ms {
+            query.push(("start", start.to_string()));
+        }
+        if let Some(end) = end_ms {
+            query.push(("end", end.to_string()));
+        }
+        let body: Value = http
+            .get(format!("{REST_URL}/v5/market/kline"))
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("kline request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("kline response invalid: {e}")))?;
+        if body.get("retCode").and_then(Value::as_i64) != Some(0) {
+            return Err(Error::Exchange(format!("bybit error response: {body}")));
+        }
+        let rows = body
+            .pointer("/result/list")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Exchange(format!("bybit response without list: {body}")))?;
+        // Rows arrive newest first; the trait contract is oldest first.
+        let mut klines = rows
+            .iter()
+            .map(|row| Self::kline_from_row(symbol, interval, row))
+            .collect::<Result<Vec<_>>>()?;
+        klines.reverse();
+        Ok(klines)
+    }
+
+    fn ws_url(&self) -> String {
+        format!(
+            "wss://stream.bybit.com/v5/public/{}",
+            self.market.category()
+        )
+    }
+
+    fn ws_subscribe(&self, sources: &[StreamSource]) -> Vec<String> {
+        let args: Vec<String> = sources
+            .iter()
+            .map(|s| {
+                let topic = match s.stream.as_str() {
+                    "trade" => "publicTrade",
+                    other => other,
+                };
+                format!("{topic}.{}", self.instrument(&s.symbol))
+            })
+            .collect();
+        vec![serde_json::json!({"op": "subscribe", "args": args}).to_string()]
+    }
+
+    fn parse_ws_message(&self, text: &str) -> Result<Vec<Event>> {
+        let value: Value = serde_json::from_str(text)?;
+        let topic = value.get("topic").and_then(Value::as_str).unwrap_or("");
+        if !topic.starts_with("publicTrade.") {
+            // Acks, pongs and other topics.
+            return Ok(Vec::new());
+        }
+        let entries = match value.get("data").and_then(Value::as_array) {
+            Some(entries) => entries,
+            None => return Ok(Vec::new()),
+        };
+        entries
+            .iter()
+            .map(|entry| Ok(Event::Trade(Self::trade_from_entry(entry)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_intervals() {
+        assert_eq!(Bybit::rest_interval("1m").unwrap(), "1");
+        assert_eq!(Bybit::rest_interval("1h").unwrap(), "60");
+        assert_eq!(Bybit::rest_interval("1d").unwrap(), "D");
+        assert!(Bybit::rest_interval("7q").is_err());
+    }
+
+    #[test]
+    fn parses_kline_row() {
+        let row = serde_json::json!([
+            "1670608800000", "17071", "17073", "17027", "17055.5", "268611", "4581759.6"
+        ]);
+        let k = Bybit::kline_from_row("BTCUSDT", "1h", &row).unwrap();
+        assert_eq!(k.open_time, 1_670_608_800_000);
+        assert_eq!(k.close_time, 1_670_612_399_999);
+        assert_eq!(k.quote_volume, 4_581_759.6);
+    }
+
+    #[test]
+    fn parses_trade_frame() {
+        let frame = r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1672304486868,"data":[{"T":1672304486865,"s":"BTCUSDT","S":"Sell","v":"0.001","p":"16578.50","i":"20f43950-d8dd-5b31-9112-a178eb6023af","BT":false}]}"#;
+        let events = Bybit::default().parse_ws_message(frame).unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Trade(t) => {
+                assert_eq!(t.symbol, "BTCUSDT");
+                assert_eq!(t.price, 16_578.50);
+                assert_eq!(t.trade_time, 1_672_304_486_865);
+                assert!(t.is_buyer_maker);
+            }
+            other => panic!("expected trade, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn subscribe_topic_and_url_follow_market() {
+        let linear = Bybit::new(BybitMarket::Linear);
+        assert!(linear.ws_url().ends_with("/linear"));
+        let payload = &linear.ws_subscribe(&[StreamSource {
+            symbol: "btcusdt".into(),
+            stream: "trade".into(),
+        }])[0];
+        assert!(payload.contains("publicTrade.BTCUSDT"));
+    }
+}
@@ -0,0 +1,231 @@
+//! Cached `exchangeInfo` with periodic refresh.
+//!
+//! Symbol expansion, validation and precision logic all need the
+//! symbol universe, but none of them should hit the endpoint on every
+//! use. The cache keeps one copy in memory (refreshed after the TTL)
+//! and mirrors it to disk, so a restart — or an API outage — can fall
+//! back to the last known universe.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use super::symbols::{fetch_exchange_info, SymbolInfo};
+use crate::error::{Error, Result};
+
+/// The `[exchange_info]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InfoCacheConfig {
+    /// Seconds before the in-memory copy is considered stale.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_ttl_secs() -> u64 {
+    3_600
+}
+
+impl Default for InfoCacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_secs: default_ttl_secs(),
+        }
+    }
+}
+
+/// On-disk snapshot shape.
+#[derive(Serialize, Deserialize)]
+struct DiskSnapshot {
+    fetched_at: i64,
+    symbols: Vec<SymbolInfo>,
+}
+
+struct CacheState {
+    fetched_at: i64,
+    symbols: Arc<Vec<SymbolInfo>>,
+}
+
+/// A process-wide exchangeInfo cache. Cheap to clone.
+#[derive(Clone)]
+pub struct ExchangeInfoCache {
+    rest_url: String,
+    http: reqwest::Client,
+    ttl_ms: i64,
+    disk_path: PathBuf,
+    state: Arc<RwLock<Option<CacheState>>>,
+}
+
+impl ExchangeInfoCache {
+    /// Create a cache for one REST host, mirrored to
+    /// `<data_dir>/exchange_info.json`.
+    pub fn new(
+        rest_url: &str,
+        http: reqwest::Client,
+        data_dir: &str,
+        cfg: &InfoCacheConfig,
+    ) -> Self {
+        Self {
+            rest_url: rest_url.to_string(),
+            http,
+            ttl_ms: (cfg.ttl_secs as i64) * 1000,
+            disk_path: PathBuf::from(data_dir).join("exchange_info.json"),
+            state: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// The symbol universe: memory if fresh, else refetched, else the
+    /// stale disk copy (with a warning) when the endpoint is down.
+    pub async fn get(&self) -> Result<Arc<Vec<SymbolInfo>>> {
+        let now = chrono::Utc::now().timestamp_millis();
+        if let Some(state) = self.state.read().await.as_ref() {
+            if now - state.fetched_at < self.ttl_ms {
+                return Ok(state.symbols.clone());
+            }
+        }
+        match fetch_exchange_info(&self.http, &self.rest_url).await {
+            Ok(symbols) => Ok(self.store(symbols, now).await),
+            Err(e) => {
+                // Stale beats nothing: fall back to memory, then disk.
+                if let Some(state) = self.state.read().await.as_ref() {
+                    tracing::warn!(error = %e, "exchangeInfo refresh failed; using stale copy");
+                    return Ok(state.symbols.clone());
+                }
+                if let Some(snapshot) = self.load_disk() {
+                    tracing::warn!(error = %e, "exchangeInfo unavailable; using disk snapshot");
+                    return Ok(self.prime(snapshot.symbols, snapshot.fetched_at).await);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Insert a universe directly (startup from disk, tests).
+    pub async fn prime(&self, symbols: Vec<SymbolInfo>, fetched_at: i64) -> Arc<Vec<SymbolInfo>> {
+        let symbols = Arc::new(symbols);
+        *self.state.write().await = Some(CacheState {
+            fetched_at,
+            symbols: symbols.clone(),
+        });
+        symbols
+    }
+
+    async fn store(&self, symbols: Vec<SymbolInfo>, now: i64) -> Arc<Vec<SymbolInfo>> {
+        let snapshot = DiskSnapshot {
+            fetched_at: now,
+            symbols,
+        };
+        if let Some(dir) = self.disk_path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        if let Ok(body) = serde_json::to_vec(&snapshot) {
+            let tmp = self.disk_path.with_extension("json.tmp");
+            if std::fs::write(&tmp, body).is_ok() {
+                let _ = std::fs::rename(&tmp, &self.disk_path);
+            }
+        }
+        self.prime(snapshot.symbols, now).await
+    }
+
+    fn load_disk(&self) -> Option<DiskSnapshot> {
+        let text = std::fs::read_to_string(&self.disk_path).ok()?;
+        serde_json::from_str(&text).ok()
+    }
+
+    /// Age of the cached copy in milliseconds, if any.
+    pub async fn age_ms(&self, now: i64) -> Option<i64> {
+        self.state
+            .read()
+            .await
+            .as_ref()
+            .map(|s| now - s.fetched_at)
+    }
+}
+
+impl std::fmt::Debug for ExchangeInfoCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExchangeInfoCache")
+            .field("rest_url", &self.rest_url)
+            .field("ttl_ms", &self.ttl_ms)
+            .finish()
+    }
+}
+
+/// Convenience: Err when a universe is required and nothing cached.
+pub fn no_universe_error() -> Error {
+    Error::Exchange("exchangeInfo unavailable and no cached copy exists".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(symbol: &str) -> SymbolInfo {
+        SymbolInfo {
+            symbol: symbol.to_string(),
+            status: "TRADING".to_string(),
+            base_asset: String::new(),
+            quote_asset: String::new(),
+            permissions: Vec::new(),
+            tick_size: None,
+            step_size: None,
+            min_notional: None,
+        }
+    }
+
+    fn cache(dir: &str, ttl_secs: u64) -> ExchangeInfoCache {
+        ExchangeInfoCache::new(
+            // Unroutable: forces the fallback paths in tests.
+            "http://127.0.0.1:1",
+            reqwest::Client::new(),
+            dir,
+            &InfoCacheConfig { ttl_secs },
+        )
+    }
+
+    #[tokio::test]
+    async fn fresh_memory_copy_is_served_without_fetching() {
+        let dir = std::env::temp_dir().join(format!("bzl-infocache-{}", std::process::id()));
+        let cache = cache(dir.to_str().unwrap(), 3_600);
+        let now = chrono::Utc::now().timestamp_millis();
+        cache.prime(vec![info("BTCUSDT")], now).await;
+        let got = cache.get().await.unwrap();
+        assert_eq!(got.len(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn stale_memory_falls_back_when_endpoint_down() {
+        let dir = std::env::temp_dir().join(format!("bzl-infocache2-{}", std::process::id()));
+        let cache = cache(dir.to_str().unwrap(), 0);
+        cache.prime(vec![info("BTCUSDT")], 0).await;
+        // TTL of zero makes the copy stale; the refetch fails, so the
+        // stale copy is served.
+        let got = cache.get().await.unwrap();
+        assert_eq!(got[0].symbol, "BTCUSDT");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn disk_snapshot_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("bzl-infocache3-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let first = cache(dir.to_str().unwrap(), 3_600);
+        first.store(vec![info("ETHUSDT")], 42).await;
+        // A fresh cache instance (new process) with the endpoint down
+        // recovers the universe from disk.
+        let second = cache(dir.to_str().unwrap(), 3_600);
+        let got = second.get().await.unwrap();
+        assert_eq!(got[0].symbol, "ETHUSDT");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn nothing_cached_surfaces_the_error() {
+        let dir = std::env::temp_dir().join(format!("bzl-infocache4-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let cache = cache(dir.to_str().unwrap(), 3_600);
+        assert!(cache.get().await.is_err());
+    }
+}
@@ -0,0 +1,159 @@
+//! Rotation and weight tracking for multiple exchange API keys.
+//!
+//! Binance grants a per-key request-weight budget; a collector backfilling
+//! many symbols at once can burn through a single key's budget and start
+//! drawing 429s (or a temporary 418 ban) well before it needs to. Spreading
+//! calls round-robin across several configured keys, and steering away from
+//! whichever one is closest to its limit or currently banned, keeps the
+//! collector running on the healthy keys instead of stalling on the first
+//! exhausted one.
+
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// One configured API key (`[[binance.api_keys]]` in the config file).
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApiKeyConfig {
+    pub key: String,
+    /// Only needed for signed (account/trading) endpoints.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Requests-per-minute weight budget Binance grants this key.
+    #[serde(default = "default_weight_limit")]
+    pub weight_limit: i64,
+}
+
+fn default_weight_limit() -> i64 {
+    1_200
+}
+
+/// Fraction of `weight_limit` at which a key is set aside in favor of a
+/// less-loaded one, leaving headroom before Binance itself would reject it.
+const WEIGHT_HEADROOM: f64 = 0.9;
+
+#[derive(Debug)]
+struct KeyState {
+    cfg: ApiKeyConfig,
+    // Last `X-MBX-USED-WEIGHT-1M` reported for this key; Binance reports
+    // the window total directly, so this is a snapshot, not an accumulator.
+    used_weight: AtomicI64,
+    // Epoch ms until which this key is skipped after a 418/429; 0 means
+    // not banned.
+    banned_until: AtomicI64,
+}
+
+/// Round-robins across configured keys, skipping any that are banned or
+/// within [`WEIGHT_HEADROOM`] of their weight budget.
+#[derive(Debug, Clone)]
+pub struct KeyPool {
+    keys: Arc<Vec<KeyState>>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl KeyPool {
+    pub fn new(keys: Vec<ApiKeyConfig>) -> Self {
+        assert!(!keys.is_empty(), "a key pool needs at least one key");
+        Self {
+            keys: Arc::new(
+                keys.into_iter()
+                    .map(|cfg| KeyState {
+                        cfg,
+                        used_weight: AtomicI64::new(0),
+                        banned_until: AtomicI64::new(0),
+                    })
+                    .collect(),
+            ),
+            cursor: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Pick the next usable key, round-robin, skipping any currently
+    /// banned or near its weight budget. `None` only when every key is
+    /// unusable right now.
+    pub fn select(&self, now_ms: i64) -> Option<&str> {
+        let start = self.cursor.fetch_add(1, Ordering::Relaxed);
+        (0..self.keys.len())
+            .map(|i| &self.keys[(start + i) % self.keys.len()])
+            .find(|state| {
+                state.banned_until.load(Ordering::Relaxed) <= now_ms
+                    && (state.used_weight.load(Ordering::Relaxed) as f64)
+                        < state.cfg.weight_limit as f64 * WEIGHT_HEADROOM
+            })
+            .map(|state| state.cfg.key.as_str())
+    }
+
+    /// Record the weight Binance reported as used for `key` in the
+    /// current one-minute window.
+    pub fn record_weight(&self, key: &str, used_weight: i64) {
+        if let Some(state) = self.keys.iter().find(|s| s.cfg.key == key) {
+            state.used_weight.store(used_weight, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of configured keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Always `false`: [`KeyPool::new`] rejects an empty key list.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Mark `key` unusable until `now_ms + retry_after_secs * 1000`,
+    /// after a 429 (rate limited) or 418 (temporarily banned) response.
+    pub fn record_ban(&self, key: &str, retry_after_secs: i64, now_ms: i64) {
+        if let Some(state) = self.keys.iter().find(|s| s.cfg.key == key) {
+            state
+                .banned_until
+                .store(now_ms + retry_after_secs.max(0) * 1_000, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(name: &str) -> ApiKeyConfig {
+        ApiKeyConfig {
+            key: name.to_string(),
+            secret: None,
+            weight_limit: 1_200,
+        }
+    }
+
+    #[test]
+    fn round_robins_across_healthy_keys() {
+        let pool = KeyPool::new(vec![key("a"), key("b")]);
+        let first = pool.select(0).unwrap().to_string();
+        let second = pool.select(0).unwrap().to_string();
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn skips_a_key_near_its_weight_budget() {
+        let pool = KeyPool::new(vec![key("a"), key("b")]);
+        pool.record_weight("a", 1_150);
+        for _ in 0..4 {
+            assert_eq!(pool.select(0), Some("b"));
+        }
+    }
+
+    #[test]
+    fn skips_a_banned_key_until_it_expires() {
+        let pool = KeyPool::new(vec![key("a"), key("b")]);
+        pool.record_ban("a", 60, 1_000);
+        for _ in 0..4 {
+            assert_eq!(pool.select(1_000), Some("b"));
+        }
+        assert_eq!(pool.select(61_001), Some("a"));
+    }
+
+    #[test]
+    fn no_usable_key_returns_none() {
+        let pool = KeyPool::new(vec![key("a")]);
+        pool.record_ban("a", 60, 0);
+        assert_eq!(pool.select(0), None);
+    }
+}
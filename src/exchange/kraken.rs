@@ -0,0 +1,283 @@
+//! Kraken: REST OHLC and the v1 trade WebSocket.
+//!
+//! Kraken is the odd one out on naming: BTC is `XBT`, WebSocket pairs
+//! are slash-separated (`XBT/USD`), and REST responses key results by
+//! the "classic" form with asset-class prefixes (`XXBTZUSD`). All three
+//! are mapped onto the unified concatenated symbol here.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{split_symbol, Exchange};
+use crate::error::{Error, Result};
+use crate::model::{interval_ms, Kline, Trade};
+use crate::pipeline::spec::StreamSource;
+use crate::pipeline::Event;
+
+const REST_URL: &str = "https://api.kraken.com";
+const WS_URL: &str = "wss://ws.kraken.com";
+
+/// Kraken venue handle.
+#[derive(Debug, Clone, Default)]
+pub struct Kraken;
+
+impl Kraken {
+    /// Kraken's asset code for a unified one.
+    fn asset(code: &str) -> &str {
+        match code {
+            "BTC" => "XBT",
+            other => other,
+        }
+    }
+
+    /// Unified asset code for a Kraken one, with the single-letter
+    /// class prefix (`X`/`Z`) stripped from classic four-letter codes.
+    fn unified_asset(code: &str) -> &str {
+        let code = match code {
+            c if c.len() == 4 && (c.starts_with('X') || c.starts_with('Z')) => &c[1..],
+            c => c,
+        };
+        match code {
+            "XBT" => "BTC",
+            other => other,
+        }
+    }
+
+    /// Slash-separated pair for the WebSocket API.
+    fn ws_pair(&self, symbol: &str) -> String {
+        match split_symbol(&symbol.to_uppercase()) {
+            Some((base, quote)) => format!("{}/{}", Self::asset(base), Self::asset(quote)),
+            None => symbol.to_uppercase(),
+        }
+    }
+
+    fn kline_from_row(symbol: &str, interval: &str, row: &Value) -> Result<Kline> {
+        let num = |i: usize| -> Result<f64> {
+            let v = row
+                .get(i)
+                .ok_or_else(|| Error::Exchange(format!("OHLC row too short: {row}")))?;
+            v.as_str()
+                .and_then(|s| s.parse().ok())
+                .or_else(|| v.as_f64())
+                .ok_or_else(|| Error::Exchange(format!("bad OHLC field {i} in {row}")))
+        };
+        let open_time = (num(0)? as i64) * 1000;
+        let close_time = interval_ms(interval)
+            .map(|ms| open_time + ms - 1)
+            .unwrap_or(open_time);
+        let vwap = num(5)?;
+        let volume = num(6)?;
+        Ok(Kline {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            open_time,
+            close_time,
+            open: num(1)?,
+            high: num(2)?,
+            low: num(3)?,
+            close: num(4)?,
+            volume,
+            // Kraken reports VWAP instead of turnover; the product is
+            // the closest equivalent.
+            quote_volume: vwap * volume,
+            trade_count: row.get(7).and_then(Value::as_i64).unwrap_or(0),
+        })
+    }
+
+    fn trade_from_row(symbol: &str, row: &Value) -> Result<Trade> {
+        let str_field = |i: usize| -> Result<&str> {
+            row.get(i)
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::Exchange(format!("bad trade field {i}: {row}")))
+        };
+        let price: f64 = str_field(0)?
+            .parse()
+            .map_err(|_| Error::Exchange(format!("bad trade price: {row}")))?;
+        let qty: f64 = str_field(1)?
+            .parse()
+            .map_err(|_| Error::Exchange(format!("bad trade volume: {row}")))?;
+        let time_s: f64 = str_field(2)?
+            .parse()
+            .map_err(|_| Error::Exchange(format!("bad trade time: {row}")))?;
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            // Kraken assigns no public trade ids.
+            trade_id: 0,
+            price,
+            qty,
+            trade_time: (time_s * 1000.0) as i64,
+            // `s` marks a taker sell, leaving the buyer as maker.
+            is_buyer_maker: str_field(3)? == "s",
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for Kraken {
+    fn name(&self) -> &'static str {
+        "kraken"
+    }
+
+    fn instrument(&self, symbol: &str) -> String {
+        match split_symbol(&symbol.to_uppercase()) {
+            Some((base, quote)) => format!("{}{}", Self::asset(base), Self::asset(quote)),
+            None => symbol.to_uppercase(),
+        }
+    }
+
+    fn unified_symbol(&self, instrument: &str) -> String {
+        if let Some((base, quote)) = instrument.split_once('/') {
+            return format!("{}{}", Self::unified_asset(base), Self::unified_asset(quote));
+        }
+        // Classic REST keys: class-prefixed four-letter codes back to
+        // back, e.g. `XXBTZUSD`.
+        if instrument.len() == 8 && instrument.starts_with('X') {
+            let (base, quote) = instrument.split_at(4);
+            return format!("{}{}", Self::unified_asset(base), Self::unified_asset(quote));
+        }
+        let upper = instrument.to_uppercase();
+        match upper.strip_prefix("XBT") {
+            Some(quote) => format!("BTC{quote}"),
+            None => upper,
+        }
+    }
+
+    async fn fetch_klines(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        interval: &str,
+        start_ms: Option<i64>,
+        _end_ms: Option<i64>,
+        _limit: usize,
+    ) -> Result<Vec<Kline>> {
+        let minutes = interval_ms(interval)
+            .ok_or_else(|| Error::Exchange(format!("unknown interval `{interval}`")))?
+            / 60_000;
+        let mut query: Vec<(&str, String)> = vec![
+            ("pair", self.instrument(symbol)),
+            ("interval", minutes.to_string()),
+        ];
+        // Kraken pages forward from `since` (seconds) and ignores any
+        // end bound; callers trim the tail themselves.
+        if let Some(start) = start_ms {
+            query.push(("since", (start / 1000).to_string()));
+        }
+        let body: Value = http
+            .get(format!("{REST_URL}/0/public/OHLC"))
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("OHLC request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("OHLC response invalid: {e}")))?;
+        if let Some(errors) = body.get("error").and_then(Value::as_array) {
+            if !errors.is_empty() {
+                return Err(Error::Exchange(format!("kraken error response: {errors:?}")));
+            }
+        }
+        let result = body
+            .get("result")
+            .and_then(Value::as_object)
+            .ok_or_else(|| Error::Exchange(format!("kraken response without result: {body}")))?;
+        let rows = result
+            .iter()
+            .find(|(key, _)| *key != "last")
+            .and_then(|(_, v)| v.as_array())
+            .ok_or_else(|| Error::Exchange("kraken response without pair rows".to_string()))?;
+        rows.iter()
+            .map(|row| Self::kline_from_row(symbol, interval, row))
+            .collect()
+    }
+
+    fn ws_url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    fn ws_subscribe(&self, sources: &[StreamSource]) -> Vec<String> {
+        let pairs: Vec<String> = sources.iter().map(|s| self.ws_pair(&s.symbol)).collect();
+        vec![serde_json::json!({
+            "event": "subscribe",
+            "pair": pairs,
+            "subscription": {"name": "trade"},
+        })
+        .to_string()]
+    }
+
+    fn parse_ws_message(&self, text: &str) -> Result<Vec<Event>> {
+        let value: Value = serde_json::from_str(text)?;
+        // Data frames are arrays: [channelID, [...rows], "trade", "XBT/USD"].
+        let frame = match value.as_array() {
+            Some(frame) if frame.len() >= 4 => frame,
+            // Objects are events (heartbeat, subscriptionStatus, ...).
+            _ => return Ok(Vec::new()),
+        };
+        if frame[frame.len() - 2].as_str() != Some("trade") {
+            return Ok(Vec::new());
+        }
+        let symbol = self.unified_symbol(
+            frame[frame.len() - 1]
+                .as_str()
+                .ok_or_else(|| Error::Exchange(format!("trade frame without pair: {text}")))?,
+        );
+        let rows = frame[1]
+            .as_array()
+            .ok_or_else(|| Error::Exchange(format!("trade frame without rows: {text}")))?;
+        rows.iter()
+            .map(|row| Ok(Event::Trade(Self::trade_from_row(&symbol, row)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_pair_naming() {
+        let k = Kraken;
+        assert_eq!(k.instrument("BTCUSD"), "XBTUSD");
+        assert_eq!(k.instrument("ETHUSDT"), "ETHUSDT");
+        assert_eq!(k.ws_pair("btcusd"), "XBT/USD");
+        assert_eq!(k.unified_symbol("XBT/USD"), "BTCUSD");
+        assert_eq!(k.unified_symbol("XXBTZUSD"), "BTCUSD");
+        assert_eq!(k.unified_symbol("XBTUSDT"), "BTCUSDT");
+    }
+
+    #[test]
+    fn parses_ohlc_row() {
+        let row = serde_json::json!([
+            1688671200, "30306.1", "30306.2", "30305.7", "30305.7", "30306.0", "3.39", 23
+        ]);
+        let k = Kraken::kline_from_row("BTCUSD", "1m", &row).unwrap();
+        assert_eq!(k.open_time, 1_688_671_200_000);
+        assert_eq!(k.close_time, 1_688_671_259_999);
+        assert_eq!(k.trade_count, 23);
+        assert!((k.quote_volume - 30_306.0 * 3.39).abs() < 1e-6);
+    }
+
+    #[test]
+    fn parses_trade_frame() {
+        let frame = r#"[337,[["5541.20000","0.15850568","1534614057.321597","s","l",""]],"trade","XBT/USD"]"#;
+        let events = Kraken.parse_ws_message(frame).unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Trade(t) => {
+                assert_eq!(t.symbol, "BTCUSD");
+                assert_eq!(t.price, 5_541.2);
+                assert_eq!(t.trade_time, 1_534_614_057_321);
+                assert!(t.is_buyer_maker);
+            }
+            other => panic!("expected trade, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn heartbeat_yields_nothing() {
+        assert!(Kraken
+            .parse_ws_message(r#"{"event":"heartbeat"}"#)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+//! Exchange abstraction.
+//!
+//! Every venue implements [`Exchange`]: translate the crate's unified
+//! symbol (`BTCUSDT`) to the venue's instrument naming, fetch historical
+//! candles over REST, and parse public WebSocket frames into pipeline
+//! [`Event`]s. Transport (connection management, reconnects) lives with
+//! the collectors, not here, so each implementation stays testable from
+//! recorded payloads.
+
+pub mod binance;
+pub mod bybit;
+pub mod info_cache;
+pub mod keypool;
+pub mod kraken;
+pub mod okx;
+pub mod symbols;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::model::Kline;
+use crate::pipeline::spec::StreamSource;
+use crate::pipeline::Event;
+
+/// A venue the crate can collect from.
+#[async_trait]
+pub trait Exchange: Send + Sync {
+    /// Short lowercase venue name used in logs and table prefixes.
+    fn name(&self) -> &'static str;
+
+    /// Translate a unified symbol (`BTCUSDT`) to the venue's
+    /// instrument id (e.g. OKX `BTC-USDT-SWAP`).
+    fn instrument(&self, symbol: &str) -> String;
+
+    /// Translate a venue instrument id back to the unified symbol.
+    fn unified_symbol(&self, instrument: &str) -> String;
+
+    /// Fetch up to `limit` candles over REST, oldest first.
+    async fn fetch_klines(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        interval: &str,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Kline>>;
+
+    /// Public WebSocket endpoint.
+    fn ws_url(&self) -> String;
+
+    /// Subscription payload(s) for the given streams.
+    fn ws_subscribe(&self, sources: &[StreamSource]) -> Vec<String>;
+
+    /// Unsubscription payload(s) for the given streams; venues without
+    /// runtime unsubscribe return nothing.
+    fn ws_unsubscribe(&self, _sources: &[StreamSource]) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Parse one WebSocket text frame. Heartbeats and subscription
+    /// acknowledgements yield an empty vector.
+    fn parse_ws_message(&self, text: &str) -> Result<Vec<Event>>;
+}
+
+/// Split a unified symbol into base and quote by well-known quote
+/// assets (longest match wins).
+pub(crate) fn split_symbol(symbol: &str) -> Option<(&str, &str)> {
+    const QUOTES: [&str; 7] = ["USDT", "FDUSD", "USDC", "BUSD", "USD", "BTC", "ETH"];
+    let upper = symbol;
+    QUOTES
+        .iter()
+        .filter(|q| upper.len() > q.len() && upper.ends_with(*q))
+        .max_by_key(|q| q.len())
+        .map(|q| (&upper[..upper.len() - q.len()], &upper[upper.len() - q.len()..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_by_longest_known_quote() {
+        assert_eq!(split_symbol("BTCUSDT"), Some(("BTC", "USDT")));
+        assert_eq!(split_symbol("ETHBTC"), Some(("ETH", "BTC")));
+        assert_eq!(split_symbol("BTCFDUSD"), Some(("BTC", "FDUSD")));
+        assert_eq!(split_symbol("USDT"), None);
+        assert_eq!(split_symbol("FOOBAR"), None);
+    }
+}
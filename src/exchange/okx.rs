@@ -0,0 +1,271 @@
+//! OKX: REST candles and public trade WebSocket.
+//!
+//! OKX names instruments `BTC-USDT` (spot) and `BTC-USDT-SWAP` (linear
+//! perp); both map onto the crate's unified `BTCUSDT` symbol, with the
+//! market chosen when the venue handle is constructed.
+
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::{split_symbol, Exchange};
+use crate::error::{Error, Result};
+use crate::model::{interval_ms, Kline, Trade};
+use crate::pipeline::spec::StreamSource;
+use crate::pipeline::Event;
+
+const REST_URL: &str = "https://www.okx.com";
+const WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+
+/// Which OKX market to collect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OkxMarket {
+    /// Spot pairs (`BTC-USDT`).
+    #[default]
+    Spot,
+    /// Linear perpetual swaps (`BTC-USDT-SWAP`).
+    Swap,
+}
+
+/// OKX venue handle.
+#[derive(Debug, Clone, Default)]
+pub struct Okx {
+    market: OkxMarket,
+}
+
+impl Okx {
+    /// Create a handle for the given market.
+    pub fn new(market: OkxMarket) -> Self {
+        Self { market }
+    }
+
+    /// Map the crate's interval strings onto OKX `bar` values, which
+    /// uppercase everything from hours up.
+    fn bar(interval: &str) -> String {
+        match interval {
+            "1h" | "2h" | "4h" | "6h" | "12h" => interval.to_uppercase(),
+            "1d" | "3d" | "1w" => format!("{}utc", interval.to_uppercase()),
+            "1M" => "1Mutc".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    fn kline_from_row(symbol: &str, interval: &str, row: &Value) -> Result<Kline> {
+        let num = |i: usize| -> Result<f64> {
+            row.get(i)
+                .and_then(Value::as_str)
+                .and_then(|s| s.parse().ok())
+                .ok_or_else(|| Error::Exchange(format!("bad candle field {i} in {row}")))
+        };
+        let open_time = num(0)? as i64;
+        let close_time = interval_ms(interval)
+            .map(|ms| open_time + ms - 1)
+            .unwrap_or(open_time);
+        Ok(Kline {
+            symbol: symbol.to_string(),
+            interval: interval.to_string(),
+            open_time,
+            close_time,
+            open: num(1)?,
+            high: num(2)?,
+            low: num(3)?,
+            close: num(4)?,
+            volume: num(5)?,
+            quote_volume: num(7).or_else(|_| num(6))?,
+            trade_count: 0,
+        })
+    }
+
+    fn trade_from_entry(&self, entry: &Value) -> Result<Trade> {
+        let str_field = |key: &str| -> Result<&str> {
+            entry
+                .get(key)
+                .and_then(Value::as_str)
+                .ok_or_else(|| Error::Exchange(format!("bad trade field `{key}`: {entry}")))
+        };
+        let num = |key: &str| -> Result<f64> {
+            str_field(key)?
+                .parse()
+                .map_err(|_| Error::Exchange(format!("bad trade field `{key}`: {entry}")))
+        };
+        Ok(Trade {
+            symbol: self.unified_symbol(str_field("instId")?),
+            trade_id: str_field("tradeId")?.parse().unwrap_or(0),
+            price: num("px")?,
+            qty: num("sz")?,
+            trade_time: str_field("ts")?.parse().unwrap_or(0),
+            // A `sell` side means the taker sold, i.e. the buyer was
+            // the resting maker order.
+            is_buyer_maker: str_field("side")? == "sell",
+        })
+    }
+}
+
+#[async_trait]
+impl Exchange for Okx {
+    fn name(&self) -> &'static str {
+        "okx"
+    }
+
+    fn instrument(&self, symbol: &str) -> String {
+        let dashed = match split_symbol(&symbol.to_uppercase()) {
+            Some((base, quote)) => format!("{base}-{quote}"),
+            None => symbol.to_uppercase(),
+        };
+        match self.market {
+            OkxMarket::Spot => dashed,
+            OkxMarket::Swap => format!("{dashed}-SWAP"),
+        }
+    }
+
+    fn unified_symbol(&self, instrument: &str) -> String {
+        instrument
+            .trim_end_matches("-SWAP")
+            .replace('-', "")
+            .to_uppercase()
+    }
+
+    async fn fetch_klines(
+        &self,
+        http: &reqwest::Client,
+        symbol: &str,
+        interval: &str,
+        start_ms: Option<i64>,
+        end_ms: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<Kline>> {
+        let mut query: Vec<(&str, String)> = vec![
+            ("instId", self.instrument(symbol)),
+            ("bar", Self::bar(interval)),
+            ("limit", limit.to_string()),
+        ];
+        // OKX pages backwards: `after` returns rows strictly older than
+        // the given timestamp, `before` strictly newer.
+        if let Some(end) = end_ms {
+            query.push(("after", end.to_string()));
+        }
+        if let Some(start) = start_ms {
+            query.push(("before", (start - 1).to_string()));
+        }
+        let body: Value = http
+            .get(format!("{REST_URL}/api/v5/market/candles"))
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("candles request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("candles response invalid: {e}")))?;
+        if body.get("code").and_then(Value::as_str) != Some("0") {
+            return Err(Error::Exchange(format!("okx error response: {body}")));
+        }
+        let rows = body
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Exchange(format!("okx response without data: {body}")))?;
+        // Rows arrive newest first; the trait contract is oldest first.
+        let mut klines = rows
+            .iter()
+            .map(|row| Self::kline_from_row(symbol, interval, row))
+            .collect::<Result<Vec<_>>>()?;
+        klines.reverse();
+        Ok(klines)
+    }
+
+    fn ws_url(&self) -> String {
+        WS_URL.to_string()
+    }
+
+    fn ws_subscribe(&self, sources: &[StreamSource]) -> Vec<String> {
+        let args: Vec<Value> = sources
+            .iter()
+            .map(|s| {
+                let channel = match s.stream.as_str() {
+                    "trade" => "trades",
+                    other => other,
+                };
+                serde_json::json!({
+                    "channel": channel,
+                    "instId": self.instrument(&s.symbol),
+                })
+            })
+            .collect();
+        vec![serde_json::json!({"op": "subscribe", "args": args}).to_string()]
+    }
+
+    fn parse_ws_message(&self, text: &str) -> Result<Vec<Event>> {
+        let value: Value = serde_json::from_str(text)?;
+        let channel = value
+            .pointer("/arg/channel")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if channel != "trades" {
+            // Acks, pings and other channels.
+            return Ok(Vec::new());
+        }
+        let entries = match value.get("data").and_then(Value::as_array) {
+            Some(entries) => entries,
+            None => return Ok(Vec::new()),
+        };
+        entries
+            .iter()
+            .map(|entry| Ok(Event::Trade(self.trade_from_entry(entry)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_instruments_both_ways() {
+        let spot = Okx::new(OkxMarket::Spot);
+        let swap = Okx::new(OkxMarket::Swap);
+        assert_eq!(spot.instrument("BTCUSDT"), "BTC-USDT");
+        assert_eq!(swap.instrument("btcusdt"), "BTC-USDT-SWAP");
+        assert_eq!(spot.unified_symbol("BTC-USDT"), "BTCUSDT");
+        assert_eq!(swap.unified_symbol("BTC-USDT-SWAP"), "BTCUSDT");
+    }
+
+    #[test]
+    fn maps_bars() {
+        assert_eq!(Okx::bar("1m"), "1m");
+        assert_eq!(Okx::bar("1h"), "1H");
+        assert_eq!(Okx::bar("1d"), "1Dutc");
+    }
+
+    #[test]
+    fn parses_trade_frame() {
+        let frame = r#"{"arg":{"channel":"trades","instId":"BTC-USDT-SWAP"},"data":[{"instId":"BTC-USDT-SWAP","tradeId":"130639474","px":"42219.9","sz":"0.12","side":"sell","ts":"1630048897897"}]}"#;
+        let events = Okx::new(OkxMarket::Swap).parse_ws_message(frame).unwrap();
+        assert_eq!(events.len(), 1);
+        match &events[0] {
+            Event::Trade(t) => {
+                assert_eq!(t.symbol, "BTCUSDT");
+                assert_eq!(t.price, 42_219.9);
+                assert!(t.is_buyer_maker);
+            }
+            other => panic!("expected trade, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn subscribe_ack_yields_nothing() {
+        let ack = r#"{"event":"subscribe","arg":{"channel":"trades","instId":"BTC-USDT"}}"#;
+        assert!(Okx::default().parse_ws_message(ack).unwrap().is_empty());
+    }
+
+    #[test]
+    fn candle_rows_reverse_to_oldest_first() {
+        let row = |ts: i64| {
+            serde_json::json!([
+                ts.to_string(),
+                "100", "110", "90", "105", "12.5", "1250", "1312.5", "1"
+            ])
+        };
+        let k1 = Okx::kline_from_row("BTCUSDT", "1m", &row(120_000)).unwrap();
+        assert_eq!(k1.open_time, 120_000);
+        assert_eq!(k1.close_time, 179_999);
+        assert_eq!(k1.quote_volume, 1_312.5);
+    }
+}
@@ -0,0 +1,528 @@
+//! Symbol metadata from `exchangeInfo` and user-input validation.
+//!
+//! A typo'd symbol used to be discovered only after the API returned
+//! empty arrays for an hour; long-running jobs now validate their
+//! symbol lists up front and fail with near-match suggestions.
+
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+/// One symbol's metadata from `exchangeInfo`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolInfo {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Trading status, e.g. `TRADING` or `BREAK`.
+    pub status: String,
+    /// Base asset, e.g. `BTC`.
+    pub base_asset: String,
+    /// Quote asset, e.g. `USDT`.
+    pub quote_asset: String,
+    /// Trading permissions (`SPOT`, `MARGIN`, ...), flattened from
+    /// both the legacy `permissions` array and `permissionSets`.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Price increment from `PRICE_FILTER`, if present.
+    #[serde(default)]
+    pub tick_size: Option<f64>,
+    /// Quantity increment from `LOT_SIZE`, if present.
+    #[serde(default)]
+    pub step_size: Option<f64>,
+    /// Minimum order notional from `NOTIONAL`/`MIN_NOTIONAL`, if
+    /// present.
+    #[serde(default)]
+    pub min_notional: Option<f64>,
+}
+
+/// Parse the `symbols` array of a Binance `exchangeInfo` response.
+pub fn parse_exchange_info(body: &Value) -> Result<Vec<SymbolInfo>> {
+    let symbols = body
+        .get("symbols")
+        .and_then(Value::as_array)
+        .ok_or_else(|| Error::Exchange("exchangeInfo without symbols array".to_string()))?;
+    symbols
+        .iter()
+        .map(|s| {
+            let field = |key: &str| -> Result<String> {
+                s.get(key)
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| Error::Exchange(format!("symbol entry without `{key}`: {s}")))
+            };
+            let mut permissions: Vec<String> = s
+                .get("permissions")
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default();
+            if let Some(sets) = s.get("permissionSets").and_then(Value::as_array) {
+                for set in sets.iter().filter_map(Value::as_array) {
+                    permissions.extend(set.iter().filter_map(Value::as_str).map(str::to_string));
+                }
+            }
+            permissions.sort();
+            permissions.dedup();
+            let filter_num = |filter_type: &str, key: &str| -> Option<f64> {
+                s.get("filters")
+                    .and_then(Value::as_array)?
+                    .iter()
+                    .find(|f| f.get("filterType").and_then(Value::as_str) == Some(filter_type))?
+                    .get(key)
+                    .and_then(Value::as_str)?
+                    .parse()
+                    .ok()
+            };
+            Ok(SymbolInfo {
+                symbol: field("symbol")?,
+                status: field("status")?,
+                base_asset: field("baseAsset")?,
+                quote_asset: field("quoteAsset")?,
+                permissions,
+                tick_size: filter_num("PRICE_FILTER", "tickSize"),
+                step_size: filter_num("LOT_SIZE", "stepSize"),
+                min_notional: filter_num("NOTIONAL", "minNotional")
+                    .or_else(|| filter_num("MIN_NOTIONAL", "minNotional")),
+            })
+        })
+        .collect()
+}
+
+/// Fetch `exchangeInfo` from the given REST host.
+pub async fn fetch_exchange_info(
+    http: &reqwest::Client,
+    rest_url: &str,
+) -> Result<Vec<SymbolInfo>> {
+    let body: Value = http
+        .get(format!("{rest_url}/api/v3/exchangeInfo"))
+        .send()
+        .await
+        .map_err(|e| Error::Exchange(format!("exchangeInfo request failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| Error::Exchange(format!("exchangeInfo response invalid: {e}")))?;
+    parse_exchange_info(&body)
+}
+
+/// Row shape of the `symbol_filters` dimension table.
+#[derive(Debug, serde::Serialize)]
+struct SymbolFilterRow<'a> {
+    snapshot_time: i64,
+    symbol: &'a str,
+    status: &'a str,
+    base_asset: &'a str,
+    quote_asset: &'a str,
+    tick_size: f64,
+    step_size: f64,
+    min_notional: f64,
+    permissions: &'a [String],
+}
+
+/// Create the versioned `symbol_filters` dimension table.
+pub async fn ensure_filters_schema(db: &crate::db::ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS symbol_filters (\
+         snapshot_time Int64, symbol String, status String, \
+         base_asset String, quote_asset String, \
+         tick_size Float64, step_size Float64, min_notional Float64, \
+         permissions Array(String)) \
+         ENGINE = MergeTree ORDER BY (symbol, snapshot_time)",
+    )
+    .await
+}
+
+/// Snapshot the full symbol universe into `symbol_filters`, versioned
+/// by `snapshot_time`, so price data can be joined with the filters in
+/// force at the time.
+pub async fn snapshot_filters(
+    db: &crate::db::ClickHouse,
+    symbols: &[SymbolInfo],
+    snapshot_time: i64,
+) -> Result<usize> {
+    let rows: Vec<SymbolFilterRow<'_>> = symbols
+        .iter()
+        .map(|s| SymbolFilterRow {
+            snapshot_time,
+            symbol: &s.symbol,
+            status: &s.status,
+            base_asset: &s.base_asset,
+            quote_asset: &s.quote_asset,
+            tick_size: s.tick_size.unwrap_or(0.0),
+            step_size: s.step_size.unwrap_or(0.0),
+            min_notional: s.min_notional.unwrap_or(0.0),
+            permissions: &s.permissions,
+        })
+        .collect();
+    db.insert_rows("symbol_filters", &rows).await?;
+    Ok(rows.len())
+}
+
+/// One observed change between two exchangeInfo snapshots.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SymbolChange {
+    /// Change timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Symbol the change applies to.
+    pub symbol: String,
+    /// What changed: `listed`, `delisted`, `status`, `filters` or
+    /// `permissions`.
+    pub field: String,
+    /// Previous value, rendered as text.
+    pub old: String,
+    /// New value, rendered as text.
+    pub new: String,
+}
+
+impl SymbolChange {
+    /// Render as a pipeline alert for in-process consumers.
+    pub fn to_alert(&self) -> crate::pipeline::Alert {
+        crate::pipeline::Alert {
+            symbol: self.symbol.clone(),
+            source: "symbol_status".to_string(),
+            message: format!("{} changed: {} -> {}", self.field, self.old, self.new),
+            time: self.time,
+        }
+    }
+}
+
+fn filters_repr(s: &SymbolInfo) -> String {
+    format!(
+        "tick={:?} step={:?} min_notional={:?}",
+        s.tick_size, s.step_size, s.min_notional
+    )
+}
+
+/// Diff two exchangeInfo snapshots into change records: listings,
+/// delistings, and status/filter/permission changes.
+pub fn diff_universe(
+    previous: &[SymbolInfo],
+    current: &[SymbolInfo],
+    now_ms: i64,
+) -> Vec<SymbolChange> {
+    let prev_by_symbol: std::collections::HashMap<&str, &SymbolInfo> =
+        previous.iter().map(|s| (s.symbol.as_str(), s)).collect();
+    let mut changes = Vec::new();
+    let mut change = |symbol: &str, field: &str, old: String, new: String| {
+        changes.push(SymbolChange {
+            time: now_ms,
+            symbol: symbol.to_string(),
+            field: field.to_string(),
+            old,
+            new,
+        });
+    };
+    for cur in current {
+        match prev_by_symbol.get(cur.symbol.as_str()) {
+            None => change(&cur.symbol, "listed", String::new(), cur.status.clone()),
+            Some(prev) => {
+                if prev.status != cur.status {
+                    change(&cur.symbol, "status", prev.status.clone(), cur.status.clone());
+                }
+                if filters_repr(prev) != filters_repr(cur) {
+                    change(&cur.symbol, "filters", filters_repr(prev), filters_repr(cur));
+                }
+                if prev.permissions != cur.permissions {
+                    change(
+                        &cur.symbol,
+                        "permissions",
+                        prev.permissions.join(","),
+                        cur.permissions.join(","),
+                    );
+                }
+            }
+        }
+    }
+    let current_names: std::collections::HashSet<&str> =
+        current.iter().map(|s| s.symbol.as_str()).collect();
+    for prev in previous {
+        if !current_names.contains(prev.symbol.as_str()) {
+            change(&prev.symbol, "delisted", prev.status.clone(), String::new());
+        }
+    }
+    changes
+}
+
+/// Create the `symbol_status_changes` history table.
+pub async fn ensure_changes_schema(db: &crate::db::ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS symbol_status_changes (\
+         time Int64, symbol String, field String, old String, new String) \
+         ENGINE = MergeTree ORDER BY (symbol, time)",
+    )
+    .await
+}
+
+/// Persist change records into the history table.
+pub async fn record_changes(db: &crate::db::ClickHouse, changes: &[SymbolChange]) -> Result<()> {
+    db.insert_rows("symbol_status_changes", changes).await
+}
+
+/// Filter settings for listing pairs; the old behaviour (USDT +
+/// TRADING only) is the default.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct PairsFilter {
+    /// Accepted quote assets.
+    #[serde(default = "default_quote_assets")]
+    pub quote_assets: Vec<String>,
+    /// Accepted statuses.
+    #[serde(default = "default_statuses")]
+    pub statuses: Vec<String>,
+    /// Required permissions; empty accepts any.
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// Optional regex the base asset must match.
+    #[serde(default)]
+    pub base_regex: Option<String>,
+}
+
+fn default_quote_assets() -> Vec<String> {
+    vec!["USDT".to_string()]
+}
+
+fn default_statuses() -> Vec<String> {
+    vec!["TRADING".to_string()]
+}
+
+impl Default for PairsFilter {
+    fn default() -> Self {
+        Self {
+            quote_assets: default_quote_assets(),
+            statuses: default_statuses(),
+            permissions: Vec::new(),
+            base_regex: None,
+        }
+    }
+}
+
+/// Apply a [`PairsFilter`] to the symbol universe.
+pub fn filter_pairs<'a>(
+    known: &'a [SymbolInfo],
+    filter: &PairsFilter,
+) -> Result<Vec<&'a SymbolInfo>> {
+    let base_re = filter
+        .base_regex
+        .as_deref()
+        .map(regex::Regex::new)
+        .transpose()
+        .map_err(|e| Error::Config(format!("bad base_regex: {e}")))?;
+    Ok(known
+        .iter()
+        .filter(|s| {
+            filter.quote_assets.iter().any(|q| q.eq_ignore_ascii_case(&s.quote_asset))
+                && filter.statuses.iter().any(|st| st.eq_ignore_ascii_case(&s.status))
+                && (filter.permissions.is_empty()
+                    || filter
+                        .permissions
+                        .iter()
+                        .all(|p| s.permissions.iter().any(|have| have.eq_ignore_ascii_case(p))))
+                && base_re.as_ref().is_none_or(|re| re.is_match(&s.base_asset))
+        })
+        .collect())
+}
+
+/// Check every requested symbol against the known universe; unknown
+/// symbols fail with up to three near matches each.
+pub fn validate_symbols(known: &[SymbolInfo], requested: &[String]) -> Result<()> {
+    let mut problems = Vec::new();
+    for symbol in requested {
+        let upper = symbol.to_uppercase();
+        if known.iter().any(|s| s.symbol == upper) {
+            continue;
+        }
+        let mut candidates: Vec<(usize, &str)> = known
+            .iter()
+            .map(|s| (edit_distance(&upper, &s.symbol), s.symbol.as_str()))
+            .filter(|(d, _)| *d <= 2)
+            .collect();
+        candidates.sort();
+        let suggestions: Vec<&str> = candidates.iter().take(3).map(|(_, s)| *s).collect();
+        if suggestions.is_empty() {
+            problems.push(format!("`{symbol}` is not a known symbol"));
+        } else {
+            problems.push(format!(
+                "`{symbol}` is not a known symbol (did you mean {}?)",
+                suggestions.join(", ")
+            ));
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::Config(problems.join("; ")))
+    }
+}
+
+/// Plain Levenshtein distance; the symbol universe is small enough
+/// that O(n·m) per pair is irrelevant.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, &ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn universe() -> Vec<SymbolInfo> {
+        ["BTCUSDT", "ETHUSDT", "BNBUSDT", "ETHBTC"]
+            .into_iter()
+            .map(|s| SymbolInfo {
+                symbol: s.to_string(),
+                status: "TRADING".to_string(),
+                base_asset: String::new(),
+                quote_asset: String::new(),
+                permissions: Vec::new(),
+                tick_size: None,
+                step_size: None,
+                min_notional: None,
+            })
+            .collect()
+    }
+
+    fn pair(symbol: &str, base: &str, quote: &str, status: &str, perms: &[&str]) -> SymbolInfo {
+        SymbolInfo {
+            symbol: symbol.to_string(),
+            status: status.to_string(),
+            base_asset: base.to_string(),
+            quote_asset: quote.to_string(),
+            permissions: perms.iter().map(|p| p.to_string()).collect(),
+            tick_size: None,
+            step_size: None,
+            min_notional: None,
+        }
+    }
+
+    #[test]
+    fn default_filter_keeps_trading_usdt_pairs() {
+        let known = vec![
+            pair("BTCUSDT", "BTC", "USDT", "TRADING", &["SPOT"]),
+            pair("ETHBTC", "ETH", "BTC", "TRADING", &["SPOT"]),
+            pair("LUNAUSDT", "LUNA", "USDT", "BREAK", &["SPOT"]),
+        ];
+        let hits = filter_pairs(&known, &PairsFilter::default()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].symbol, "BTCUSDT");
+    }
+
+    #[test]
+    fn filter_honours_quotes_statuses_permissions_and_regex() {
+        let known = vec![
+            pair("BTCUSDT", "BTC", "USDT", "TRADING", &["SPOT", "MARGIN"]),
+            pair("BNBBTC", "BNB", "BTC", "TRADING", &["SPOT"]),
+            pair("BADUSDT", "BAD", "USDT", "BREAK", &["SPOT"]),
+        ];
+        let filter = PairsFilter {
+            quote_assets: vec!["USDT".into(), "BTC".into()],
+            statuses: vec!["TRADING".into(), "BREAK".into()],
+            permissions: vec!["SPOT".into()],
+            base_regex: Some("^B".into()),
+        };
+        let hits = filter_pairs(&known, &filter).unwrap();
+        let names: Vec<&str> = hits.iter().map(|s| s.symbol.as_str()).collect();
+        assert_eq!(names, vec!["BTCUSDT", "BNBBTC", "BADUSDT"]);
+        let margin_only = PairsFilter {
+            permissions: vec!["MARGIN".into()],
+            ..PairsFilter::default()
+        };
+        assert_eq!(filter_pairs(&known, &margin_only).unwrap().len(), 1);
+        let bad_re = PairsFilter {
+            base_regex: Some("[".into()),
+            ..PairsFilter::default()
+        };
+        assert!(filter_pairs(&known, &bad_re).is_err());
+    }
+
+    #[test]
+    fn known_symbols_pass_case_insensitively() {
+        validate_symbols(&universe(), &["btcusdt".into(), "ETHUSDT".into()]).unwrap();
+    }
+
+    #[test]
+    fn typo_suggests_near_matches() {
+        let err = validate_symbols(&universe(), &["BTCUSTD".into()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("BTCUSTD"));
+        assert!(err.contains("BTCUSDT"));
+    }
+
+    #[test]
+    fn nonsense_fails_without_suggestions() {
+        let err = validate_symbols(&universe(), &["ZZZZZZZZZ".into()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not a known symbol"));
+        assert!(!err.contains("did you mean"));
+    }
+
+    #[test]
+    fn parses_exchange_info_symbols() {
+        let body = serde_json::json!({
+            "symbols": [
+                {"symbol": "BTCUSDT", "status": "TRADING", "baseAsset": "BTC", "quoteAsset": "USDT",
+                 "filters": [
+                    {"filterType": "PRICE_FILTER", "tickSize": "0.01"},
+                    {"filterType": "LOT_SIZE", "stepSize": "0.00001"},
+                    {"filterType": "NOTIONAL", "minNotional": "5.00"},
+                 ]},
+                {"symbol": "LUNAUSDT", "status": "BREAK", "baseAsset": "LUNA", "quoteAsset": "USDT"},
+            ]
+        });
+        let symbols = parse_exchange_info(&body).unwrap();
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[1].status, "BREAK");
+        assert_eq!(symbols[0].tick_size, Some(0.01));
+        assert_eq!(symbols[0].step_size, Some(0.00001));
+        assert_eq!(symbols[0].min_notional, Some(5.0));
+        assert_eq!(symbols[1].tick_size, None);
+    }
+
+    #[test]
+    fn edit_distance_basics() {
+        assert_eq!(edit_distance("BTCUSDT", "BTCUSDT"), 0);
+        assert_eq!(edit_distance("BTCUSDT", "BTCUSTD"), 2);
+        assert_eq!(edit_distance("A", ""), 1);
+    }
+
+    #[test]
+    fn universe_diff_reports_all_change_kinds() {
+        let mut old_btc = pair("BTCUSDT", "BTC", "USDT", "TRADING", &["SPOT"]);
+        old_btc.tick_size = Some(0.01);
+        let mut new_btc = old_btc.clone();
+        new_btc.status = "BREAK".to_string();
+        new_btc.tick_size = Some(0.1);
+        new_btc.permissions = vec!["SPOT".into(), "MARGIN".into()];
+        let gone = pair("OLDUSDT", "OLD", "USDT", "TRADING", &[]);
+        let fresh = pair("NEWUSDT", "NEW", "USDT", "TRADING", &[]);
+        let changes = diff_universe(
+            &[old_btc, gone],
+            &[new_btc, fresh],
+            1_700_000_000_000,
+        );
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["status", "filters", "permissions", "listed", "delisted"]);
+        let status = &changes[0];
+        assert_eq!(status.old, "TRADING");
+        assert_eq!(status.new, "BREAK");
+        let alert = status.to_alert();
+        assert!(alert.message.contains("status changed"));
+        // No changes -> empty diff.
+        let stable = pair("ETHUSDT", "ETH", "USDT", "TRADING", &[]);
+        assert!(diff_universe(std::slice::from_ref(&stable), std::slice::from_ref(&stable), 0).is_empty());
+    }
+}
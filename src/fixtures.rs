@@ -0,0 +1,111 @@
+//! HTTP record/replay fixtures.
+//!
+//! With `--record`, every REST response the exchange layer sees is
+//! captured to a fixture file; with `--replay`, requests are answered
+//! from those files without touching the network — so a parsing
+//! failure seen in a production backfill can be reproduced and
+//! debugged deterministically.
+
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+/// The active fixture mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FixtureMode {
+    /// Capture responses into the directory.
+    Record(PathBuf),
+    /// Serve responses from the directory; missing fixtures fail.
+    Replay(PathBuf),
+}
+
+static MODE: RwLock<Option<FixtureMode>> = RwLock::new(None);
+
+/// Set (or clear) the process-wide fixture mode.
+pub fn set_mode(mode: Option<FixtureMode>) -> Result<()> {
+    if let Some(FixtureMode::Record(dir)) = &mode {
+        std::fs::create_dir_all(dir)?;
+    }
+    *MODE.write().expect("fixture mode poisoned") = mode;
+    Ok(())
+}
+
+/// Stable fixture file name for one request: the path with slashes
+/// flattened plus a short digest of the query string.
+fn key(path: &str, query: &str) -> String {
+    let flat = path.trim_matches('/').replace('/', "_");
+    let digest = Sha256::digest(query.as_bytes());
+    format!("{flat}-{:x}.json", digest)
+        .chars()
+        .take(120)
+        .collect()
+}
+
+fn canonical_query(query: &[(&str, String)]) -> String {
+    let mut parts: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+    parts.sort();
+    parts.join("&")
+}
+
+/// In replay mode, answer the request from its fixture.
+pub fn lookup(path: &str, query: &[(&str, String)]) -> Option<Result<Value>> {
+    let guard = MODE.read().expect("fixture mode poisoned");
+    let Some(FixtureMode::Replay(dir)) = guard.as_ref() else {
+        return None;
+    };
+    let file = dir.join(key(path, &canonical_query(query)));
+    Some(match std::fs::read_to_string(&file) {
+        Ok(text) => serde_json::from_str(&text).map_err(Error::from),
+        Err(_) => Err(Error::Config(format!(
+            "no fixture for {path} at {} — record it first",
+            file.display()
+        ))),
+    })
+}
+
+/// In record mode, persist a response for later replay.
+pub fn store(path: &str, query: &[(&str, String)], body: &Value) {
+    let guard = MODE.read().expect("fixture mode poisoned");
+    let Some(FixtureMode::Record(dir)) = guard.as_ref() else {
+        return;
+    };
+    let file = dir.join(key(path, &canonical_query(query)));
+    if let Err(e) = std::fs::write(&file, body.to_string()) {
+        tracing::warn!(path, error = %e, "cannot record fixture");
+    } else {
+        tracing::info!(fixture = %file.display(), "recorded");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_then_replay_round_trips() {
+        let dir = std::env::temp_dir().join(format!("bzl-fixtures-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let query = [("symbol", "BTCUSDT".to_string()), ("limit", "10".to_string())];
+        set_mode(Some(FixtureMode::Record(dir.clone()))).unwrap();
+        assert!(lookup("/api/v3/klines", &query).is_none());
+        store("/api/v3/klines", &query, &serde_json::json!([1, 2, 3]));
+
+        set_mode(Some(FixtureMode::Replay(dir.clone()))).unwrap();
+        let replayed = lookup("/api/v3/klines", &query).unwrap().unwrap();
+        assert_eq!(replayed, serde_json::json!([1, 2, 3]));
+        // Query order does not matter.
+        let swapped = [("limit", "10".to_string()), ("symbol", "BTCUSDT".to_string())];
+        assert!(lookup("/api/v3/klines", &swapped).unwrap().is_ok());
+        // A different request has no fixture.
+        let other = [("symbol", "ETHUSDT".to_string())];
+        assert!(lookup("/api/v3/klines", &other).unwrap().is_err());
+
+        set_mode(None).unwrap();
+        assert!(lookup("/api/v3/klines", &query).is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
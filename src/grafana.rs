@@ -0,0 +1,89 @@
+//! Grafana-friendly schema for the ClickHouse datasource.
+//!
+//! Grafana's ClickHouse plugin wants epoch-second `time` columns and
+//! plain scalar rows, not the `DateTime64(3)` archive schema or
+//! nested/array columns — so this adds read-only views instead of
+//! reworking the underlying tables, plus an annotation view over
+//! [`crate::audit`] so onboarding, retirement, handovers and backfills
+//! overlay on dashboards without a second write path.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+/// The `[grafana]` config section; enabled whenever it is present.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrafanaConfig {
+    /// `audit_log.action` values surfaced as dashboard annotations.
+    #[serde(default = "default_annotate_actions")]
+    pub annotate_actions: Vec<String>,
+}
+
+impl Default for GrafanaConfig {
+    fn default() -> Self {
+        Self {
+            annotate_actions: default_annotate_actions(),
+        }
+    }
+}
+
+fn default_annotate_actions() -> Vec<String> {
+    [
+        "symbol_onboarded",
+        "symbol_retired",
+        "handover",
+        "backfill_started",
+        "backfill_finished",
+        "backfill_failed",
+    ]
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+/// Create the Grafana-facing views. Requires `audit_log` (see
+/// [`crate::audit::Audit::ensure_schema`]) and the archive tables to
+/// exist already.
+pub async fn ensure_schema(db: &ClickHouse, cfg: &GrafanaConfig) -> Result<()> {
+    let actions = cfg
+        .annotate_actions
+        .iter()
+        .map(|a| format!("'{}'", a.replace('\'', "''")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    db.execute(
+        "CREATE VIEW IF NOT EXISTS klines_grafana AS \
+         SELECT toUnixTimestamp(open_time) AS time, symbol, interval, \
+         open, high, low, close, volume, quote_volume, trade_count \
+         FROM klines",
+    )
+    .await?;
+    db.execute(
+        "CREATE VIEW IF NOT EXISTS trades_grafana AS \
+         SELECT toUnixTimestamp(trade_time) AS time, symbol, trade_id, \
+         price, qty, is_buyer_maker \
+         FROM trades",
+    )
+    .await?;
+    db.execute(&format!(
+        "CREATE VIEW IF NOT EXISTS grafana_annotations AS \
+         SELECT intDiv(time, 1000) AS time, \
+         concat(action, ' ', subject, ': ', detail) AS text, [action] AS tags \
+         FROM audit_log WHERE action IN ({actions})"
+    ))
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_actions_cover_the_documented_examples() {
+        let cfg = GrafanaConfig::default();
+        assert!(cfg.annotate_actions.contains(&"backfill_started".to_string()));
+        assert!(cfg.annotate_actions.contains(&"symbol_onboarded".to_string()));
+    }
+}
@@ -0,0 +1,194 @@
+//! In-process event hooks for library consumers.
+//!
+//! Embedding applications can react to market events without standing
+//! up any relay infrastructure: register callbacks on a [`Hooks`]
+//! registry and drop a [`HookProcessor`] into the pipeline.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use bzl_database::hooks::{Hooks, HookProcessor};
+//!
+//! let mut hooks = Hooks::new();
+//! hooks.on_candle_close(|k| println!("{} {} closed at {}", k.symbol, k.interval, k.close));
+//! hooks.on_gap_detected(|gap| eprintln!("gap: {gap:?}"));
+//! let processor = HookProcessor::new(Arc::new(hooks));
+//! # let _ = processor;
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::model::{Kline, Trade};
+use crate::pipeline::{Event, Processor};
+
+/// A detected gap in a candle series.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GapInfo {
+    /// Symbol the gap is in.
+    pub symbol: String,
+    /// Interval the gap is in.
+    pub interval: String,
+    /// Open time of the last candle before the gap.
+    pub before: i64,
+    /// Open time of the first candle after the gap.
+    pub after: i64,
+    /// Number of missing candles.
+    pub missing: i64,
+}
+
+type Callback<T> = Box<dyn Fn(&T) + Send + Sync>;
+
+/// Callback registry.
+#[derive(Default)]
+pub struct Hooks {
+    on_trade: Vec<Callback<Trade>>,
+    on_candle_close: Vec<Callback<Kline>>,
+    on_gap_detected: Vec<Callback<GapInfo>>,
+}
+
+impl Hooks {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `f` for every trade.
+    pub fn on_trade(&mut self, f: impl Fn(&Trade) + Send + Sync + 'static) -> &mut Self {
+        self.on_trade.push(Box::new(f));
+        self
+    }
+
+    /// Run `f` for every closed candle.
+    pub fn on_candle_close(&mut self, f: impl Fn(&Kline) + Send + Sync + 'static) -> &mut Self {
+        self.on_candle_close.push(Box::new(f));
+        self
+    }
+
+    /// Run `f` whenever a candle series skips one or more windows.
+    pub fn on_gap_detected(&mut self, f: impl Fn(&GapInfo) + Send + Sync + 'static) -> &mut Self {
+        self.on_gap_detected.push(Box::new(f));
+        self
+    }
+}
+
+/// Pipeline stage invoking registered hooks; events pass through
+/// unchanged.
+pub struct HookProcessor {
+    hooks: Arc<Hooks>,
+    last_open: HashMap<(String, String), i64>,
+}
+
+impl HookProcessor {
+    /// Wrap a registry as a pipeline stage.
+    pub fn new(hooks: Arc<Hooks>) -> Self {
+        Self {
+            hooks,
+            last_open: HashMap::new(),
+        }
+    }
+}
+
+impl Processor for HookProcessor {
+    fn name(&self) -> &str {
+        "hooks"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        match &event {
+            Event::Trade(t) => {
+                for hook in &self.hooks.on_trade {
+                    hook(t);
+                }
+            }
+            Event::Kline(k) => {
+                for hook in &self.hooks.on_candle_close {
+                    hook(k);
+                }
+                if let Some(step) = crate::model::interval_ms(&k.interval) {
+                    let key = (k.symbol.clone(), k.interval.clone());
+                    if let Some(&last) = self.last_open.get(&key) {
+                        let delta = k.open_time - last;
+                        if delta > step {
+                            let gap = GapInfo {
+                                symbol: k.symbol.clone(),
+                                interval: k.interval.clone(),
+                                before: last,
+                                after: k.open_time,
+                                missing: delta / step - 1,
+                            };
+                            for hook in &self.hooks.on_gap_detected {
+                                hook(&gap);
+                            }
+                        }
+                    }
+                    self.last_open.insert(key, k.open_time);
+                }
+            }
+            _ => {}
+        }
+        vec![event]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+
+    fn kline(open_time: i64) -> Event {
+        Event::Kline(Kline {
+            symbol: "BTCUSDT".into(),
+            interval: "1m".into(),
+            open_time,
+            close_time: open_time + 59_999,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: 1.0,
+            quote_volume: 1.0,
+            trade_count: 1,
+        })
+    }
+
+    #[test]
+    fn hooks_fire_and_events_pass_through() {
+        let trades = Arc::new(AtomicUsize::new(0));
+        let candles = Arc::new(AtomicUsize::new(0));
+        let gaps: Arc<Mutex<Vec<GapInfo>>> = Arc::new(Mutex::new(Vec::new()));
+        let mut hooks = Hooks::new();
+        let t = trades.clone();
+        hooks.on_trade(move |_| {
+            t.fetch_add(1, Ordering::Relaxed);
+        });
+        let c = candles.clone();
+        hooks.on_candle_close(move |_| {
+            c.fetch_add(1, Ordering::Relaxed);
+        });
+        let g = gaps.clone();
+        hooks.on_gap_detected(move |gap| g.lock().unwrap().push(gap.clone()));
+
+        let mut processor = HookProcessor::new(Arc::new(hooks));
+        let trade = Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price: 1.0,
+            qty: 1.0,
+            trade_time: 0,
+            is_buyer_maker: false,
+        });
+        assert_eq!(processor.process(trade).len(), 1);
+        assert_eq!(processor.process(kline(0)).len(), 1);
+        // Candles 1 and 2 missing: one gap of two windows.
+        processor.process(kline(180_000));
+        assert_eq!(trades.load(Ordering::Relaxed), 1);
+        assert_eq!(candles.load(Ordering::Relaxed), 2);
+        let gaps = gaps.lock().unwrap();
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].missing, 2);
+        assert_eq!(gaps[0].before, 0);
+        assert_eq!(gaps[0].after, 180_000);
+    }
+}
@@ -0,0 +1,41 @@
+//! The process-wide HTTP client.
+//!
+//! Every subsystem used to build its own `reqwest::Client`, which
+//! wastes connection pools and loses keep-alive across tasks. All REST
+//! traffic now goes through one tuned, shared client.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// The shared client. Cloning is cheap — clones share the pool.
+pub fn client() -> reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(build).clone()
+}
+
+fn build() -> reqwest::Client {
+    reqwest::Client::builder()
+        .user_agent(concat!("bzl-database/", env!("CARGO_PKG_VERSION")))
+        .gzip(true)
+        .tcp_keepalive(Duration::from_secs(30))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .pool_max_idle_per_host(16)
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("HTTP client construction cannot fail with static options")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_calls_share_one_client() {
+        // `Client` clones share the inner pool; the static guarantees
+        // every call sees the same instance.
+        let a = client();
+        let b = client();
+        assert_eq!(format!("{a:?}"), format!("{b:?}"));
+    }
+}
@@ -0,0 +1,281 @@
+//! Persistent, prioritized backfill job queue.
+//!
+//! Gap repairs, new-listing backfills and manual requests are enqueued
+//! as rows in ClickHouse and processed by workers under the normal
+//! request pacing — highest priority first, oldest range first within
+//! a priority. Status is visible via `bzl backfill list`.
+//!
+//! ClickHouse has no compare-and-set, so claiming a job is optimistic
+//! the same way `ops::sharding` claims symbols: a worker writes itself
+//! in as the `running` owner, then re-reads the row to confirm its own
+//! write is the one that stuck before it starts fetching. A `running`
+//! job whose owner hasn't renewed it within [`RUN_LEASE_MS`] is treated
+//! as abandoned (its worker crashed mid-job) and becomes claimable
+//! again, mirroring the TTL leases in `ops::sharding` and
+//! `ops::leadership`.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::exchange::binance::Binance;
+use crate::exchange::Exchange;
+use crate::model::Interval;
+use crate::pipeline::Event;
+use crate::sink::Sink;
+
+/// A stuck `running` job older than this is assumed abandoned by a
+/// crashed worker and becomes claimable again.
+const RUN_LEASE_MS: i64 = 5 * 60_000;
+
+/// One backfill job.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackfillJob {
+    /// Deterministic id (hash of symbol/interval/range).
+    pub job_id: String,
+    /// Symbol to backfill.
+    pub symbol: String,
+    /// Interval to backfill.
+    pub interval: Interval,
+    /// Range start in epoch milliseconds.
+    pub from: i64,
+    /// Range end in epoch milliseconds.
+    pub to: i64,
+    /// Larger runs first.
+    pub priority: i32,
+    /// `pending`, `running`, `done` or `failed`.
+    pub status: String,
+    /// Worker currently holding the job (set while `running`).
+    #[serde(default)]
+    pub owner: String,
+    /// Last status detail (error text, row counts).
+    pub detail: String,
+    /// Write timestamp for the Replacing engine; also the running
+    /// lease's renewal time.
+    pub updated_at: i64,
+}
+
+impl BackfillJob {
+    /// Create a pending job; the id is deterministic so re-enqueueing
+    /// the same range replaces rather than duplicates.
+    pub fn new(symbol: &str, interval: Interval, from: i64, to: i64, priority: i32, now: i64) -> Self {
+        let symbol = symbol.to_uppercase();
+        let digest = Sha256::digest(format!("{symbol}/{interval}/{from}/{to}").as_bytes());
+        Self {
+            job_id: format!("{digest:x}")[..16].to_string(),
+            symbol,
+            interval,
+            from,
+            to,
+            priority,
+            status: "pending".to_string(),
+            owner: String::new(),
+            detail: String::new(),
+            updated_at: now,
+        }
+    }
+}
+
+/// This worker's identity, for job ownership.
+pub fn worker_id() -> String {
+    let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "worker".to_string());
+    format!("{host}-{}", std::process::id())
+}
+
+/// Pick the next job to run: highest priority, then earliest range,
+/// among pending jobs and `running` jobs whose lease expired.
+pub fn pick_next(jobs: &[BackfillJob], now: i64) -> Option<&BackfillJob> {
+    jobs.iter()
+        .filter(|j| j.status == "pending" || (j.status == "running" && now - j.updated_at > RUN_LEASE_MS))
+        .max_by(|a, b| {
+            a.priority
+                .cmp(&b.priority)
+                .then(b.from.cmp(&a.from))
+        })
+}
+
+/// Create the queue table.
+pub async fn ensure_schema(db: &ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS backfill_jobs (\
+         job_id String, symbol String, interval String, from Int64, to Int64, \
+         priority Int32, status String, owner String, detail String, updated_at Int64) \
+         ENGINE = ReplacingMergeTree(updated_at) ORDER BY job_id",
+    )
+    .await
+}
+
+/// Enqueue (or re-enqueue) a job.
+pub async fn enqueue(db: &ClickHouse, job: &BackfillJob) -> Result<()> {
+    db.insert_rows("backfill_jobs", std::slice::from_ref(job)).await
+}
+
+/// Latest state of every job.
+pub async fn list(db: &ClickHouse) -> Result<Vec<BackfillJob>> {
+    db.query_rows(
+        "SELECT job_id, argMax(symbol, updated_at) AS symbol, \
+         argMax(interval, updated_at) AS interval, argMax(from, updated_at) AS from, \
+         argMax(to, updated_at) AS to, argMax(priority, updated_at) AS priority, \
+         argMax(status, updated_at) AS status, argMax(owner, updated_at) AS owner, \
+         argMax(detail, updated_at) AS detail, max(updated_at) AS updated_at \
+         FROM backfill_jobs GROUP BY job_id ORDER BY job_id",
+    )
+    .await
+}
+
+/// Record a status transition.
+pub async fn mark(
+    db: &ClickHouse,
+    job: &BackfillJob,
+    status: &str,
+    owner: &str,
+    detail: &str,
+    now: i64,
+) -> Result<()> {
+    let mut updated = job.clone();
+    updated.status = status.to_string();
+    updated.owner = owner.to_string();
+    updated.detail = detail.to_string();
+    updated.updated_at = now;
+    enqueue(db, &updated).await
+}
+
+/// Try to claim `job` for `worker`: write it in as the running owner,
+/// then re-read the row to confirm the write actually won the race
+/// against any other worker claiming the same job at the same time.
+async fn try_claim(db: &ClickHouse, job: &BackfillJob, worker: &str, now: i64) -> Result<bool> {
+    mark(db, job, "running", worker, "", now).await?;
+    let confirmed = list(db).await?;
+    Ok(confirmed
+        .iter()
+        .any(|j| j.job_id == job.job_id && j.status == "running" && j.owner == worker))
+}
+
+/// Work pending (and abandoned) jobs until the queue is empty or
+/// `max_jobs` is hit; returns jobs completed.
+pub async fn work(
+    db: &ClickHouse,
+    exchange: &Binance,
+    http: &reqwest::Client,
+    sinks: &mut [Box<dyn Sink>],
+    max_jobs: usize,
+    audit: &crate::audit::Audit,
+    worker: &str,
+) -> Result<usize> {
+    let mut completed = 0;
+    let mut contested = std::collections::HashSet::new();
+    while completed < max_jobs {
+        let now = chrono::Utc::now().timestamp_millis();
+        let jobs = list(db).await?;
+        let candidates: Vec<BackfillJob> =
+            jobs.into_iter().filter(|j| !contested.contains(&j.job_id)).collect();
+        let Some(job) = pick_next(&candidates, now).cloned() else {
+            break;
+        };
+        if !try_claim(db, &job, worker, now).await? {
+            // Another worker's claim landed first; leave it alone and
+            // try the next job this round.
+            tracing::info!(job = job.job_id, worker, "backfill claim lost the race; skipping");
+            contested.insert(job.job_id.clone());
+            continue;
+        }
+        audit
+            .record("backfill_started", &job.symbol, &format!("job {} {}..{}", job.job_id, job.from, job.to))
+            .await;
+        match run_job(&job, exchange, http, sinks).await {
+            Ok(rows) => {
+                let now = chrono::Utc::now().timestamp_millis();
+                mark(db, &job, "done", worker, &format!("{rows} rows"), now).await?;
+                audit
+                    .record("backfill_finished", &job.symbol, &format!("job {} {rows} rows", job.job_id))
+                    .await;
+                completed += 1;
+            }
+            Err(e) => {
+                let now = chrono::Utc::now().timestamp_millis();
+                mark(db, &job, "failed", worker, &e.to_string(), now).await?;
+                audit
+                    .record("backfill_failed", &job.symbol, &format!("job {}: {e}", job.job_id))
+                    .await;
+                completed += 1;
+            }
+        }
+    }
+    Ok(completed)
+}
+
+async fn run_job(
+    job: &BackfillJob,
+    exchange: &Binance,
+    http: &reqwest::Client,
+    sinks: &mut [Box<dyn Sink>],
+) -> Result<usize> {
+    let step = job.interval.ms();
+    let mut cursor = job.from;
+    let mut rows = 0;
+    for _ in 0..100_000 {
+        if cursor >= job.to {
+            break;
+        }
+        let klines = exchange
+            .fetch_klines(
+                http,
+                &job.symbol,
+                job.interval.as_str(),
+                Some(cursor),
+                Some(job.to),
+                1000,
+            )
+            .await?;
+        if klines.is_empty() {
+            break;
+        }
+        cursor = klines.last().expect("non-empty").open_time + step;
+        rows += klines.len();
+        let events: Vec<Event> = klines.into_iter().map(Event::Kline).collect();
+        for sink in sinks.iter_mut() {
+            sink.write(&events).await?;
+        }
+        // Stay under the global request budget.
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    }
+    for sink in sinks.iter_mut() {
+        sink.flush().await?;
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_are_deterministic_and_selection_is_prioritized() {
+        let a = BackfillJob::new("btcusdt", Interval::M1, 0, 100, 1, 0);
+        let b = BackfillJob::new("BTCUSDT", Interval::M1, 0, 100, 5, 99);
+        assert_eq!(a.job_id, b.job_id);
+
+        let mut done = BackfillJob::new("ETHUSDT", Interval::M1, 0, 100, 99, 0);
+        done.status = "done".to_string();
+        let low_early = BackfillJob::new("AUSDT", Interval::M1, 0, 100, 1, 0);
+        let high_late = BackfillJob::new("BUSDT", Interval::M1, 500, 600, 5, 0);
+        let high_early = BackfillJob::new("CUSDT", Interval::M1, 100, 200, 5, 0);
+        let jobs = vec![done, low_early, high_late, high_early.clone()];
+        assert_eq!(pick_next(&jobs, 0), Some(&high_early));
+        assert!(pick_next(&[], 0).is_none());
+    }
+
+    #[test]
+    fn stale_running_job_is_reclaimed_after_its_lease_expires() {
+        let mut stuck = BackfillJob::new("BTCUSDT", Interval::M1, 0, 100, 1, 0);
+        stuck.status = "running".to_string();
+        stuck.owner = "dead-worker-1".to_string();
+        stuck.updated_at = 0;
+        let jobs = vec![stuck.clone()];
+        // Lease hasn't expired yet: nothing to pick.
+        assert!(pick_next(&jobs, RUN_LEASE_MS).is_none());
+        // Lease expired: the abandoned job is claimable again.
+        assert_eq!(pick_next(&jobs, RUN_LEASE_MS + 1), Some(&stuck));
+    }
+}
@@ -0,0 +1,61 @@
+//! Bi-temporal candle storage.
+//!
+//! When a candle is re-downloaded differently than originally stored
+//! (an exchange correction, a repaired gap), overwriting destroys the
+//! history a backtest actually saw. With `[clickhouse] bitemporal`
+//! enabled, every kline insert also lands in `klines_bitemporal` with
+//! an `ingested_at` column, and two views answer either question:
+//! `klines_latest` (current best knowledge) and `klines_original` (as
+//! first recorded).
+
+/// DDL for the bi-temporal table and its two views.
+pub fn schema() -> Vec<String> {
+    let agg = |func: &str| -> String {
+        [
+            "close_time", "open", "high", "low", "close", "volume", "quote_volume", "trade_count",
+        ]
+        .iter()
+        .map(|col| format!("{func}({col}, ingested_at) AS {col}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+    vec![
+        "CREATE TABLE IF NOT EXISTS klines_bitemporal (\
+         symbol String, interval String, \
+         open_time DateTime64(3, 'UTC'), close_time DateTime64(3, 'UTC'), \
+         open Float64, high Float64, low Float64, close Float64, \
+         volume Float64, quote_volume Float64, trade_count Int64, \
+         ingested_at Int64) \
+         ENGINE = MergeTree ORDER BY (symbol, interval, open_time, ingested_at)"
+            .to_string(),
+        format!(
+            "CREATE VIEW IF NOT EXISTS klines_latest AS \
+             SELECT symbol, interval, open_time, {} \
+             FROM klines_bitemporal GROUP BY symbol, interval, open_time",
+            agg("argMax")
+        ),
+        format!(
+            "CREATE VIEW IF NOT EXISTS klines_original AS \
+             SELECT symbol, interval, open_time, {} \
+             FROM klines_bitemporal GROUP BY symbol, interval, open_time",
+            agg("argMin")
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_defines_table_and_both_views() {
+        let ddl = schema();
+        assert_eq!(ddl.len(), 3);
+        assert!(ddl[0].contains("klines_bitemporal"));
+        assert!(ddl[0].contains("ingested_at Int64"));
+        assert!(ddl[1].contains("klines_latest"));
+        assert!(ddl[1].contains("argMax(close, ingested_at) AS close"));
+        assert!(ddl[2].contains("klines_original"));
+        assert!(ddl[2].contains("argMin(open, ingested_at) AS open"));
+    }
+}
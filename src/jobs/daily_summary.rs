@@ -0,0 +1,63 @@
+//! Per-symbol daily summary rollup.
+//!
+//! Screening queries should not scan raw 1m data: this job folds each
+//! UTC day into one compact row per symbol — OHLC, volume, trade
+//! count, VWAP, candle count and completeness — kept in
+//! `daily_summary` and replaced idempotently on re-runs.
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// DDL for the summary table.
+pub fn schema() -> &'static str {
+    "CREATE TABLE IF NOT EXISTS daily_summary (\
+     day Int64, symbol String, open Float64, high Float64, low Float64, \
+     close Float64, volume Float64, quote_volume Float64, trade_count Int64, \
+     vwap Float64, candles UInt32, completeness_pct Float64) \
+     ENGINE = ReplacingMergeTree ORDER BY (symbol, day)"
+}
+
+/// The rollup statement for one `[from, to)` window, aligned to days.
+pub fn plan(from: i64, to: i64) -> String {
+    let from = from - from.rem_euclid(DAY_MS);
+    format!(
+        "INSERT INTO daily_summary \
+         SELECT intDiv(toUnixTimestamp64Milli(open_time), {DAY_MS}) * {DAY_MS} AS day, symbol, \
+                argMin(open, open_time) AS open, max(high) AS high, \
+                min(low) AS low, argMax(close, open_time) AS close, \
+                sum(volume) AS volume, sum(quote_volume) AS quote_volume, \
+                sum(trade_count) AS trade_count, \
+                if(sum(volume) > 0, sum(quote_volume) / sum(volume), 0) AS vwap, \
+                toUInt32(count()) AS candles, \
+                count() * 100.0 / 1440 AS completeness_pct \
+         FROM klines \
+         WHERE interval = '1m' AND open_time >= {} AND open_time < {} \
+         GROUP BY symbol, day",
+        crate::db::dt64_literal(from),
+        crate::db::dt64_literal(to)
+    )
+}
+
+/// Run the rollup for one window.
+pub async fn run(db: &ClickHouse, from: i64, to: i64) -> Result<()> {
+    db.execute(schema()).await?;
+    db.execute(&plan(from, to)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_groups_by_day_and_symbol() {
+        let sql = plan(1_700_000_123_000, 1_700_100_000_000);
+        assert!(sql.contains("GROUP BY symbol, day"));
+        assert!(sql.contains("interval = '1m'"));
+        // The window start is aligned down to a day boundary.
+        assert!(sql.contains("open_time >= fromUnixTimestamp64Milli(1699920000000)"));
+        assert!(sql.contains("/ 1440"));
+        assert!(sql.contains("argMin(open, open_time)"));
+    }
+}
@@ -0,0 +1,89 @@
+//! Storage-reduction downsampling.
+//!
+//! Raw ticks are only irreplaceable for a while; past the configured
+//! age they are folded into 1s candles (kept in the normal `klines`
+//! table under the `1s` interval) and the raw rows are dropped. The
+//! whole job is expressed as ClickHouse SQL so the data never round
+//! trips through the collector.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+/// The `[downsample]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownsampleConfig {
+    /// Age in days after which raw trades are downsampled and dropped.
+    #[serde(default = "default_max_age_days")]
+    pub raw_trades_max_age_days: u32,
+}
+
+fn default_max_age_days() -> u32 {
+    30
+}
+
+impl Default for DownsampleConfig {
+    fn default() -> Self {
+        Self {
+            raw_trades_max_age_days: default_max_age_days(),
+        }
+    }
+}
+
+/// The statements one downsampling run executes, in order.
+pub fn plan(cutoff_ms: i64) -> Vec<String> {
+    vec![
+        format!(
+            "INSERT INTO klines \
+             (symbol, interval, open_time, close_time, open, high, low, close, \
+              volume, quote_volume, trade_count) \
+             SELECT symbol, '1s', \
+                    fromUnixTimestamp64Milli(bucket_ms) AS bucket, \
+                    fromUnixTimestamp64Milli(bucket_ms + 999), \
+                    argMin(price, (trade_time, trade_id)), \
+                    max(price), min(price), \
+                    argMax(price, (trade_time, trade_id)), \
+                    sum(qty), sum(price * qty), count() \
+             FROM (SELECT *, intDiv(toUnixTimestamp64Milli(trade_time), 1000) * 1000 AS bucket_ms \
+                   FROM trades WHERE trade_time < {cutoff}) \
+             GROUP BY symbol, bucket_ms",
+            cutoff = crate::db::dt64_literal(cutoff_ms)
+        ),
+        format!(
+            "ALTER TABLE trades DELETE WHERE trade_time < {}",
+            crate::db::dt64_literal(cutoff_ms)
+        ),
+    ]
+}
+
+/// Run one downsampling pass against the database. `now_ms` anchors the
+/// age cutoff so the job is deterministic under test.
+pub async fn run(db: &ClickHouse, cfg: &DownsampleConfig, now_ms: i64) -> Result<i64> {
+    let cutoff = now_ms - i64::from(cfg.raw_trades_max_age_days) * 86_400_000;
+    for sql in plan(cutoff) {
+        db.execute(&sql).await?;
+    }
+    Ok(cutoff)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_downsamples_then_deletes() {
+        let statements = plan(1_700_000_000_000);
+        assert_eq!(statements.len(), 2);
+        assert!(statements[0].starts_with("INSERT INTO klines"));
+        assert!(statements[0].contains("'1s'"));
+        assert!(statements[0].contains("trade_time < fromUnixTimestamp64Milli(1700000000000)"));
+        assert!(statements[1].starts_with("ALTER TABLE trades DELETE"));
+        assert!(statements[1].contains("fromUnixTimestamp64Milli(1700000000000)"));
+    }
+
+    #[test]
+    fn default_age_is_thirty_days() {
+        assert_eq!(DownsampleConfig::default().raw_trades_max_age_days, 30);
+    }
+}
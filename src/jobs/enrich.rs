@@ -0,0 +1,148 @@
+//! Asset metadata enrichment.
+//!
+//! An optional job that pulls asset names, ranks and circulating
+//! supply from CoinGecko into an `asset_metadata` table keyed by base
+//! asset, enabling sector- and cap-weighted analysis on top of the
+//! price archive.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+
+/// The `[enrich]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnrichConfig {
+    /// Metadata API base; CoinGecko-compatible.
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// Pages of 250 assets to fetch, by descending market cap.
+    #[serde(default = "default_pages")]
+    pub pages: u32,
+}
+
+fn default_base_url() -> String {
+    "https://api.coingecko.com/api/v3".to_string()
+}
+
+fn default_pages() -> u32 {
+    4
+}
+
+impl Default for EnrichConfig {
+    fn default() -> Self {
+        Self {
+            base_url: default_base_url(),
+            pages: default_pages(),
+        }
+    }
+}
+
+/// One asset's metadata row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssetMetadata {
+    /// Fetch timestamp in epoch milliseconds.
+    pub fetched_at: i64,
+    /// Uppercase asset code, e.g. `BTC`.
+    pub asset: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Market cap rank (0 when unknown).
+    pub rank: i64,
+    /// Market cap in USD (0 when unknown).
+    pub market_cap: f64,
+    /// Circulating supply (0 when unknown).
+    pub circulating_supply: f64,
+}
+
+/// Parse one `coins/markets` page.
+pub fn parse_markets_page(body: &Value, fetched_at: i64) -> Result<Vec<AssetMetadata>> {
+    let rows = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("markets response not an array: {body}")))?;
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            Some(AssetMetadata {
+                fetched_at,
+                asset: row.get("symbol")?.as_str()?.to_uppercase(),
+                name: row.get("name")?.as_str()?.to_string(),
+                rank: row
+                    .get("market_cap_rank")
+                    .and_then(Value::as_i64)
+                    .unwrap_or(0),
+                market_cap: row
+                    .get("market_cap")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0),
+                circulating_supply: row
+                    .get("circulating_supply")
+                    .and_then(Value::as_f64)
+                    .unwrap_or(0.0),
+            })
+        })
+        .collect())
+}
+
+/// Create the `asset_metadata` table.
+pub async fn ensure_schema(db: &ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS asset_metadata (\
+         fetched_at Int64, asset String, name String, rank Int64, \
+         market_cap Float64, circulating_supply Float64) \
+         ENGINE = ReplacingMergeTree(fetched_at) ORDER BY asset",
+    )
+    .await
+}
+
+/// Run one enrichment pass; returns rows written.
+pub async fn run(http: &reqwest::Client, db: &ClickHouse, cfg: &EnrichConfig) -> Result<usize> {
+    ensure_schema(db).await?;
+    let fetched_at = chrono::Utc::now().timestamp_millis();
+    let mut total = 0;
+    for page in 1..=cfg.pages {
+        let body: Value = http
+            .get(format!("{}/coins/markets", cfg.base_url))
+            .query(&[
+                ("vs_currency", "usd"),
+                ("per_page", "250"),
+                ("page", &page.to_string()),
+                ("order", "market_cap_desc"),
+            ])
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("markets request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("markets response invalid: {e}")))?;
+        let rows = parse_markets_page(&body, fetched_at)?;
+        if rows.is_empty() {
+            break;
+        }
+        total += rows.len();
+        db.insert_rows("asset_metadata", &rows).await?;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_markets_page() {
+        let body = serde_json::json!([
+            {"symbol": "btc", "name": "Bitcoin", "market_cap_rank": 1,
+             "market_cap": 1.0e12, "circulating_supply": 1.96e7},
+            {"symbol": "eth", "name": "Ethereum", "market_cap_rank": 2,
+             "market_cap": null, "circulating_supply": null},
+        ]);
+        let rows = parse_markets_page(&body, 42).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].asset, "BTC");
+        assert_eq!(rows[0].rank, 1);
+        assert_eq!(rows[1].market_cap, 0.0);
+        assert!(parse_markets_page(&serde_json::json!({"error": "x"}), 0).is_err());
+    }
+}
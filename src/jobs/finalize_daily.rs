@@ -0,0 +1,166 @@
+//! End-of-day candle finalization.
+//!
+//! The daily candle built live from streaming trades can differ
+//! slightly from what the exchange later serves for the same closed
+//! day — a late trade correction, a brief gap patched after the fact.
+//! Once a UTC day has fully closed, this job re-fetches that day's
+//! `1d` candle straight from the exchange, logs how it differs from
+//! whatever was stored during the day, and overwrites the stored row
+//! (`klines` is a `ReplacingMergeTree`, so the re-inserted row wins on
+//! the next merge). It then marks the day finalized in
+//! `daily_finalized` and checks that table before reprocessing a day,
+//! so a closed day is re-fetched and logged at most once.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::exchange::binance::Binance;
+use crate::exchange::Exchange;
+use crate::model::Kline;
+use crate::pipeline::Event;
+use crate::sink::clickhouse::ClickHouseSink;
+use crate::sink::Sink;
+
+const DAY_MS: i64 = 86_400_000;
+
+/// The `[finalize_daily]` config section; enabled whenever present.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FinalizeDailyConfig {
+    /// Symbols to finalize once each UTC day closes.
+    pub symbols: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct FinalizedMarker {
+    day: i64,
+    symbol: String,
+    finalized_at: i64,
+}
+
+/// DDL for the finalization marker table.
+pub fn schema() -> &'static str {
+    "CREATE TABLE IF NOT EXISTS daily_finalized (\
+     day Int64, symbol String, finalized_at Int64) \
+     ENGINE = ReplacingMergeTree ORDER BY (symbol, day)"
+}
+
+/// Whether the UTC day starting at `day_start` has fully closed as of
+/// `now_ms`, i.e. it is safe to treat its candle as final.
+pub fn day_has_closed(day_start: i64, now_ms: i64) -> bool {
+    now_ms >= day_start + DAY_MS
+}
+
+/// Whether `symbol`/`day_start` has already been finalized.
+async fn is_finalized(db: &ClickHouse, symbol: &str, day_start: i64) -> Result<bool> {
+    db.execute(schema()).await?;
+    let count: u64 = db
+        .query_scalar(&format!(
+            "SELECT count() FROM daily_finalized WHERE symbol = '{symbol}' AND day = {day_start}"
+        ))
+        .await?
+        .trim()
+        .parse()
+        .unwrap_or(0);
+    Ok(count > 0)
+}
+
+/// The stored `1d` candle for `symbol`/`day_start`, if any.
+async fn stored_candle(db: &ClickHouse, symbol: &str, day_start: i64) -> Result<Option<Kline>> {
+    let rows: Vec<Kline> = db
+        .query_rows(&format!(
+            "SELECT * FROM klines WHERE symbol = '{symbol}' AND interval = '1d' \
+             AND open_time = {day_start}"
+        ))
+        .await?;
+    Ok(rows.into_iter().next())
+}
+
+/// Re-fetch and store the final `1d` candle for one symbol/day, logging
+/// how it differs from what was stored during the day, then mark it
+/// finalized. Returns `None` without marking anything if the exchange
+/// has nothing for that day (a delisted symbol, for example) or if the
+/// day was already finalized.
+pub async fn finalize_symbol(
+    db: &ClickHouse,
+    exchange: &Binance,
+    http: &reqwest::Client,
+    symbol: &str,
+    day_start: i64,
+    now_ms: i64,
+) -> Result<Option<Kline>> {
+    let symbol = symbol.to_uppercase();
+    if is_finalized(db, &symbol, day_start).await? {
+        return Ok(None);
+    }
+    let previous = stored_candle(db, &symbol, day_start).await?;
+    let candles = exchange
+        .fetch_klines(http, &symbol, "1d", Some(day_start), Some(day_start + DAY_MS), 1)
+        .await?;
+    let Some(kline) = candles.into_iter().next() else {
+        return Ok(None);
+    };
+    match &previous {
+        Some(prev) if prev.close != kline.close || prev.volume != kline.volume => {
+            tracing::info!(
+                symbol,
+                day_start,
+                prev_close = prev.close,
+                final_close = kline.close,
+                prev_volume = prev.volume,
+                final_volume = kline.volume,
+                "finalize: closing candle differs from what was stored during the day"
+            );
+        }
+        Some(_) => {
+            tracing::debug!(symbol, day_start, "finalize: stored candle already matched");
+        }
+        None => {
+            tracing::info!(symbol, day_start, "finalize: no candle was stored during the day");
+        }
+    }
+    let mut sink = ClickHouseSink::new(db.clone());
+    sink.write(&[Event::Kline(kline.clone())]).await?;
+    sink.flush().await?;
+    db.insert_rows(
+        "daily_finalized",
+        &[FinalizedMarker {
+            day: day_start,
+            symbol,
+            finalized_at: now_ms,
+        }],
+    )
+    .await?;
+    Ok(Some(kline))
+}
+
+/// Finalize every configured symbol for the UTC day starting at
+/// `day_start`, skipping any symbol already finalized for that day.
+/// Callers are expected to check [`day_has_closed`] before scheduling
+/// this.
+pub async fn run(
+    db: &ClickHouse,
+    exchange: &Binance,
+    http: &reqwest::Client,
+    cfg: &FinalizeDailyConfig,
+    day_start: i64,
+    now_ms: i64,
+) -> Result<()> {
+    for symbol in &cfg.symbols {
+        finalize_symbol(db, exchange, http, symbol, day_start, now_ms).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn day_closes_exactly_one_day_after_it_starts() {
+        let day_start = 1_700_000_000_000 - 1_700_000_000_000 % DAY_MS;
+        assert!(!day_has_closed(day_start, day_start + DAY_MS - 1));
+        assert!(day_has_closed(day_start, day_start + DAY_MS));
+        assert!(day_has_closed(day_start, day_start + DAY_MS + 60_000));
+    }
+}
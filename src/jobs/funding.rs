@@ -0,0 +1,174 @@
+//! Funding payment schedule tracking for perp symbols.
+//!
+//! Polls the futures API for realized funding payments (kept in a
+//! normalized `funding` calendar table) and upcoming funding times,
+//! emitting an alert shortly before each funding timestamp.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+use crate::pipeline::Alert;
+
+/// The `[funding]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingConfig {
+    /// Perp symbols to track.
+    pub symbols: Vec<String>,
+    /// Futures REST host.
+    #[serde(default = "default_fapi_url")]
+    pub fapi_url: String,
+    /// Alert this many seconds before each funding timestamp.
+    #[serde(default = "default_pre_alert_secs")]
+    pub pre_alert_secs: u64,
+}
+
+fn default_fapi_url() -> String {
+    "https://fapi.binance.com".to_string()
+}
+
+fn default_pre_alert_secs() -> u64 {
+    300
+}
+
+/// One realized funding payment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FundingPayment {
+    /// Perp symbol.
+    pub symbol: String,
+    /// Funding timestamp in epoch milliseconds.
+    pub funding_time: i64,
+    /// Funding rate applied at that timestamp.
+    pub funding_rate: f64,
+}
+
+/// Parse a `/fapi/v1/fundingRate` response.
+pub fn parse_funding_history(body: &Value) -> Result<Vec<FundingPayment>> {
+    let rows = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("fundingRate response not an array: {body}")))?;
+    rows.iter()
+        .map(|row| {
+            Ok(FundingPayment {
+                symbol: row
+                    .get("symbol")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| Error::Exchange(format!("funding row without symbol: {row}")))?
+                    .to_string(),
+                funding_time: row
+                    .get("fundingTime")
+                    .and_then(Value::as_i64)
+                    .ok_or_else(|| Error::Exchange(format!("funding row without time: {row}")))?,
+                funding_rate: row
+                    .get("fundingRate")
+                    .and_then(Value::as_str)
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(|| Error::Exchange(format!("funding row without rate: {row}")))?,
+            })
+        })
+        .collect()
+}
+
+/// Parse the next funding time from a `/fapi/v1/premiumIndex` response.
+pub fn parse_next_funding_time(body: &Value) -> Option<i64> {
+    body.get("nextFundingTime").and_then(Value::as_i64)
+}
+
+/// An alert if `next_funding_time` falls within the pre-alert window.
+pub fn upcoming_alert(
+    symbol: &str,
+    next_funding_time: i64,
+    now_ms: i64,
+    pre_alert_secs: u64,
+) -> Option<Alert> {
+    let lead = next_funding_time - now_ms;
+    if lead <= 0 || lead > (pre_alert_secs as i64) * 1000 {
+        return None;
+    }
+    Some(Alert {
+        symbol: symbol.to_string(),
+        source: "funding".to_string(),
+        message: format!("funding in {}s", lead / 1000),
+        time: now_ms,
+    })
+}
+
+/// Create the funding calendar table.
+pub async fn ensure_schema(db: &ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS funding (\
+         symbol String, funding_time Int64, funding_rate Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (symbol, funding_time)",
+    )
+    .await
+}
+
+/// One polling pass: persist realized payments and return alerts for
+/// imminent funding timestamps.
+pub async fn run_once(
+    http: &reqwest::Client,
+    db: Option<&ClickHouse>,
+    cfg: &FundingConfig,
+    now_ms: i64,
+) -> Result<Vec<Alert>> {
+    let mut alerts = Vec::new();
+    for symbol in &cfg.symbols {
+        let symbol = symbol.to_uppercase();
+        let history: Value = http
+            .get(format!("{}/fapi/v1/fundingRate", cfg.fapi_url))
+            .query(&[("symbol", symbol.as_str()), ("limit", "1000")])
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("fundingRate request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("fundingRate response invalid: {e}")))?;
+        let payments = parse_funding_history(&history)?;
+        if let Some(db) = db {
+            db.insert_rows("funding", &payments).await?;
+        }
+        let premium: Value = http
+            .get(format!("{}/fapi/v1/premiumIndex", cfg.fapi_url))
+            .query(&[("symbol", symbol.as_str())])
+            .send()
+            .await
+            .map_err(|e| Error::Exchange(format!("premiumIndex request failed: {e}")))?
+            .json()
+            .await
+            .map_err(|e| Error::Exchange(format!("premiumIndex response invalid: {e}")))?;
+        if let Some(next) = parse_next_funding_time(&premium) {
+            alerts.extend(upcoming_alert(&symbol, next, now_ms, cfg.pre_alert_secs));
+        }
+    }
+    Ok(alerts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_funding_history() {
+        let body = serde_json::json!([
+            {"symbol": "BTCUSDT", "fundingTime": 1_700_000_000_000i64, "fundingRate": "0.00010000"},
+            {"symbol": "BTCUSDT", "fundingTime": 1_700_028_800_000i64, "fundingRate": "-0.00005000"},
+        ]);
+        let payments = parse_funding_history(&body).unwrap();
+        assert_eq!(payments.len(), 2);
+        assert!((payments[1].funding_rate + 0.00005).abs() < 1e-12);
+        assert!(parse_funding_history(&serde_json::json!({"code": -1})).is_err());
+    }
+
+    #[test]
+    fn alerts_only_inside_the_lead_window() {
+        // 10 minutes out with a 5-minute window: quiet.
+        assert!(upcoming_alert("BTCUSDT", 600_000, 0, 300).is_none());
+        // 4 minutes out: alert.
+        let alert = upcoming_alert("BTCUSDT", 240_000, 0, 300).unwrap();
+        assert_eq!(alert.source, "funding");
+        assert!(alert.message.contains("240s"));
+        // Already past: quiet.
+        assert!(upcoming_alert("BTCUSDT", -1, 0, 300).is_none());
+    }
+}
@@ -0,0 +1,116 @@
+//! Multi-assets index composition collection.
+//!
+//! Snapshots the futures `indexInfo` endpoint — which indices exist
+//! and each constituent's weight — into a versioned table, so stored
+//! mark-price and index series can be decomposed and audited later.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+
+/// One constituent's weight in one index at one time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexComponentRow {
+    /// Index symbol, e.g. `DEFIUSDT`.
+    pub index_symbol: String,
+    /// Snapshot timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Constituent base asset.
+    pub asset: String,
+    /// Weight in quantity terms.
+    pub weight_qty: f64,
+    /// Weight in percentage terms.
+    pub weight_pct: f64,
+}
+
+/// Parse an `indexInfo` response into component rows.
+pub fn parse_index_info(body: &Value) -> Result<Vec<IndexComponentRow>> {
+    let indices = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("indexInfo response not an array: {body}")))?;
+    let mut rows = Vec::new();
+    for index in indices {
+        let index_symbol = index
+            .get("symbol")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Exchange(format!("index without symbol: {index}")))?;
+        let time = index.get("time").and_then(Value::as_i64).unwrap_or(0);
+        let components = index
+            .get("baseAssetList")
+            .and_then(Value::as_array)
+            .ok_or_else(|| Error::Exchange(format!("index without baseAssetList: {index}")))?;
+        for component in components {
+            let str_num = |key: &str| -> f64 {
+                component
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(0.0)
+            };
+            rows.push(IndexComponentRow {
+                index_symbol: index_symbol.to_string(),
+                time,
+                asset: component
+                    .get("baseAsset")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                weight_qty: str_num("weightInQuantity"),
+                weight_pct: str_num("weightInPercentage"),
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Create the composition table.
+pub async fn ensure_schema(db: &ClickHouse) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS index_composition (\
+         index_symbol String, time Int64, asset String, \
+         weight_qty Float64, weight_pct Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (index_symbol, asset, time)",
+    )
+    .await
+}
+
+/// One snapshot pass; returns rows written.
+pub async fn run_once(http: &reqwest::Client, db: &ClickHouse, fapi_url: &str) -> Result<usize> {
+    let body: Value = http
+        .get(format!("{fapi_url}/fapi/v1/indexInfo"))
+        .send()
+        .await
+        .map_err(|e| Error::Exchange(format!("indexInfo request failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| Error::Exchange(format!("indexInfo response invalid: {e}")))?;
+    let rows = parse_index_info(&body)?;
+    db.insert_rows("index_composition", &rows).await?;
+    Ok(rows.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_index_components() {
+        let body = serde_json::json!([
+            {"symbol": "DEFIUSDT", "time": 1_700_000_000_000i64, "component": "baseAsset",
+             "baseAssetList": [
+                {"baseAsset": "UNI", "quoteAsset": "USDT",
+                 "weightInQuantity": "12.5", "weightInPercentage": "0.25"},
+                {"baseAsset": "AAVE", "quoteAsset": "USDT",
+                 "weightInQuantity": "1.5", "weightInPercentage": "0.75"},
+             ]}
+        ]);
+        let rows = parse_index_info(&body).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].index_symbol, "DEFIUSDT");
+        assert_eq!(rows[1].asset, "AAVE");
+        assert_eq!(rows[1].weight_pct, 0.75);
+        assert!(parse_index_info(&serde_json::json!({"code": -1})).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! New-listing detection and automatic onboarding.
+//!
+//! The collector polls `exchangeInfo`, diffs the filtered symbol
+//! universe against what it already knows, and onboards anything new:
+//! backfill from the first candle, then subscribe the live stream — so
+//! new coins are captured from their first minute.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::exchange::symbols::{filter_pairs, PairsFilter, SymbolInfo};
+use crate::error::Result;
+
+/// The `[listings]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListingsConfig {
+    /// Poll period in seconds.
+    #[serde(default = "default_poll_secs")]
+    pub poll_secs: u64,
+    /// Pipeline template for onboarded symbols; `{symbol}` is replaced
+    /// with the lowercase symbol, e.g.
+    /// `"{symbol}@trade -> candle_builder(1m) -> [clickhouse, csv]"`.
+    pub pipeline: String,
+    /// Which symbols qualify for auto-onboarding.
+    #[serde(default)]
+    pub filter: PairsFilter,
+}
+
+fn default_poll_secs() -> u64 {
+    300
+}
+
+/// Tracks the known universe and surfaces newly listed symbols.
+#[derive(Debug, Default)]
+pub struct ListingWatcher {
+    known: HashSet<String>,
+    primed: bool,
+}
+
+impl ListingWatcher {
+    /// Create an empty watcher. The first poll primes the known set
+    /// without reporting anything as new.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forget a symbol (after delisting) so a future relisting counts
+    /// as new again.
+    pub fn forget(&mut self, symbol: &str) {
+        self.known.remove(symbol);
+    }
+
+    /// Diff one exchangeInfo snapshot against the known set, returning
+    /// newly listed symbols that pass the filter.
+    pub fn diff<'a>(
+        &mut self,
+        universe: &'a [SymbolInfo],
+        filter: &PairsFilter,
+    ) -> Result<Vec<&'a SymbolInfo>> {
+        let matching = filter_pairs(universe, filter)?;
+        let mut fresh = Vec::new();
+        for info in matching {
+            if self.known.insert(info.symbol.clone()) && self.primed {
+                fresh.push(info);
+            }
+        }
+        self.primed = true;
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(symbol: &str) -> SymbolInfo {
+        SymbolInfo {
+            symbol: symbol.to_string(),
+            status: "TRADING".to_string(),
+            base_asset: symbol.trim_end_matches("USDT").to_string(),
+            quote_asset: "USDT".to_string(),
+            permissions: Vec::new(),
+            tick_size: None,
+            step_size: None,
+            min_notional: None,
+        }
+    }
+
+    #[test]
+    fn first_poll_primes_later_polls_report_new() {
+        let mut watcher = ListingWatcher::new();
+        let filter = PairsFilter::default();
+        let first = vec![info("BTCUSDT"), info("ETHUSDT")];
+        assert!(watcher.diff(&first, &filter).unwrap().is_empty());
+        let second = vec![info("BTCUSDT"), info("ETHUSDT"), info("NEWUSDT")];
+        let fresh = watcher.diff(&second, &filter).unwrap();
+        assert_eq!(fresh.len(), 1);
+        assert_eq!(fresh[0].symbol, "NEWUSDT");
+        // Already onboarded symbols stay quiet.
+        assert!(watcher.diff(&second, &filter).unwrap().is_empty());
+    }
+
+    #[test]
+    fn filter_excludes_non_matching_listings() {
+        let mut watcher = ListingWatcher::new();
+        let filter = PairsFilter::default();
+        watcher.diff(&[info("BTCUSDT")], &filter).unwrap();
+        let mut halted = info("HALTUSDT");
+        halted.status = "BREAK".to_string();
+        assert!(watcher.diff(&[info("BTCUSDT"), halted], &filter).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,15 @@
+//! Scheduled maintenance jobs.
+
+pub mod backfill_queue;
+pub mod bitemporal;
+pub mod daily_summary;
+pub mod downsample;
+pub mod enrich;
+pub mod finalize_daily;
+pub mod funding;
+pub mod index_info;
+pub mod listings;
+pub mod retention;
+pub mod sentiment;
+pub mod turnover;
+pub mod usd_reference;
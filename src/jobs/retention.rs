@@ -0,0 +1,129 @@
+//! Retention cleanup for the local `data/` tree.
+//!
+//! Daily CSV files older than the configured age are deleted (the
+//! database keeps the canonical copy); `.state` checkpoints and
+//! non-daily files are never touched. A dry run lists what would go.
+
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// The `[retention]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Days of daily files to keep.
+    #[serde(default = "default_keep_days")]
+    pub keep_days: u32,
+}
+
+fn default_keep_days() -> u32 {
+    30
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            keep_days: default_keep_days(),
+        }
+    }
+}
+
+/// The UTC day encoded in a daily file name
+/// (`<kind>-YYYY-MM-DD.csv`), if any.
+fn file_day(name: &str) -> Option<NaiveDate> {
+    let stem = name.strip_suffix(".csv")?;
+    if stem.len() < 10 {
+        return None;
+    }
+    NaiveDate::parse_from_str(&stem[stem.len() - 10..], "%Y-%m-%d").ok()
+}
+
+/// Files under `root` whose day is before the cutoff, sorted.
+pub fn plan(root: &Path, cutoff: NaiveDate) -> Result<Vec<PathBuf>> {
+    let mut doomed = Vec::new();
+    if !root.exists() {
+        return Ok(doomed);
+    }
+    for symbol_dir in std::fs::read_dir(root)? {
+        let symbol_dir = symbol_dir?;
+        if !symbol_dir.file_type()?.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(symbol_dir.path())? {
+            let path = file?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            if let Some(day) = file_day(name) {
+                if day < cutoff {
+                    doomed.push(path);
+                }
+            }
+        }
+    }
+    doomed.sort();
+    Ok(doomed)
+}
+
+/// Run one retention pass; returns (files deleted, bytes freed). With
+/// `dry_run` nothing is removed and the doomed files are printed.
+pub fn run(root: &Path, cfg: &RetentionConfig, today: NaiveDate, dry_run: bool) -> Result<(usize, u64)> {
+    let cutoff = today - chrono::Duration::days(i64::from(cfg.keep_days));
+    let doomed = plan(root, cutoff)?;
+    let mut bytes = 0;
+    for path in &doomed {
+        bytes += std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+        if dry_run {
+            println!("[dry-run] would delete {}", path.display());
+        } else {
+            std::fs::remove_file(path)?;
+            tracing::info!(path = %path.display(), "deleted by retention policy");
+        }
+    }
+    Ok((doomed.len(), bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_only_daily_files_past_cutoff() {
+        let root = std::env::temp_dir().join(format!("bzl-retention-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "trades-2023-10-01.csv",
+            "trades-2023-12-01.csv",
+            "klines-1m-2023-10-15.csv",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.join(name), "x").unwrap();
+        }
+        std::fs::write(root.join("BTCUSDT-1m.state"), "{}").unwrap();
+        let cutoff = NaiveDate::from_ymd_opt(2023, 11, 1).unwrap();
+        let doomed = plan(&root, cutoff).unwrap();
+        let names: Vec<String> = doomed
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["klines-1m-2023-10-15.csv", "trades-2023-10-01.csv"]);
+
+        // Dry run deletes nothing.
+        let cfg = RetentionConfig { keep_days: 30 };
+        let today = NaiveDate::from_ymd_opt(2023, 12, 1).unwrap();
+        let (n, _) = run(&root, &cfg, today, true).unwrap();
+        assert_eq!(n, 2);
+        assert!(dir.join("trades-2023-10-01.csv").exists());
+        // Real run removes them and keeps the rest.
+        run(&root, &cfg, today, false).unwrap();
+        assert!(!dir.join("trades-2023-10-01.csv").exists());
+        assert!(dir.join("trades-2023-12-01.csv").exists());
+        assert!(dir.join("notes.txt").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,326 @@
+//! Futures sentiment ratio collection.
+//!
+//! Polls the futures data endpoints at their native 5m granularity —
+//! global long/short account ratio, top trader long/short position
+//! ratio, and taker buy/sell volume ratio — into dedicated tables.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+
+/// The `[sentiment]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SentimentConfig {
+    /// Perp symbols to collect.
+    pub symbols: Vec<String>,
+    /// Futures REST host.
+    #[serde(default = "default_fapi_url")]
+    pub fapi_url: String,
+    /// Native granularity of the endpoints.
+    #[serde(default = "default_period")]
+    pub period: String,
+}
+
+fn default_fapi_url() -> String {
+    "https://fapi.binance.com".to_string()
+}
+
+fn default_period() -> String {
+    "5m".to_string()
+}
+
+/// One long/short ratio observation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LongShortRow {
+    /// Perp symbol.
+    pub symbol: String,
+    /// Observation timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Long/short ratio.
+    pub ratio: f64,
+    /// Long share (accounts or positions).
+    pub long_pct: f64,
+    /// Short share (accounts or positions).
+    pub short_pct: f64,
+}
+
+/// One taker buy/sell volume observation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TakerVolumeRow {
+    /// Perp symbol.
+    pub symbol: String,
+    /// Observation timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Buy/sell volume ratio.
+    pub ratio: f64,
+    /// Taker buy volume.
+    pub buy_vol: f64,
+    /// Taker sell volume.
+    pub sell_vol: f64,
+}
+
+fn num(row: &Value, key: &str) -> Result<f64> {
+    row.get(key)
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| Error::Exchange(format!("bad sentiment field `{key}`: {row}")))
+}
+
+fn time_of(row: &Value) -> Result<i64> {
+    row.get("timestamp")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| Error::Exchange(format!("sentiment row without timestamp: {row}")))
+}
+
+/// Parse a long/short ratio response (account or position flavours).
+pub fn parse_long_short(body: &Value) -> Result<Vec<LongShortRow>> {
+    let rows = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("sentiment response not an array: {body}")))?;
+    rows.iter()
+        .map(|row| {
+            Ok(LongShortRow {
+                symbol: row
+                    .get("symbol")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                time: time_of(row)?,
+                ratio: num(row, "longShortRatio")?,
+                long_pct: num(row, "longAccount")?,
+                short_pct: num(row, "shortAccount")?,
+            })
+        })
+        .collect()
+}
+
+/// Parse a taker buy/sell volume response.
+pub fn parse_taker_volume(symbol: &str, body: &Value) -> Result<Vec<TakerVolumeRow>> {
+    let rows = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("sentiment response not an array: {body}")))?;
+    rows.iter()
+        .map(|row| {
+            Ok(TakerVolumeRow {
+                symbol: symbol.to_string(),
+                time: time_of(row)?,
+                ratio: num(row, "buySellRatio")?,
+                buy_vol: num(row, "buyVol")?,
+                sell_vol: num(row, "sellVol")?,
+            })
+        })
+        .collect()
+}
+
+/// One open-interest observation, aligned to the same 5m timestamps
+/// as the position ratios.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpenInterestRow {
+    /// Perp symbol.
+    pub symbol: String,
+    /// Observation timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Open interest in base units.
+    pub open_interest: f64,
+    /// Open interest notional in quote units.
+    pub notional: f64,
+}
+
+/// Parse an `openInterestHist` response.
+pub fn parse_open_interest(body: &Value) -> Result<Vec<OpenInterestRow>> {
+    let rows = body
+        .as_array()
+        .ok_or_else(|| Error::Exchange(format!("openInterestHist response not an array: {body}")))?;
+    rows.iter()
+        .map(|row| {
+            Ok(OpenInterestRow {
+                symbol: row
+                    .get("symbol")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                time: time_of(row)?,
+                open_interest: num(row, "sumOpenInterest")?,
+                notional: num(row, "sumOpenInterestValue")?,
+            })
+        })
+        .collect()
+}
+
+/// Create the sentiment tables.
+pub async fn ensure_schema(db: &ClickHouse) -> Result<()> {
+    for ddl in [
+        "CREATE TABLE IF NOT EXISTS open_interest (\
+         symbol String, time Int64, open_interest Float64, notional Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (symbol, time)",
+        "CREATE TABLE IF NOT EXISTS global_long_short (\
+         symbol String, time Int64, ratio Float64, long_pct Float64, short_pct Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (symbol, time)",
+        "CREATE TABLE IF NOT EXISTS top_long_short_position (\
+         symbol String, time Int64, ratio Float64, long_pct Float64, short_pct Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (symbol, time)",
+        "CREATE TABLE IF NOT EXISTS taker_buy_sell (\
+         symbol String, time Int64, ratio Float64, buy_vol Float64, sell_vol Float64) \
+         ENGINE = ReplacingMergeTree ORDER BY (symbol, time)",
+    ] {
+        db.execute(ddl).await?;
+    }
+    Ok(())
+}
+
+async fn fetch(http: &reqwest::Client, url: &str, symbol: &str, period: &str) -> Result<Value> {
+    http.get(url)
+        .query(&[("symbol", symbol), ("period", period), ("limit", "500")])
+        .send()
+        .await
+        .map_err(|e| Error::Exchange(format!("sentiment request failed: {e}")))?
+        .json()
+        .await
+        .map_err(|e| Error::Exchange(format!("sentiment response invalid: {e}")))
+}
+
+/// One polling pass over all three endpoints; returns rows written.
+pub async fn run_once(
+    http: &reqwest::Client,
+    db: &ClickHouse,
+    cfg: &SentimentConfig,
+) -> Result<usize> {
+    let mut total = 0;
+    for symbol in &cfg.symbols {
+        let symbol = symbol.to_uppercase();
+        let global = parse_long_short(
+            &fetch(
+                http,
+                &format!("{}/futures/data/globalLongShortAccountRatio", cfg.fapi_url),
+                &symbol,
+                &cfg.period,
+            )
+            .await?,
+        )?;
+        db.insert_rows("global_long_short", &global).await?;
+        total += global.len();
+        let top = parse_long_short(
+            &fetch(
+                http,
+                &format!("{}/futures/data/topLongShortPositionRatio", cfg.fapi_url),
+                &symbol,
+                &cfg.period,
+            )
+            .await?,
+        )?;
+        db.insert_rows("top_long_short_position", &top).await?;
+        total += top.len();
+        let taker = parse_taker_volume(
+            &symbol,
+            &fetch(
+                http,
+                &format!("{}/futures/data/takerlongshortRatio", cfg.fapi_url),
+                &symbol,
+                &cfg.period,
+            )
+            .await?,
+        )?;
+        db.insert_rows("taker_buy_sell", &taker).await?;
+        total += taker.len();
+    }
+    Ok(total)
+}
+
+/// Backfill top-trader position ratios and open interest for a time
+/// window, page by page, so both series share the same 5m timestamps
+/// and join trivially with funding. Returns rows written.
+pub async fn backfill_top_positions(
+    http: &reqwest::Client,
+    db: &ClickHouse,
+    cfg: &SentimentConfig,
+    from: i64,
+    to: i64,
+) -> Result<usize> {
+    const PAGE_MS: i64 = 500 * 5 * 60_000;
+    let mut total = 0;
+    for symbol in &cfg.symbols {
+        let symbol = symbol.to_uppercase();
+        let mut cursor = from;
+        while cursor < to {
+            let window_end = (cursor + PAGE_MS).min(to);
+            let range = [
+                ("startTime", cursor.to_string()),
+                ("endTime", window_end.to_string()),
+            ];
+            let fetch_range = |url: String| {
+                let range = range.clone();
+                let symbol = symbol.clone();
+                let period = cfg.period.clone();
+                async move {
+                    http.get(url)
+                        .query(&[
+                            ("symbol", symbol.as_str()),
+                            ("period", period.as_str()),
+                            ("limit", "500"),
+                            ("startTime", range[0].1.as_str()),
+                            ("endTime", range[1].1.as_str()),
+                        ])
+                        .send()
+                        .await
+                        .map_err(|e| Error::Exchange(format!("sentiment request failed: {e}")))?
+                        .json::<Value>()
+                        .await
+                        .map_err(|e| Error::Exchange(format!("sentiment response invalid: {e}")))
+                }
+            };
+            let top = parse_long_short(
+                &fetch_range(format!(
+                    "{}/futures/data/topLongShortPositionRatio",
+                    cfg.fapi_url
+                ))
+                .await?,
+            )?;
+            db.insert_rows("top_long_short_position", &top).await?;
+            let oi = parse_open_interest(
+                &fetch_range(format!("{}/futures/data/openInterestHist", cfg.fapi_url)).await?,
+            )?;
+            db.insert_rows("open_interest", &oi).await?;
+            total += top.len() + oi.len();
+            cursor = window_end;
+        }
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_shapes() {
+        let ls = serde_json::json!([
+            {"symbol": "BTCUSDT", "longShortRatio": "1.8", "longAccount": "0.64",
+             "shortAccount": "0.36", "timestamp": 1_700_000_000_000i64}
+        ]);
+        let rows = parse_long_short(&ls).unwrap();
+        assert_eq!(rows[0].ratio, 1.8);
+        assert_eq!(rows[0].long_pct, 0.64);
+
+        let taker = serde_json::json!([
+            {"buySellRatio": "1.2", "buyVol": "600.0", "sellVol": "500.0",
+             "timestamp": 1_700_000_000_000i64}
+        ]);
+        let rows = parse_taker_volume("BTCUSDT", &taker).unwrap();
+        assert_eq!(rows[0].symbol, "BTCUSDT");
+        assert_eq!(rows[0].sell_vol, 500.0);
+        assert!(parse_long_short(&serde_json::json!({"code": -1})).is_err());
+    }
+
+    #[test]
+    fn parses_open_interest() {
+        let body = serde_json::json!([
+            {"symbol": "BTCUSDT", "sumOpenInterest": "82000.5", "sumOpenInterestValue": "3.1e9",
+             "timestamp": 1_700_000_000_000i64}
+        ]);
+        let rows = parse_open_interest(&body).unwrap();
+        assert_eq!(rows[0].open_interest, 82_000.5);
+        assert_eq!(rows[0].time, 1_700_000_000_000);
+    }
+}
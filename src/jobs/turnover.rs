@@ -0,0 +1,65 @@
+//! Per-symbol hourly turnover rollup.
+//!
+//! Notional turnover, trade counts and taker buy/sell split are asked
+//! for constantly (dashboards, alerting) and are expensive to compute
+//! from raw trades on demand: this job folds each UTC hour into one
+//! compact row per symbol, kept in `hourly_turnover` and replaced
+//! idempotently on re-runs, so it can be scheduled to run just past
+//! each hour boundary and stay cheap to re-run over the same window.
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+const HOUR_MS: i64 = 3_600_000;
+
+/// DDL for the rollup table.
+pub fn schema() -> &'static str {
+    "CREATE TABLE IF NOT EXISTS hourly_turnover (\
+     hour Int64, symbol String, trade_count UInt64, \
+     buy_notional Float64, sell_notional Float64, notional Float64) \
+     ENGINE = ReplacingMergeTree ORDER BY (symbol, hour)"
+}
+
+/// The rollup statement for one `[from, to)` window, aligned to hours.
+///
+/// `is_buyer_maker` means the buyer was passive, so the seller was the
+/// aggressor — that trade counts as taker-sell notional, and vice
+/// versa.
+pub fn plan(from: i64, to: i64) -> String {
+    let from = from - from.rem_euclid(HOUR_MS);
+    format!(
+        "INSERT INTO hourly_turnover \
+         SELECT intDiv(toUnixTimestamp64Milli(trade_time), {HOUR_MS}) * {HOUR_MS} AS hour, \
+                symbol, \
+                toUInt64(count()) AS trade_count, \
+                sumIf(price * qty, NOT is_buyer_maker) AS buy_notional, \
+                sumIf(price * qty, is_buyer_maker) AS sell_notional, \
+                sum(price * qty) AS notional \
+         FROM trades \
+         WHERE trade_time >= {} AND trade_time < {} \
+         GROUP BY symbol, hour",
+        crate::db::dt64_literal(from),
+        crate::db::dt64_literal(to)
+    )
+}
+
+/// Run the rollup for one window.
+pub async fn run(db: &ClickHouse, from: i64, to: i64) -> Result<()> {
+    db.execute(schema()).await?;
+    db.execute(&plan(from, to)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plan_groups_by_hour_and_symbol() {
+        let sql = plan(1_700_000_123_000, 1_700_010_000_000);
+        assert!(sql.contains("GROUP BY symbol, hour"));
+        assert!(sql.contains("sumIf(price * qty, NOT is_buyer_maker)"));
+        assert!(sql.contains("sumIf(price * qty, is_buyer_maker)"));
+        // The window start is aligned down to an hour boundary.
+        assert!(sql.contains("trade_time >= fromUnixTimestamp64Milli(1699999200000)"));
+    }
+}
@@ -0,0 +1,137 @@
+//! Derived USD reference series.
+//!
+//! USDT-quoted candles are converted into accounting-grade USD using a
+//! configurable reference — either a stable-peg assumption or a stored
+//! USDT/USD series from another venue — and written to `klines_usd`
+//! alongside the originals, with the applied rate kept per row.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::model::Interval;
+
+/// How USDT converts to USD.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum UsdReference {
+    /// Assume a fixed peg (default 1.0).
+    Peg {
+        /// USD per USDT.
+        #[serde(default = "default_peg_rate")]
+        rate: f64,
+    },
+    /// Use a stored reference series (e.g. Kraken `USDTUSD` klines),
+    /// joined as-of each candle's open time.
+    Series {
+        /// Reference symbol in the `klines` table.
+        symbol: String,
+    },
+}
+
+fn default_peg_rate() -> f64 {
+    1.0
+}
+
+impl Default for UsdReference {
+    fn default() -> Self {
+        UsdReference::Peg {
+            rate: default_peg_rate(),
+        }
+    }
+}
+
+/// DDL for the derived table.
+pub fn schema() -> &'static str {
+    "CREATE TABLE IF NOT EXISTS klines_usd (\
+     symbol String, interval String, \
+     open_time DateTime64(3, 'UTC'), close_time DateTime64(3, 'UTC'), \
+     open Float64, high Float64, low Float64, close Float64, \
+     volume Float64, quote_volume Float64, trade_count Int64, \
+     usd_rate Float64) \
+     ENGINE = ReplacingMergeTree ORDER BY (symbol, interval, open_time)"
+}
+
+/// The conversion statement for one interval and time window.
+pub fn plan(reference: &UsdReference, interval: Interval, from: i64, to: i64) -> String {
+    let from_dt = crate::db::dt64_literal(from);
+    let to_dt = crate::db::dt64_literal(to);
+    match reference {
+        UsdReference::Peg { rate } => format!(
+            "INSERT INTO klines_usd \
+             SELECT symbol, interval, open_time, close_time, \
+                    open * {rate}, high * {rate}, low * {rate}, close * {rate}, \
+                    volume, quote_volume * {rate}, trade_count, {rate} \
+             FROM klines \
+             WHERE endsWith(symbol, 'USDT') AND interval = '{interval}' \
+               AND open_time >= {from_dt} AND open_time < {to_dt}"
+        ),
+        UsdReference::Series { symbol } => format!(
+            "INSERT INTO klines_usd \
+             SELECT k.symbol, k.interval, k.open_time, k.close_time, \
+                    k.open * r.close, k.high * r.close, k.low * r.close, k.close * r.close, \
+                    k.volume, k.quote_volume * r.close, k.trade_count, r.close \
+             FROM klines AS k \
+             ASOF LEFT JOIN (\
+                 SELECT open_time, close FROM klines \
+                 WHERE symbol = '{symbol}' AND interval = '{interval}'\
+             ) AS r ON k.open_time >= r.open_time \
+             WHERE endsWith(k.symbol, 'USDT') AND k.interval = '{interval}' \
+               AND k.open_time >= {from_dt} AND k.open_time < {to_dt}"
+        ),
+    }
+}
+
+/// Run the conversion for one interval/window.
+pub async fn run(
+    db: &ClickHouse,
+    reference: &UsdReference,
+    interval: Interval,
+    from: i64,
+    to: i64,
+) -> Result<()> {
+    db.execute(schema()).await?;
+    db.execute(&plan(reference, interval, from, to)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peg_plan_scales_by_rate() {
+        let sql = plan(
+            &UsdReference::Peg { rate: 0.999 },
+            Interval::M1,
+            0,
+            86_400_000,
+        );
+        assert!(sql.contains("open * 0.999"));
+        assert!(sql.contains("endsWith(symbol, 'USDT')"));
+        assert!(sql.contains("interval = '1m'"));
+    }
+
+    #[test]
+    fn series_plan_asof_joins_reference() {
+        let sql = plan(
+            &UsdReference::Series {
+                symbol: "USDTUSD".into(),
+            },
+            Interval::H1,
+            0,
+            1,
+        );
+        assert!(sql.contains("ASOF LEFT JOIN"));
+        assert!(sql.contains("symbol = 'USDTUSD'"));
+        assert!(sql.contains("k.open * r.close"));
+    }
+
+    #[test]
+    fn config_parses_both_modes() {
+        let peg: UsdReference = toml::from_str("mode = \"peg\"\n").unwrap();
+        assert!(matches!(peg, UsdReference::Peg { rate } if rate == 1.0));
+        let series: UsdReference =
+            toml::from_str("mode = \"series\"\nsymbol = \"USDTUSD\"\n").unwrap();
+        assert!(matches!(series, UsdReference::Series { symbol } if symbol == "USDTUSD"));
+    }
+}
@@ -0,0 +1,39 @@
+//! Collection and archival of crypto market data.
+//!
+//! The crate is organised around a small event pipeline: collectors turn
+//! exchange payloads into [`pipeline::Event`]s, processors filter and
+//! transform them, and sinks persist whatever survives.
+
+pub mod admin;
+pub mod audit;
+pub mod chaos;
+pub mod checkpoint;
+pub mod clock;
+pub mod config;
+pub mod db;
+pub mod error;
+pub mod exchange;
+pub mod feed;
+pub mod fixtures;
+pub mod grafana;
+pub mod hooks;
+pub mod i18n;
+pub mod http;
+pub mod jobs;
+pub mod logging;
+pub mod model;
+pub mod ops;
+pub mod pipeline;
+pub mod planner;
+pub mod precision;
+pub mod queue;
+pub mod secrets;
+pub mod sink;
+pub mod storage;
+pub mod stream;
+pub mod subscriptions;
+pub mod testing;
+pub mod tiers;
+pub mod util;
+
+pub use error::{Error, Result};
@@ -0,0 +1,135 @@
+//! Logging setup shared by all binaries: console output plus optional
+//! rotating file logs for long-running collectors on headless boxes.
+
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::error::{Error, Result};
+
+/// How often the log file rolls over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRotation {
+    /// One file per UTC day.
+    #[default]
+    Daily,
+    /// One file per hour.
+    Hourly,
+    /// A single ever-growing file.
+    Never,
+}
+
+/// The `[logging]` config section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// Directory for rotated log files; file logging is off when
+    /// unset.
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// Rotation scheme for the file output.
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Filter for the file output, e.g.
+    /// `info,bzl_database::exchange=debug`. Defaults to the console
+    /// filter.
+    #[serde(default)]
+    pub file_filter: Option<String>,
+}
+
+/// `--quiet`/`--verbose` tiers shared by every binary. `--quiet` wins
+/// over any number of `-v`s; explicit flags override `RUST_LOG`.
+#[derive(Debug, Clone, Copy, Default, clap::Args)]
+pub struct Verbosity {
+    /// Only warnings and errors (suppresses per-message output).
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// More detail; repeat for trace (`-vv`).
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+}
+
+impl Verbosity {
+    /// The filter directives these flags ask for, if any.
+    fn directives(self) -> Option<&'static str> {
+        if self.quiet {
+            Some("warn")
+        } else {
+            match self.verbose {
+                0 => None,
+                1 => Some("debug"),
+                _ => Some("trace"),
+            }
+        }
+    }
+}
+
+/// Initialize logging: console (filtered by the verbosity flags, then
+/// `RUST_LOG`, default `info`) plus an optional rotating file layer.
+/// The returned guard must stay alive for the process lifetime or
+/// buffered file output is lost.
+pub fn init(
+    cfg: &LoggingConfig,
+    verbosity: Verbosity,
+) -> Result<Option<tracing_appender::non_blocking::WorkerGuard>> {
+    let console_filter = match verbosity.directives() {
+        Some(directives) => EnvFilter::new(directives),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let console = tracing_subscriber::fmt::layer().with_filter(console_filter);
+
+    let Some(dir) = &cfg.dir else {
+        tracing_subscriber::registry().with(console).init();
+        return Ok(None);
+    };
+    std::fs::create_dir_all(dir)?;
+    let binary = std::env::args()
+        .next()
+        .and_then(|p| {
+            std::path::Path::new(&p)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "bzl".to_string());
+    let appender = match cfg.rotation {
+        LogRotation::Daily => tracing_appender::rolling::daily(dir, format!("{binary}.log")),
+        LogRotation::Hourly => tracing_appender::rolling::hourly(dir, format!("{binary}.log")),
+        LogRotation::Never => tracing_appender::rolling::never(dir, format!("{binary}.log")),
+    };
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let file_filter = match &cfg.file_filter {
+        Some(directives) => EnvFilter::try_new(directives)
+            .map_err(|e| Error::Config(format!("bad logging file_filter: {e}")))?,
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
+    let file = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_writer(writer)
+        .with_filter(file_filter);
+    tracing_subscriber::registry().with(console).with(file).init();
+    Ok(Some(guard))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn section_parses_with_defaults() {
+        let cfg: LoggingConfig = toml::from_str("").unwrap();
+        assert!(cfg.dir.is_none());
+        assert_eq!(cfg.rotation, LogRotation::Daily);
+        let cfg: LoggingConfig = toml::from_str(
+            r#"
+            dir = "logs"
+            rotation = "hourly"
+            file_filter = "info,bzl_database::exchange=debug"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.rotation, LogRotation::Hourly);
+        assert!(cfg.file_filter.unwrap().contains("exchange=debug"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Core market data types shared by collectors, processors and sinks.
+
+use serde::{Deserialize, Serialize};
+
+/// A single executed trade.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Exchange-assigned trade id.
+    pub trade_id: i64,
+    /// Execution price.
+    pub price: f64,
+    /// Base-asset quantity.
+    pub qty: f64,
+    /// Trade timestamp in epoch milliseconds.
+    pub trade_time: i64,
+    /// Whether the buyer was the maker (i.e. a sell-side aggressor).
+    pub is_buyer_maker: bool,
+}
+
+impl Trade {
+    /// Quote-asset notional of the trade.
+    pub fn notional(&self) -> f64 {
+        self.price * self.qty
+    }
+}
+
+/// A validated candle interval.
+///
+/// Raw interval strings compared ad hoc silently mishandled `4h`,
+/// `1w`, `1M` and `1s`; every component now parses into this enum and
+/// takes durations from [`Interval::ms`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "&'static str")]
+pub enum Interval {
+    /// One second.
+    S1,
+    /// One minute.
+    M1,
+    /// Three minutes.
+    M3,
+    /// Five minutes.
+    M5,
+    /// Fifteen minutes.
+    M15,
+    /// Thirty minutes.
+    M30,
+    /// One hour.
+    H1,
+    /// Two hours.
+    H2,
+    /// Four hours.
+    H4,
+    /// Six hours.
+    H6,
+    /// Eight hours.
+    H8,
+    /// Twelve hours.
+    H12,
+    /// One day.
+    D1,
+    /// Three days.
+    D3,
+    /// One week.
+    W1,
+    /// One calendar month.
+    Mo1,
+}
+
+impl Interval {
+    /// Every supported interval, shortest first.
+    pub const ALL: [Interval; 16] = [
+        Interval::S1,
+        Interval::M1,
+        Interval::M3,
+        Interval::M5,
+        Interval::M15,
+        Interval::M30,
+        Interval::H1,
+        Interval::H2,
+        Interval::H4,
+        Interval::H6,
+        Interval::H8,
+        Interval::H12,
+        Interval::D1,
+        Interval::D3,
+        Interval::W1,
+        Interval::Mo1,
+    ];
+
+    /// The Binance-style interval string (`1m`, `4h`, `1M`, ...).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Interval::S1 => "1s",
+            Interval::M1 => "1m",
+            Interval::M3 => "3m",
+            Interval::M5 => "5m",
+            Interval::M15 => "15m",
+            Interval::M30 => "30m",
+            Interval::H1 => "1h",
+            Interval::H2 => "2h",
+            Interval::H4 => "4h",
+            Interval::H6 => "6h",
+            Interval::H8 => "8h",
+            Interval::H12 => "12h",
+            Interval::D1 => "1d",
+            Interval::D3 => "3d",
+            Interval::W1 => "1w",
+            Interval::Mo1 => "1M",
+        }
+    }
+
+    /// Duration in milliseconds. `1M` uses the 30-day convention for
+    /// stepping; month boundaries themselves come from the exchange.
+    pub fn ms(self) -> i64 {
+        const MINUTE: i64 = 60_000;
+        match self {
+            Interval::S1 => 1_000,
+            Interval::M1 => MINUTE,
+            Interval::M3 => 3 * MINUTE,
+            Interval::M5 => 5 * MINUTE,
+            Interval::M15 => 15 * MINUTE,
+            Interval::M30 => 30 * MINUTE,
+            Interval::H1 => 60 * MINUTE,
+            Interval::H2 => 120 * MINUTE,
+            Interval::H4 => 240 * MINUTE,
+            Interval::H6 => 360 * MINUTE,
+            Interval::H8 => 480 * MINUTE,
+            Interval::H12 => 720 * MINUTE,
+            Interval::D1 => 1_440 * MINUTE,
+            Interval::D3 => 3 * 1_440 * MINUTE,
+            Interval::W1 => 7 * 1_440 * MINUTE,
+            Interval::Mo1 => 30 * 1_440 * MINUTE,
+        }
+    }
+}
+
+impl std::str::FromStr for Interval {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|i| i.as_str() == s)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|i| i.as_str()).collect();
+                crate::error::Error::Config(format!(
+                    "unknown interval `{s}` (known: {})",
+                    known.join(", ")
+                ))
+            })
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TryFrom<String> for Interval {
+    type Error = crate::error::Error;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Interval> for &'static str {
+    fn from(i: Interval) -> Self {
+        i.as_str()
+    }
+}
+
+/// Millisecond duration of an interval string, if recognised.
+pub fn interval_ms(interval: &str) -> Option<i64> {
+    interval.parse::<Interval>().ok().map(Interval::ms)
+}
+
+/// A top-of-book (best bid/offer) snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bbo {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Best bid price.
+    pub bid_price: f64,
+    /// Quantity at the best bid.
+    pub bid_qty: f64,
+    /// Best ask price.
+    pub ask_price: f64,
+    /// Quantity at the best ask.
+    pub ask_qty: f64,
+    /// Snapshot timestamp in epoch milliseconds.
+    pub time: i64,
+}
+
+/// A `miniTicker`/`ticker` stream update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MiniTicker {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Event timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Last price.
+    pub close: f64,
+    /// Rolling 24h high as reported by the venue.
+    pub high: f64,
+    /// Rolling 24h low as reported by the venue.
+    pub low: f64,
+    /// Cumulative 24h base volume as reported by the venue.
+    pub volume: f64,
+}
+
+/// Rolling-window statistics derived from the ticker stream.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RollingStats {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Emission timestamp in epoch milliseconds (minute resolution).
+    pub time: i64,
+    /// Window label, e.g. `1h`.
+    pub window: String,
+    /// Highest observed price in the window.
+    pub high: f64,
+    /// Lowest observed price in the window.
+    pub low: f64,
+    /// Base volume traded in the window.
+    pub volume: f64,
+    /// Percent return over the window.
+    pub return_pct: f64,
+}
+
+/// A point-in-time order book snapshot (top N levels).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthSnapshot {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Snapshot timestamp in epoch milliseconds.
+    pub time: i64,
+    /// Bid levels, best first, as `(price, qty)`.
+    pub bids: Vec<(f64, f64)>,
+    /// Ask levels, best first, as `(price, qty)`.
+    pub asks: Vec<(f64, f64)>,
+}
+
+/// An OHLCV candle for one symbol and interval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Kline {
+    /// Exchange symbol, e.g. `BTCUSDT`.
+    pub symbol: String,
+    /// Candle interval, e.g. `1m`.
+    pub interval: String,
+    /// Candle open time in epoch milliseconds.
+    pub open_time: i64,
+    /// Candle close time in epoch milliseconds.
+    pub close_time: i64,
+    /// Open price.
+    pub open: f64,
+    /// Highest price.
+    pub high: f64,
+    /// Lowest price.
+    pub low: f64,
+    /// Close price.
+    pub close: f64,
+    /// Base-asset volume.
+    pub volume: f64,
+    /// Quote-asset volume.
+    pub quote_volume: f64,
+    /// Number of trades in the candle.
+    pub trade_count: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_parses_and_round_trips() {
+        for interval in Interval::ALL {
+            assert_eq!(interval.as_str().parse::<Interval>().unwrap(), interval);
+        }
+        assert_eq!(Interval::ALL.len(), 16);
+        assert_eq!("4h".parse::<Interval>().unwrap().ms(), 14_400_000);
+        assert_eq!("1s".parse::<Interval>().unwrap().ms(), 1_000);
+        assert_eq!("8h".parse::<Interval>().unwrap().ms(), 28_800_000);
+        assert_eq!("3d".parse::<Interval>().unwrap().ms(), 259_200_000);
+        assert_eq!("1M".parse::<Interval>().unwrap(), Interval::Mo1);
+    }
+
+    #[test]
+    fn unknown_interval_lists_known_ones() {
+        let err = "7q".parse::<Interval>().unwrap_err().to_string();
+        assert!(err.contains("7q"));
+        assert!(err.contains("1m"));
+        assert_eq!(interval_ms("7q"), None);
+        assert_eq!(interval_ms("1w"), Some(604_800_000));
+    }
+}
@@ -0,0 +1,79 @@
+//! As-of (time-travel) query helpers.
+//!
+//! "What was the last known candle/price for X as of time T" — the
+//! point-in-time reconstruction primitive. The queries lean on the
+//! tables' `(symbol, …, time)` ordering keys, so they resolve with a
+//! single reverse scan instead of a full-range aggregate.
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::model::{Interval, Kline};
+
+/// SQL for the last candle at or before `at_ms`.
+pub fn kline_asof_sql(symbol: &str, interval: Interval, at_ms: i64) -> String {
+    format!(
+        "SELECT symbol, interval, \
+         toUnixTimestamp64Milli(open_time) AS open_time, \
+         toUnixTimestamp64Milli(close_time) AS close_time, \
+         open, high, low, close, volume, quote_volume, trade_count FROM klines \
+         WHERE symbol = '{}' AND interval = '{interval}' AND open_time <= {} \
+         ORDER BY open_time DESC LIMIT 1",
+        symbol.to_uppercase(),
+        crate::db::dt64_literal(at_ms)
+    )
+}
+
+/// SQL for the last trade price at or before `at_ms`.
+pub fn price_asof_sql(symbol: &str, at_ms: i64) -> String {
+    format!(
+        "SELECT toUnixTimestamp64Milli(trade_time) AS trade_time, price FROM trades \
+         WHERE symbol = '{}' AND trade_time <= {} \
+         ORDER BY trade_time DESC, trade_id DESC LIMIT 1",
+        symbol.to_uppercase(),
+        crate::db::dt64_literal(at_ms)
+    )
+}
+
+/// The last known candle for a symbol/interval as of `at_ms`.
+pub async fn kline_asof(
+    db: &ClickHouse,
+    symbol: &str,
+    interval: Interval,
+    at_ms: i64,
+) -> Result<Option<Kline>> {
+    let rows: Vec<Kline> = db.query_rows(&kline_asof_sql(symbol, interval, at_ms)).await?;
+    Ok(rows.into_iter().next())
+}
+
+/// Row shape of the price-as-of query.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Deserialize)]
+pub struct PricePoint {
+    /// Trade timestamp in epoch milliseconds.
+    pub trade_time: i64,
+    /// Trade price.
+    pub price: f64,
+}
+
+/// The last known trade price for a symbol as of `at_ms`.
+pub async fn price_asof(db: &ClickHouse, symbol: &str, at_ms: i64) -> Result<Option<PricePoint>> {
+    let rows: Vec<PricePoint> = db.query_rows(&price_asof_sql(symbol, at_ms)).await?;
+    Ok(rows.into_iter().next())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asof_queries_scan_backwards_from_t() {
+        let sql = kline_asof_sql("btcusdt", Interval::M1, 1_700_000_000_000);
+        assert!(sql.contains("symbol = 'BTCUSDT'"));
+        assert!(sql.contains("open_time <= fromUnixTimestamp64Milli(1700000000000)"));
+        assert!(sql.contains("ORDER BY open_time DESC LIMIT 1"));
+
+        let sql = price_asof_sql("ethusdt", 42);
+        assert!(sql.contains("symbol = 'ETHUSDT'"));
+        assert!(sql.contains("trade_time <= fromUnixTimestamp64Milli(42)"));
+        assert!(sql.contains("trade_id DESC"));
+    }
+}
@@ -0,0 +1,138 @@
+//! CSV compaction: sort and deduplicate daily files in place.
+//!
+//! Append-mode files accumulate duplicated and out-of-order rows after
+//! restarts. Compaction rewrites each file sorted by time with
+//! duplicates removed — klines dedup by open time (last write wins),
+//! trades by exact row — using temp-file-and-rename so a crash cannot
+//! lose data.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::Result;
+
+/// Counters for one compaction run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CompactStats {
+    /// Files rewritten.
+    pub files: usize,
+    /// Rows read.
+    pub rows_in: usize,
+    /// Rows kept.
+    pub rows_out: usize,
+}
+
+fn first_field_i64(line: &str) -> i64 {
+    line.split(',').next().and_then(|f| f.parse().ok()).unwrap_or(i64::MAX)
+}
+
+fn trade_sort_key(line: &str) -> (i64, i64) {
+    let mut fields = line.split(',');
+    let id = fields.next().and_then(|f| f.parse().ok()).unwrap_or(i64::MAX);
+    let time = fields.next().and_then(|f| f.parse().ok()).unwrap_or(i64::MAX);
+    (time, id)
+}
+
+/// Compact one file; returns `(rows_in, rows_out)`.
+pub fn compact_file(path: &Path) -> Result<(usize, usize)> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let text = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let rows_in = lines.len();
+    let output: Vec<&str> = if name.starts_with("klines-") {
+        // Dedup by open time; the later occurrence wins (it came from
+        // the more recent download).
+        let mut by_time: BTreeMap<i64, &str> = BTreeMap::new();
+        for line in lines {
+            by_time.insert(first_field_i64(line), line);
+        }
+        by_time.into_values().collect()
+    } else {
+        // Trades: sort by (time, id) and drop exact duplicate rows.
+        let mut sorted = lines;
+        sorted.sort_by_key(|l| trade_sort_key(l));
+        sorted.dedup();
+        sorted
+    };
+    let rows_out = output.len();
+    let mut body = String::with_capacity(text.len());
+    for line in output {
+        body.push_str(line);
+        body.push('\n');
+    }
+    let tmp = path.with_extension("csv.tmp");
+    std::fs::write(&tmp, body)?;
+    std::fs::rename(&tmp, path)?;
+    Ok((rows_in, rows_out))
+}
+
+/// Compact every daily CSV under the archive tree.
+pub fn compact_tree(root: &Path) -> Result<CompactStats> {
+    let mut stats = CompactStats::default();
+    if !root.exists() {
+        return Ok(stats);
+    }
+    for symbol_dir in std::fs::read_dir(root)? {
+        let symbol_dir = symbol_dir?;
+        if !symbol_dir.file_type()?.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(symbol_dir.path())? {
+            let path = file?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("csv") {
+                continue;
+            }
+            let (rows_in, rows_out) = compact_file(&path)?;
+            stats.files += 1;
+            stats.rows_in += rows_in;
+            stats.rows_out += rows_out;
+            if rows_in != rows_out {
+                tracing::info!(
+                    path = %path.display(),
+                    removed = rows_in - rows_out,
+                    "compacted"
+                );
+            }
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn klines_dedup_by_open_time_last_wins() {
+        let dir = std::env::temp_dir().join(format!("bzl-compact-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("BTCUSDT")).unwrap();
+        let path = dir.join("BTCUSDT").join("klines-1m-2023-11-14.csv");
+        std::fs::write(
+            &path,
+            "120000,2,2,2,2,1,179999,2,1\n60000,1,1,1,1,1,119999,1,1\n60000,9,9,9,9,9,119999,9,9\n",
+        )
+        .unwrap();
+        let (rows_in, rows_out) = compact_file(&path).unwrap();
+        assert_eq!((rows_in, rows_out), (3, 2));
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("60000,9,"), "later duplicate wins: {lines:?}");
+        assert!(lines[1].starts_with("120000,"));
+
+        let trades = dir.join("BTCUSDT").join("trades-2023-11-14.csv");
+        std::fs::write(
+            &trades,
+            "2,2000,1.0,1.0,false\n1,1000,1.0,1.0,true\n1,1000,1.0,1.0,true\n",
+        )
+        .unwrap();
+        let stats = compact_tree(&dir).unwrap();
+        assert_eq!(stats.files, 2);
+        let text = std::fs::read_to_string(&trades).unwrap();
+        assert_eq!(text, "1,1000,1.0,1.0,true\n2,2000,1.0,1.0,false\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
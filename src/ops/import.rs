@@ -0,0 +1,207 @@
+//! Bulk import of an existing CSV archive into ClickHouse.
+//!
+//! Files are parsed and validated on a rayon pool while a single async
+//! consumer inserts batches; the bounded channel between them caps how
+//! many parsed files can be in flight, so memory stays flat no matter
+//! how large the tree is.
+
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+use crate::model::{Kline, Trade};
+use crate::pipeline::Event;
+use crate::sink::clickhouse::ClickHouseSink;
+use crate::sink::Sink;
+use crate::storage::kline_store::parse_csv_row;
+
+/// How many parsed files may wait for insertion at once.
+const IN_FLIGHT_FILES: usize = 8;
+
+/// Outcome counters for one import run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ImportStats {
+    /// Data files visited.
+    pub files: usize,
+    /// Kline rows parsed successfully.
+    pub klines: usize,
+    /// Trade rows parsed successfully.
+    pub trades: usize,
+    /// Rows rejected by validation.
+    pub bad_rows: usize,
+}
+
+/// What one parsed file contributes.
+struct ParsedFile {
+    klines: Vec<Kline>,
+    trades: Vec<Trade>,
+    bad_rows: usize,
+}
+
+/// Parse one archive file based on its name
+/// (`klines-<interval>-<day>.csv` or `trades-<day>.csv`).
+fn parse_file(symbol: &str, path: &Path) -> Result<ParsedFile> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| Error::Config(format!("bad file name: {}", path.display())))?;
+    let text = std::fs::read_to_string(path)?;
+    let mut parsed = ParsedFile {
+        klines: Vec::new(),
+        trades: Vec::new(),
+        bad_rows: 0,
+    };
+    if let Some(rest) = name.strip_prefix("klines-") {
+        let interval = rest.split('-').next().unwrap_or_default();
+        for line in text.lines() {
+            match parse_csv_row(symbol, interval, line) {
+                Ok(k) => parsed.klines.push(k),
+                Err(_) => parsed.bad_rows += 1,
+            }
+        }
+    } else if name.starts_with("trades-") {
+        for line in text.lines() {
+            match parse_trade_row(symbol, line) {
+                Ok(t) => parsed.trades.push(t),
+                Err(_) => parsed.bad_rows += 1,
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Parse one trade row (`trade_id,trade_time,price,qty,is_buyer_maker`).
+pub(crate) fn parse_trade_row(symbol: &str, line: &str) -> Result<Trade> {
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() != 5 {
+        return Err(Error::Config(format!("bad trade CSV row: {line}")));
+    }
+    let bad = |i: usize| Error::Config(format!("bad field {i} in trade CSV row: {line}"));
+    Ok(Trade {
+        symbol: symbol.to_string(),
+        trade_id: fields[0].parse().map_err(|_| bad(0))?,
+        trade_time: fields[1].parse().map_err(|_| bad(1))?,
+        price: fields[2].parse().map_err(|_| bad(2))?,
+        qty: fields[3].parse().map_err(|_| bad(3))?,
+        is_buyer_maker: fields[4].parse().map_err(|_| bad(4))?,
+    })
+}
+
+/// Enumerate `<root>/<SYMBOL>/*.csv` data files.
+fn discover_files(root: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut files = Vec::new();
+    for symbol_entry in std::fs::read_dir(root)? {
+        let symbol_entry = symbol_entry?;
+        if !symbol_entry.file_type()?.is_dir() {
+            continue;
+        }
+        let symbol = symbol_entry.file_name().to_string_lossy().into_owned();
+        for file in std::fs::read_dir(symbol_entry.path())? {
+            let file = file?;
+            let path = file.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("csv") {
+                files.push((symbol.clone(), path));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Import the archive tree under `root`. With `db = None` the run only
+/// parses and validates (the `--parse-only` mode).
+pub async fn import_tree(
+    root: impl AsRef<Path>,
+    db: Option<ClickHouse>,
+    workers: usize,
+) -> Result<ImportStats> {
+    let files = discover_files(root.as_ref())?;
+    let total_files = files.len();
+    let (tx, mut rx) = tokio::sync::mpsc::channel::<ParsedFile>(IN_FLIGHT_FILES);
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(workers.max(1))
+        .build()
+        .map_err(|e| Error::Config(format!("worker pool: {e}")))?;
+    let parser = std::thread::spawn(move || {
+        pool.install(|| {
+            files.par_iter().for_each(|(symbol, path)| {
+                match parse_file(symbol, path) {
+                    Ok(parsed) => {
+                        // blocking_send enforces the in-flight bound.
+                        let _ = tx.blocking_send(parsed);
+                    }
+                    Err(e) => tracing::warn!(path = %path.display(), error = %e, "skipping file"),
+                }
+            });
+        });
+    });
+
+    let mut sink = db.map(ClickHouseSink::new);
+    if let Some(sink) = &sink {
+        sink.ensure_schema().await?;
+    }
+    let mut stats = ImportStats {
+        files: total_files,
+        ..Default::default()
+    };
+    while let Some(parsed) = rx.recv().await {
+        stats.klines += parsed.klines.len();
+        stats.trades += parsed.trades.len();
+        stats.bad_rows += parsed.bad_rows;
+        if let Some(sink) = &mut sink {
+            let events: Vec<Event> = parsed
+                .klines
+                .into_iter()
+                .map(Event::Kline)
+                .chain(parsed.trades.into_iter().map(Event::Trade))
+                .collect();
+            sink.write(&events).await?;
+        }
+    }
+    parser
+        .join()
+        .map_err(|_| Error::Config("parser pool panicked".to_string()))?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn parses_tree_and_counts_bad_rows() {
+        let root = std::env::temp_dir().join(format!("bzl-import-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("klines-1m-2023-11-14.csv"),
+            "60000,1,2,0.5,1.5,10,119999,15,3\nnot,a,row\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("trades-2023-11-14.csv"),
+            "1,1699920000000,100.5,0.1,true\n2,1699920000100,100.6,0.2,false\n",
+        )
+        .unwrap();
+        // A stray non-CSV file is ignored.
+        std::fs::write(dir.join("notes.txt"), "hello").unwrap();
+        let stats = import_tree(&root, None, 4).await.unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.klines, 1);
+        assert_eq!(stats.trades, 2);
+        assert_eq!(stats.bad_rows, 1);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn trade_row_parses() {
+        let t = parse_trade_row("BTCUSDT", "7,1000,50.5,0.25,true").unwrap();
+        assert_eq!(t.trade_id, 7);
+        assert!(t.is_buyer_maker);
+        assert!(parse_trade_row("BTCUSDT", "7,1000,50.5").is_err());
+    }
+}
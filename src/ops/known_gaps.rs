@@ -0,0 +1,127 @@
+//! Confirmed exchange-side gaps.
+//!
+//! A missing stretch of candles can mean two very different things:
+//! the collector was down, or Binance itself has nothing for that
+//! window (a maintenance pause, a symbol delisted mid-day, an
+//! exchange-side outage). Only [`repair`](crate::ops::repair::repair)
+//! actually proves the difference — it makes a live request for the
+//! range and only then knows whether the exchange returned rows.
+//! When it comes back with zero candles for a range that was believed
+//! to hold data, that is recorded here so the gap detector and
+//! completeness report stop flagging it as something to repair.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::model::Interval;
+
+/// One confirmed-empty range for a symbol/interval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownGap {
+    pub symbol: String,
+    pub interval: String,
+    pub from_ms: i64,
+    pub to_ms: i64,
+    /// Why the range is believed genuinely empty, e.g. `"repair_confirmed_empty"`.
+    pub reason: String,
+    pub detected_at: i64,
+}
+
+/// DDL for the known-gaps table.
+pub fn schema() -> &'static str {
+    "CREATE TABLE IF NOT EXISTS known_gaps (\
+     symbol String, interval String, from_ms Int64, to_ms Int64, \
+     reason String, detected_at Int64) \
+     ENGINE = ReplacingMergeTree ORDER BY (symbol, interval, from_ms)"
+}
+
+/// Record a confirmed-empty range.
+pub async fn tag(
+    db: &ClickHouse,
+    symbol: &str,
+    interval: Interval,
+    from_ms: i64,
+    to_ms: i64,
+    reason: &str,
+    detected_at: i64,
+) -> Result<()> {
+    db.execute(schema()).await?;
+    let gap = KnownGap {
+        symbol: symbol.to_uppercase(),
+        interval: interval.as_str().to_string(),
+        from_ms,
+        to_ms,
+        reason: reason.to_string(),
+        detected_at,
+    };
+    db.insert_rows("known_gaps", &[gap]).await
+}
+
+/// Known gaps overlapping `[from, to)` for one symbol/interval, sorted
+/// by start.
+pub async fn overlapping(
+    db: &ClickHouse,
+    symbol: &str,
+    interval: Interval,
+    from: i64,
+    to: i64,
+) -> Result<Vec<KnownGap>> {
+    let sql = format!(
+        "SELECT symbol, interval, from_ms, to_ms, reason, detected_at FROM known_gaps \
+         WHERE symbol = '{}' AND interval = '{}' AND from_ms < {to} AND to_ms > {from} \
+         ORDER BY from_ms",
+        symbol.to_uppercase(),
+        interval
+    );
+    db.query_rows(&sql).await
+}
+
+/// Candles inside `[earliest, latest]` that fall within `gaps`, so
+/// callers can subtract them from an "expected" count instead of
+/// treating a confirmed-empty exchange window as missing data.
+pub fn covered_candles(gaps: &[KnownGap], earliest: i64, latest: i64, step: i64) -> u64 {
+    gaps.iter()
+        .map(|g| {
+            let from = g.from_ms.max(earliest);
+            let to = g.to_ms.min(latest + step);
+            if to > from {
+                ((to - from) / step) as u64
+            } else {
+                0
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gap(from_ms: i64, to_ms: i64) -> KnownGap {
+        KnownGap {
+            symbol: "BTCUSDT".to_string(),
+            interval: "1m".to_string(),
+            from_ms,
+            to_ms,
+            reason: "repair_confirmed_empty".to_string(),
+            detected_at: 0,
+        }
+    }
+
+    #[test]
+    fn covered_candles_counts_only_the_overlap() {
+        let step = 60_000;
+        let gaps = vec![gap(2 * step, 5 * step)];
+        // Window is [0, 9*step]; the gap covers candles at 2,3,4 -> 3 candles.
+        assert_eq!(covered_candles(&gaps, 0, 9 * step, step), 3);
+    }
+
+    #[test]
+    fn covered_candles_clips_to_the_requested_window() {
+        let step = 60_000;
+        let gaps = vec![gap(-step, 3 * step)];
+        // Only candles 0,1,2 fall inside [0, 9*step].
+        assert_eq!(covered_candles(&gaps, 0, 9 * step, step), 3);
+    }
+}
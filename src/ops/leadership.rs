@@ -0,0 +1,171 @@
+//! Hot-standby leadership.
+//!
+//! An active/standby pair both run the full collector — connections
+//! up, pipelines warm — but only the current leader persists data. The
+//! lock is a heartbeat row in ClickHouse: the standby acquires it as
+//! soon as the primary misses renewals for one TTL, keeping the gap
+//! window during failover to seconds.
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+/// The `[leadership]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeadershipConfig {
+    /// Identity of this instance; defaults to `hostname-pid`.
+    #[serde(default)]
+    pub host_id: Option<String>,
+    /// Heartbeat lifetime; the standby takes over after the leader
+    /// misses renewals for this long.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+    /// Lock name, for running several independent pairs.
+    #[serde(default = "default_role")]
+    pub role: String,
+}
+
+fn default_ttl_secs() -> u64 {
+    10
+}
+
+fn default_role() -> String {
+    "collector".to_string()
+}
+
+impl LeadershipConfig {
+    fn host_id(&self) -> String {
+        self.host_id.clone().unwrap_or_else(|| {
+            let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "collector".to_string());
+            format!("{host}-{}", std::process::id())
+        })
+    }
+}
+
+/// Whether `me` may hold the lock given its current state.
+pub fn may_lead(current: Option<(&str, i64)>, me: &str, now: i64) -> bool {
+    match current {
+        None => true,
+        Some((holder, _)) if holder == me => true,
+        Some((_, expires_at)) => expires_at <= now,
+    }
+}
+
+#[derive(Deserialize)]
+struct LockRow {
+    holder: String,
+    expires_at: i64,
+}
+
+#[derive(Serialize)]
+struct HeartbeatRow<'a> {
+    role: &'a str,
+    holder: &'a str,
+    expires_at: i64,
+    updated_at: i64,
+}
+
+/// The leadership lock against ClickHouse.
+pub struct Leadership {
+    db: ClickHouse,
+    me: String,
+    role: String,
+    ttl_ms: i64,
+}
+
+impl Leadership {
+    /// Build the lock handle for this instance.
+    pub fn new(db: ClickHouse, cfg: &LeadershipConfig) -> Self {
+        Self {
+            db,
+            me: cfg.host_id(),
+            role: cfg.role.clone(),
+            ttl_ms: (cfg.ttl_secs as i64) * 1000,
+        }
+    }
+
+    /// This instance's identity.
+    pub fn host_id(&self) -> &str {
+        &self.me
+    }
+
+    /// Create the lock table.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        self.db
+            .execute(
+                "CREATE TABLE IF NOT EXISTS leadership (\
+                 role String, holder String, expires_at Int64, updated_at Int64) \
+                 ENGINE = ReplacingMergeTree(updated_at) ORDER BY role",
+            )
+            .await
+    }
+
+    /// Try to acquire or renew the lock; returns whether this instance
+    /// leads now.
+    pub async fn heartbeat(&self, now: i64) -> Result<bool> {
+        let rows: Vec<LockRow> = self
+            .db
+            .query_rows(&format!(
+                "SELECT argMax(holder, updated_at) AS holder, \
+                 argMax(expires_at, updated_at) AS expires_at \
+                 FROM leadership WHERE role = '{}' GROUP BY role",
+                self.role
+            ))
+            .await?;
+        let current = rows.first().map(|r| (r.holder.as_str(), r.expires_at));
+        if !may_lead(current, &self.me, now) {
+            return Ok(false);
+        }
+        self.db
+            .insert_rows(
+                "leadership",
+                &[HeartbeatRow {
+                    role: &self.role,
+                    holder: &self.me,
+                    expires_at: now + self.ttl_ms,
+                    updated_at: now,
+                }],
+            )
+            .await?;
+        Ok(true)
+    }
+}
+
+impl Leadership {
+    /// Release the lock immediately (rolling-restart handover): the
+    /// heartbeat row is rewritten as already expired, so the standby's
+    /// next heartbeat acquires leadership without waiting out the TTL.
+    /// Overlapping rows written during the switch deduplicate via the
+    /// Replacing engines and insert deduplication tokens.
+    pub async fn release(&self, now: i64) -> Result<()> {
+        self.db
+            .insert_rows(
+                "leadership",
+                &[HeartbeatRow {
+                    role: &self.role,
+                    holder: &self.me,
+                    expires_at: now,
+                    updated_at: now,
+                }],
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lock_acquisition_rules() {
+        // Free lock: take it.
+        assert!(may_lead(None, "standby", 1_000));
+        // Own lock: renew.
+        assert!(may_lead(Some(("standby", 5_000)), "standby", 1_000));
+        // Live foreign lock: stand by.
+        assert!(!may_lead(Some(("primary", 5_000)), "standby", 1_000));
+        // Expired foreign lock: take over.
+        assert!(may_lead(Some(("primary", 500)), "standby", 1_000));
+    }
+}
@@ -0,0 +1,187 @@
+//! Checksum manifest for produced data files.
+//!
+//! `MANIFEST.jsonl` at the archive root records every data file's row
+//! count, sha256 and time range; `bzl verify` re-hashes the tree
+//! against it to catch silent on-disk corruption of a multi-terabyte
+//! archive.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+const MANIFEST_NAME: &str = "MANIFEST.jsonl";
+
+/// One data file's fingerprint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Path relative to the archive root.
+    pub path: String,
+    /// Data rows in the file.
+    pub rows: usize,
+    /// Hex sha256 of the file contents.
+    pub sha256: String,
+    /// Earliest row timestamp (epoch ms), if parseable.
+    pub min_time: Option<i64>,
+    /// Latest row timestamp (epoch ms), if parseable.
+    pub max_time: Option<i64>,
+}
+
+/// Outcome of a manifest verification pass.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Files whose hash still matches.
+    pub ok: usize,
+    /// Manifest entries whose file is gone.
+    pub missing: Vec<String>,
+    /// Files whose hash no longer matches.
+    pub corrupt: Vec<String>,
+}
+
+fn row_time(name: &str, line: &str) -> Option<i64> {
+    let mut fields = line.split(',');
+    if name.starts_with("trades-") {
+        fields.nth(1)?.parse().ok()
+    } else {
+        fields.next()?.parse().ok()
+    }
+}
+
+fn entry_for(root: &Path, path: &Path) -> Result<ManifestEntry> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let bytes = std::fs::read(path)?;
+    let sha256 = format!("{:x}", Sha256::digest(&bytes));
+    let text = String::from_utf8_lossy(&bytes);
+    let mut rows = 0;
+    let mut min_time = None;
+    let mut max_time = None;
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        rows += 1;
+        if let Some(t) = row_time(&name, line) {
+            min_time = Some(min_time.map_or(t, |m: i64| m.min(t)));
+            max_time = Some(max_time.map_or(t, |m: i64| m.max(t)));
+        }
+    }
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .into_owned();
+    Ok(ManifestEntry {
+        path: relative,
+        rows,
+        sha256,
+        min_time,
+        max_time,
+    })
+}
+
+fn data_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !root.exists() {
+        return Ok(files);
+    }
+    for symbol_dir in std::fs::read_dir(root)? {
+        let symbol_dir = symbol_dir?;
+        if !symbol_dir.file_type()?.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(symbol_dir.path())? {
+            let path = file?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("csv") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Regenerate the manifest over every data file; returns entry count.
+pub fn update_manifest(root: &Path) -> Result<usize> {
+    let mut body = String::new();
+    let files = data_files(root)?;
+    let count = files.len();
+    for path in files {
+        let entry = entry_for(root, &path)?;
+        body.push_str(&serde_json::to_string(&entry)?);
+        body.push('\n');
+    }
+    let manifest = root.join(MANIFEST_NAME);
+    let tmp = manifest.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, body)?;
+    std::fs::rename(&tmp, &manifest)?;
+    Ok(count)
+}
+
+/// Load the manifest entries.
+pub fn load_manifest(root: &Path) -> Result<Vec<ManifestEntry>> {
+    let text = std::fs::read_to_string(root.join(MANIFEST_NAME))
+        .map_err(|e| Error::Config(format!("no manifest at {}: {e}", root.display())))?;
+    text.lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| serde_json::from_str(l).map_err(Error::from))
+        .collect()
+}
+
+/// Re-hash every manifest entry against the files on disk.
+pub fn verify_manifest(root: &Path) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    for entry in load_manifest(root)? {
+        let path = root.join(&entry.path);
+        match std::fs::read(&path) {
+            Err(_) => report.missing.push(entry.path),
+            Ok(bytes) => {
+                let actual = format!("{:x}", Sha256::digest(&bytes));
+                if actual == entry.sha256 {
+                    report.ok += 1;
+                } else {
+                    report.corrupt.push(entry.path);
+                }
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_round_trips_and_detects_corruption() {
+        let root = std::env::temp_dir().join(format!("bzl-manifest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("klines-1m-2023-11-14.csv");
+        std::fs::write(&path, "60000,1,1,1,1,1,119999,1,1\n120000,2,2,2,2,2,179999,2,2\n").unwrap();
+        std::fs::write(dir.join("trades-2023-11-14.csv"), "1,1500,1.0,1.0,true\n").unwrap();
+
+        assert_eq!(update_manifest(&root).unwrap(), 2);
+        let entries = load_manifest(&root).unwrap();
+        let kline_entry = entries.iter().find(|e| e.path.contains("klines")).unwrap();
+        assert_eq!(kline_entry.rows, 2);
+        assert_eq!(kline_entry.min_time, Some(60_000));
+        assert_eq!(kline_entry.max_time, Some(120_000));
+        let trade_entry = entries.iter().find(|e| e.path.contains("trades")).unwrap();
+        assert_eq!(trade_entry.min_time, Some(1_500));
+
+        let clean = verify_manifest(&root).unwrap();
+        assert_eq!(clean.ok, 2);
+        assert!(clean.corrupt.is_empty());
+
+        // Flip a byte and watch verification catch it.
+        std::fs::write(&path, "60000,9,9,9,9,9,119999,9,9\n").unwrap();
+        let dirty = verify_manifest(&root).unwrap();
+        assert_eq!(dirty.ok, 1);
+        assert_eq!(dirty.corrupt.len(), 1);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
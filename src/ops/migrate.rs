@@ -0,0 +1,175 @@
+//! One-shot migration of a legacy CSV archive.
+//!
+//! Trees produced by older append+restart collectors contain
+//! duplicated, out-of-order rows — some sitting in the wrong daily
+//! file entirely. `bzl migrate` reads every data file per symbol and
+//! kind, re-buckets rows by their actual UTC day, sorts and
+//! deduplicates, and rewrites the daily files so the tree imports
+//! cleanly. Each rewritten day is renamed into place as soon as it's
+//! written, so a crash mid-run leaves a mix of already-migrated and
+//! untouched files rather than deleting originals before their
+//! replacements exist.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{TimeZone, Utc};
+
+use crate::error::Result;
+
+/// Counters for one migration run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MigrateStats {
+    /// Files read.
+    pub files_in: usize,
+    /// Files written.
+    pub files_out: usize,
+    /// Rows read.
+    pub rows_in: usize,
+    /// Rows kept after dedup.
+    pub rows_out: usize,
+}
+
+fn day_of(time_ms: i64) -> Option<String> {
+    Utc.timestamp_millis_opt(time_ms)
+        .single()
+        .map(|t| t.format("%Y-%m-%d").to_string())
+}
+
+/// Timestamp column for a kind: trades carry it second, everything
+/// else first.
+fn row_time(kind: &str, line: &str) -> Option<i64> {
+    let mut fields = line.split(',');
+    if kind == "trades" {
+        fields.nth(1)?.parse().ok()
+    } else {
+        fields.next()?.parse().ok()
+    }
+}
+
+/// The `<kind>` part of a daily file name (`trades`, `klines-1m`, ...).
+fn kind_of(name: &str) -> Option<&str> {
+    let stem = name.strip_suffix(".csv")?;
+    // The day is the trailing `-YYYY-MM-DD` (10 chars plus the dash).
+    if stem.len() < 11 {
+        return None;
+    }
+    Some(&stem[..stem.len() - 11])
+}
+
+/// Migrate one symbol directory; returns updated counters.
+fn migrate_symbol(dir: &Path, stats: &mut MigrateStats) -> Result<()> {
+    // kind -> day -> sorted, deduplicated rows.
+    let mut buckets: BTreeMap<String, BTreeMap<String, Vec<(i64, String)>>> = BTreeMap::new();
+    let mut old_files = Vec::new();
+    for file in std::fs::read_dir(dir)? {
+        let path = file?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        let Some(kind) = kind_of(name).map(str::to_string) else {
+            continue;
+        };
+        if !name.ends_with(".csv") {
+            continue;
+        }
+        stats.files_in += 1;
+        let text = std::fs::read_to_string(&path)?;
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            stats.rows_in += 1;
+            let Some(time) = row_time(&kind, line) else {
+                continue;
+            };
+            let Some(day) = day_of(time) else {
+                continue;
+            };
+            buckets
+                .entry(kind.clone())
+                .or_default()
+                .entry(day)
+                .or_default()
+                .push((time, line.to_string()));
+        }
+        old_files.push(path);
+    }
+    // Write each bucket to a temp name, then rename it into place right
+    // away — a crash mid-loop leaves some files already migrated and the
+    // rest untouched under their original names, never both gone at
+    // once. Old files whose name is reused as a target are overwritten
+    // by the rename itself; only the leftovers (a file whose rows were
+    // redistributed to other days) need an explicit delete, and that
+    // happens last, once every target is safely in place.
+    let mut targets = std::collections::HashSet::new();
+    for (kind, days) in &mut buckets {
+        for (day, rows) in days {
+            rows.sort();
+            rows.dedup_by(|a, b| a.1 == b.1);
+            stats.rows_out += rows.len();
+            let target = dir.join(format!("{kind}-{day}.csv"));
+            let tmp = dir.join(format!("{kind}-{day}.csv.migrate"));
+            let body: String = rows.iter().map(|(_, line)| format!("{line}\n")).collect();
+            std::fs::write(&tmp, body)?;
+            std::fs::rename(tmp, &target)?;
+            stats.files_out += 1;
+            targets.insert(target);
+        }
+    }
+    for path in old_files {
+        if !targets.contains(&path) {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Migrate the whole archive tree.
+pub fn migrate_tree(root: &Path) -> Result<MigrateStats> {
+    let mut stats = MigrateStats::default();
+    if !root.exists() {
+        return Ok(stats);
+    }
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() && entry.file_name() != "wal" {
+            migrate_symbol(&entry.path(), &mut stats)?;
+            tracing::info!(symbol = %entry.file_name().to_string_lossy(), "migrated");
+        }
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resplits_sorts_and_dedups() {
+        let root = std::env::temp_dir().join(format!("bzl-migrate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        // Day-14 file holding an out-of-order duplicate plus a row that
+        // belongs to day 15.
+        std::fs::write(
+            dir.join("trades-2023-11-14.csv"),
+            "2,1699920001000,101.0,1.0,false\n\
+             1,1699920000000,100.0,1.0,true\n\
+             1,1699920000000,100.0,1.0,true\n\
+             9,1700006400000,105.0,1.0,false\n",
+        )
+        .unwrap();
+        let stats = migrate_tree(&root).unwrap();
+        assert_eq!(stats.files_in, 1);
+        assert_eq!(stats.files_out, 2);
+        assert_eq!(stats.rows_in, 4);
+        assert_eq!(stats.rows_out, 3);
+        let day14 = std::fs::read_to_string(dir.join("trades-2023-11-14.csv")).unwrap();
+        assert_eq!(
+            day14,
+            "1,1699920000000,100.0,1.0,true\n2,1699920001000,101.0,1.0,false\n"
+        );
+        let day15 = std::fs::read_to_string(dir.join("trades-2023-11-15.csv")).unwrap();
+        assert!(day15.starts_with("9,"));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
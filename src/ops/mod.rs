@@ -0,0 +1,15 @@
+//! Operational tooling behind the `bzl` command.
+
+pub mod archive;
+pub mod asof;
+pub mod compact;
+pub mod import;
+pub mod known_gaps;
+pub mod leadership;
+pub mod loadgen;
+pub mod manifest;
+pub mod migrate;
+pub mod repair;
+pub mod replay;
+pub mod report;
+pub mod sharding;
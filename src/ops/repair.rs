@@ -0,0 +1,162 @@
+//! Targeted repair of bad or missing rows.
+//!
+//! `bzl repair` re-downloads the specified range from the exchange
+//! first, only deletes the stored range once that download has
+//! completed without error, then re-inserts the replacement and
+//! compares the stored row count against what was fetched — for fixing
+//! known-bad stretches without touching the rest of the archive, and
+//! without risking the range on a network hiccup mid-repair.
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::exchange::binance::Binance;
+use crate::exchange::Exchange;
+use crate::model::{Interval, Kline};
+use crate::pipeline::Event;
+use crate::sink::clickhouse::ClickHouseSink;
+use crate::sink::Sink;
+
+/// Outcome of one repair run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairReport {
+    /// Candles fetched from the exchange.
+    pub downloaded: usize,
+    /// Candles stored in the range after the repair.
+    pub stored: usize,
+    /// Set when the exchange confirmed the range holds no candles at
+    /// all, and the range was recorded in `known_gaps` accordingly.
+    pub tagged_known_gap: bool,
+}
+
+impl RepairReport {
+    /// Whether the post-check found the range consistent.
+    pub fn consistent(&self) -> bool {
+        self.downloaded == self.stored
+    }
+}
+
+/// Delete and re-download one symbol/interval range.
+pub async fn repair(
+    db: &ClickHouse,
+    exchange: &Binance,
+    http: &reqwest::Client,
+    symbol: &str,
+    interval: Interval,
+    from: i64,
+    to: i64,
+) -> Result<RepairReport> {
+    let symbol = symbol.to_uppercase();
+    let step = interval.ms();
+    let mut cursor = from;
+    let mut fetched: Vec<Kline> = Vec::new();
+    let mut first_response_empty = false;
+    // Fetch the whole replacement range into memory first. Nothing gets
+    // deleted until this loop returns without a hard error, so a
+    // network hiccup or exhausted key/host rotation leaves the stored
+    // range untouched instead of wiping it with nothing to replace it.
+    for i in 0..100_000 {
+        if cursor >= to {
+            break;
+        }
+        let klines = exchange
+            .fetch_klines(http, &symbol, interval.as_str(), Some(cursor), Some(to), 1000)
+            .await?;
+        if klines.is_empty() {
+            if i == 0 {
+                first_response_empty = true;
+            }
+            break;
+        }
+        let next = klines.last().expect("non-empty").open_time + step;
+        if next <= cursor {
+            // No forward progress (a short or repeated response):
+            // stop rather than hammering the same page forever.
+            tracing::warn!(symbol, cursor, "repair made no progress; stopping");
+            fetched.extend(klines);
+            break;
+        }
+        cursor = next;
+        fetched.extend(klines);
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    }
+
+    // Only now that the download succeeded do we supersede the suspect
+    // rows. On Replacing engines the re-inserted rows would win anyway;
+    // the delete keeps plain MergeTree correct.
+    let from_dt = crate::db::dt64_literal(from);
+    let to_dt = crate::db::dt64_literal(to);
+    db.execute(&format!(
+        "ALTER TABLE klines DELETE WHERE symbol = '{symbol}' \
+         AND interval = '{interval}' AND open_time >= {from_dt} AND open_time < {to_dt}"
+    ))
+    .await?;
+
+    let downloaded = fetched.len();
+    let mut sink = ClickHouseSink::new(db.clone());
+    let events: Vec<Event> = fetched.into_iter().map(Event::Kline).collect();
+    sink.write(&events).await?;
+    sink.flush().await?;
+
+    let stored: usize = db
+        .query_scalar(&format!(
+            "SELECT count() FROM klines WHERE symbol = '{symbol}' \
+             AND interval = '{interval}' AND open_time >= {from_dt} AND open_time < {to_dt}"
+        ))
+        .await?
+        .trim()
+        .parse()
+        .unwrap_or(0);
+    // A live request that comes back empty on the very first page proves
+    // the exchange has nothing here — as opposed to the collector simply
+    // never having tried. Recording it stops future gap reports and
+    // repairs from chasing data that was never there.
+    let tagged_known_gap = downloaded == 0 && stored == 0 && first_response_empty;
+    if tagged_known_gap {
+        crate::ops::known_gaps::tag(
+            db,
+            &symbol,
+            interval,
+            from,
+            to,
+            "repair_confirmed_empty",
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .await?;
+        tracing::info!(symbol, from, to, "repair confirmed an exchange-side gap; tagged");
+    }
+    let report = RepairReport {
+        downloaded,
+        stored,
+        tagged_known_gap,
+    };
+    if !report.consistent() {
+        tracing::warn!(
+            symbol,
+            downloaded,
+            stored,
+            "repair post-check mismatch: stored count differs from download"
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_consistency() {
+        assert!(RepairReport {
+            downloaded: 10,
+            stored: 10,
+            tagged_known_gap: false,
+        }
+        .consistent());
+        assert!(!RepairReport {
+            downloaded: 10,
+            stored: 9,
+            tagged_known_gap: false,
+        }
+        .consistent());
+    }
+}
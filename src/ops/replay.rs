@@ -0,0 +1,140 @@
+//! Replay archived data as a simulated live stream.
+//!
+//! Reads trades and klines back out of the CSV archive and re-emits
+//! them through the normal pipeline (processors and sinks), either as
+//! fast as possible (`speed = 0`) or paced relative to the original
+//! timestamps (`speed = 1` is real time, `60` is a minute per second)
+//! — so downstream systems can be tested against historical market
+//! conditions.
+
+use std::path::Path;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::pipeline::spec::Flow;
+use crate::pipeline::Event;
+
+use super::import::parse_trade_row;
+use crate::storage::kline_store::parse_csv_row;
+
+/// Load every archived event for one symbol in `[from, to)`, sorted by
+/// timestamp.
+pub(crate) fn load_events(root: &Path, symbol: &str, from: i64, to: i64) -> Result<Vec<Event>> {
+    let mut events = Vec::new();
+    let dir = root.join(symbol);
+    if !dir.exists() {
+        return Ok(events);
+    }
+    for file in std::fs::read_dir(&dir)? {
+        let path = file?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.starts_with("trades-") && name.ends_with(".csv") {
+            let text = std::fs::read_to_string(&path)?;
+            for line in text.lines() {
+                if let Ok(t) = parse_trade_row(symbol, line) {
+                    if t.trade_time >= from && t.trade_time < to {
+                        events.push(Event::Trade(t));
+                    }
+                }
+            }
+        } else if name.starts_with("klines-") && name.ends_with(".csv") {
+            let interval = name
+                .strip_prefix("klines-")
+                .and_then(|r| r.split('-').next())
+                .unwrap_or("1m");
+            let text = std::fs::read_to_string(&path)?;
+            for line in text.lines() {
+                if let Ok(k) = parse_csv_row(symbol, interval, line) {
+                    if k.open_time >= from && k.open_time < to {
+                        events.push(Event::Kline(k));
+                    }
+                }
+            }
+        }
+    }
+    events.sort_by_key(|e| e.time());
+    Ok(events)
+}
+
+/// Replay the archive through the configured flows; returns events
+/// emitted.
+pub async fn replay(
+    cfg: &Config,
+    flows: &mut [Flow],
+    symbols: &[String],
+    from: i64,
+    to: i64,
+    speed: f64,
+) -> Result<usize> {
+    let root = Path::new(&cfg.data_dir);
+    let mut emitted = 0;
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        let events = load_events(root, &symbol, from, to)?;
+        tracing::info!(symbol, events = events.len(), "replaying");
+        let mut last_time: Option<i64> = None;
+        for event in events {
+            if speed > 0.0 {
+                if let Some(last) = last_time {
+                    let gap_ms = ((event.time() - last).max(0) as f64 / speed) as u64;
+                    if gap_ms > 0 {
+                        tokio::time::sleep(std::time::Duration::from_millis(gap_ms)).await;
+                    }
+                }
+                last_time = Some(event.time());
+            }
+            for flow in flows.iter_mut() {
+                if !flow.source.symbol.eq_ignore_ascii_case(event.symbol()) {
+                    continue;
+                }
+                let out = flow.pipeline.run(event.clone());
+                if out.is_empty() {
+                    continue;
+                }
+                for sink in flow.sinks.iter_mut() {
+                    sink.write(&out).await?;
+                }
+            }
+            emitted += 1;
+        }
+    }
+    for flow in flows.iter_mut() {
+        for sink in flow.sinks.iter_mut() {
+            sink.flush().await?;
+        }
+    }
+    Ok(emitted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_events_sorted_and_windowed() {
+        let root = std::env::temp_dir().join(format!("bzl-replay-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("trades-2023-11-14.csv"),
+            "2,2000,100.0,1.0,false\n1,1000,99.0,1.0,true\n9,9000,105.0,1.0,false\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("klines-1m-2023-11-14.csv"),
+            "0,1,2,0.5,1.5,10,59999,15,3\n",
+        )
+        .unwrap();
+        let events = load_events(&root, "BTCUSDT", 0, 5_000).unwrap();
+        // Window excludes the trade at 9000; events come out time-sorted
+        // with the minute candle (close time 59999) last.
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0].time(), 1_000);
+        assert_eq!(events[1].time(), 2_000);
+        assert_eq!(events[2].time(), 59_999);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+//! Data completeness reporting.
+
+use futures_util::StreamExt;
+use serde::Serialize;
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+use crate::model::Interval;
+use crate::ops::known_gaps::{self, KnownGap};
+use crate::storage::kline_store::KlineStore;
+
+/// Coverage numbers for one symbol/interval.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Coverage {
+    /// Symbol the report covers.
+    pub symbol: String,
+    /// Interval the report covers.
+    pub interval: Interval,
+    /// Open time of the earliest candle found, if any.
+    pub earliest: Option<i64>,
+    /// Open time of the latest candle found, if any.
+    pub latest: Option<i64>,
+    /// Candles actually present.
+    pub actual: u64,
+    /// Candles expected between earliest and latest.
+    pub expected: u64,
+    /// Number of gaps (runs of missing candles).
+    pub gaps: u64,
+    /// Longest gap in milliseconds.
+    pub longest_gap_ms: i64,
+    /// Candles inside a confirmed exchange-side gap ([`known_gaps`]),
+    /// already excluded from `expected` and `completeness_pct`.
+    pub known_gap_candles: u64,
+    /// `actual / expected`, in percent.
+    pub completeness_pct: f64,
+}
+
+/// Compute coverage from sorted candle open times.
+pub fn compute_coverage(symbol: &str, interval: Interval, open_times: &[i64]) -> Coverage {
+    compute_coverage_with_known_gaps(symbol, interval, open_times, &[])
+}
+
+/// Like [`compute_coverage`], but candles inside `known_gaps` (confirmed
+/// by [`repair`](crate::ops::repair::repair) to hold no exchange data)
+/// are dropped from `expected` so they no longer count against
+/// completeness.
+pub fn compute_coverage_with_known_gaps(
+    symbol: &str,
+    interval: Interval,
+    open_times: &[i64],
+    known_gaps: &[KnownGap],
+) -> Coverage {
+    let step = interval.ms();
+    let earliest = open_times.first().copied();
+    let latest = open_times.last().copied();
+    let mut gaps = 0;
+    let mut longest_gap_ms = 0;
+    for pair in open_times.windows(2) {
+        let delta = pair[1] - pair[0];
+        if delta > step {
+            gaps += 1;
+            longest_gap_ms = longest_gap_ms.max(delta - step);
+        }
+    }
+    let expected = match (earliest, latest) {
+        (Some(first), Some(last)) => ((last - first) / step + 1) as u64,
+        _ => 0,
+    };
+    let known_gap_candles = match (earliest, latest) {
+        (Some(first), Some(last)) => known_gaps::covered_candles(known_gaps, first, last, step),
+        _ => 0,
+    };
+    let expected = expected.saturating_sub(known_gap_candles);
+    let actual = open_times.len() as u64;
+    Coverage {
+        symbol: symbol.to_string(),
+        interval,
+        earliest,
+        latest,
+        actual,
+        expected,
+        gaps,
+        longest_gap_ms,
+        known_gap_candles,
+        completeness_pct: if expected == 0 {
+            0.0
+        } else {
+            actual as f64 * 100.0 / expected as f64
+        },
+    }
+}
+
+/// Scan the store and report coverage for one symbol/interval window.
+pub async fn coverage_for(
+    store: &KlineStore,
+    symbol: &str,
+    interval: Interval,
+    from: i64,
+    to: i64,
+) -> Result<Coverage> {
+    coverage_for_with_known_gaps(store, None, symbol, interval, from, to).await
+}
+
+/// Like [`coverage_for`], but also excludes any confirmed exchange-side
+/// gaps recorded for `symbol`/`interval` in ClickHouse.
+pub async fn coverage_for_with_known_gaps(
+    store: &KlineStore,
+    known_gaps_db: Option<&ClickHouse>,
+    symbol: &str,
+    interval: Interval,
+    from: i64,
+    to: i64,
+) -> Result<Coverage> {
+    let mut open_times = Vec::new();
+    let mut stream = Box::pin(store.iter_range(symbol, interval.as_str(), from, to));
+    while let Some(kline) = stream.next().await {
+        open_times.push(kline?.open_time);
+    }
+    open_times.sort_unstable();
+    open_times.dedup();
+    let known_gaps = match known_gaps_db {
+        Some(db) => known_gaps::overlapping(db, symbol, interval, from, to).await?,
+        None => Vec::new(),
+    };
+    Ok(compute_coverage_with_known_gaps(
+        symbol,
+        interval,
+        &open_times,
+        &known_gaps,
+    ))
+}
+
+/// Render coverage rows as a plain-text table.
+pub fn render_text(rows: &[Coverage]) -> String {
+    let mut out = String::from(
+        "symbol\tinterval\tearliest\tlatest\tcandles\tgaps\tlongest_gap_ms\tcompleteness\n",
+    );
+    for c in rows {
+        out.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}/{}\t{}\t{}\t{:.2}%\n",
+            c.symbol,
+            c.interval,
+            c.earliest.map_or("-".into(), |t| t.to_string()),
+            c.latest.map_or("-".into(), |t| t.to_string()),
+            c.actual,
+            c.expected,
+            c.gaps,
+            c.longest_gap_ms,
+            c.completeness_pct
+        ));
+    }
+    out
+}
+
+/// Render coverage rows as a minimal HTML table for dashboards.
+pub fn render_html(rows: &[Coverage]) -> String {
+    let mut out = String::from(
+        "<table><tr><th>symbol</th><th>interval</th><th>candles</th>\
+         <th>gaps</th><th>longest gap (ms)</th><th>completeness</th></tr>\n",
+    );
+    for c in rows {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}/{}</td><td>{}</td><td>{}</td><td>{:.2}%</td></tr>\n",
+            c.symbol, c.interval, c.actual, c.expected, c.gaps, c.longest_gap_ms, c.completeness_pct
+        ));
+    }
+    out.push_str("</table>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_coverage_has_no_gaps() {
+        let times: Vec<i64> = (0..10).map(|i| i * 60_000).collect();
+        let c = compute_coverage("BTCUSDT", Interval::M1, &times);
+        assert_eq!(c.actual, 10);
+        assert_eq!(c.expected, 10);
+        assert_eq!(c.gaps, 0);
+        assert_eq!(c.completeness_pct, 100.0);
+    }
+
+    #[test]
+    fn gaps_are_counted_and_measured() {
+        // Missing candles at 2,3 and at 7.
+        let times: Vec<i64> = [0, 1, 4, 5, 6, 8, 9].iter().map(|i| i * 60_000).collect();
+        let c = compute_coverage("BTCUSDT", Interval::M1, &times);
+        assert_eq!(c.actual, 7);
+        assert_eq!(c.expected, 10);
+        assert_eq!(c.gaps, 2);
+        assert_eq!(c.longest_gap_ms, 2 * 60_000);
+        assert!((c.completeness_pct - 70.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn known_gaps_are_excluded_from_expected() {
+        // Missing candles at 2,3 and at 7, same as above, but 2,3 is a
+        // confirmed exchange-side gap and should stop counting against
+        // completeness.
+        let times: Vec<i64> = [0, 1, 4, 5, 6, 8, 9].iter().map(|i| i * 60_000).collect();
+        let known = vec![KnownGap {
+            symbol: "BTCUSDT".to_string(),
+            interval: "1m".to_string(),
+            from_ms: 2 * 60_000,
+            to_ms: 4 * 60_000,
+            reason: "repair_confirmed_empty".to_string(),
+            detected_at: 0,
+        }];
+        let c = compute_coverage_with_known_gaps("BTCUSDT", Interval::M1, &times, &known);
+        assert_eq!(c.actual, 7);
+        assert_eq!(c.known_gap_candles, 2);
+        assert_eq!(c.expected, 8);
+        // Still one real gap left (candle 7).
+        assert_eq!(c.gaps, 2);
+        assert!((c.completeness_pct - 87.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_series_reports_zero() {
+        let c = compute_coverage("BTCUSDT", Interval::M1, &[]);
+        assert_eq!(c.expected, 0);
+        assert_eq!(c.completeness_pct, 0.0);
+        assert!(c.earliest.is_none());
+    }
+}
@@ -0,0 +1,238 @@
+//! Work sharding across collector hosts.
+//!
+//! Several collector instances split the symbol universe through a
+//! lease table in ClickHouse: each host claims its fair share, renews
+//! its leases on a heartbeat, and picks up symbols whose leases
+//! expired when another host died. ClickHouse has no compare-and-set,
+//! so a claim is optimistic: after writing its claims a host re-reads
+//! the table and keeps only the symbols where its own write actually
+//! won, so two hosts racing for the same free symbol on a simultaneous
+//! bring-up converge on a single holder within one extra round trip
+//! instead of both collecting it until the next TTL cycle exposes the
+//! conflict.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::db::ClickHouse;
+use crate::error::Result;
+
+/// The `[sharding]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardingConfig {
+    /// Identity of this host; defaults to `hostname-pid`.
+    #[serde(default)]
+    pub host_id: Option<String>,
+    /// Lease lifetime; a host missing renewals for this long loses its
+    /// symbols to the survivors.
+    #[serde(default = "default_ttl_secs")]
+    pub ttl_secs: u64,
+}
+
+fn default_ttl_secs() -> u64 {
+    30
+}
+
+impl ShardingConfig {
+    /// The effective host identity.
+    pub fn host_id(&self) -> String {
+        self.host_id.clone().unwrap_or_else(|| {
+            let host = std::env::var("HOSTNAME").unwrap_or_else(|_| "collector".to_string());
+            format!("{host}-{}", std::process::id())
+        })
+    }
+}
+
+/// One symbol lease as stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lease {
+    /// Leased symbol.
+    pub symbol: String,
+    /// Holding host.
+    pub holder: String,
+    /// Expiry in epoch milliseconds.
+    pub expires_at: i64,
+    /// Write timestamp used by the Replacing engine.
+    pub updated_at: i64,
+}
+
+/// Decide which symbols `me` should hold now: keep unexpired own
+/// leases, then claim from the free pool up to a fair share of the
+/// universe split across the currently alive holders.
+pub fn plan_claims(all: &[String], leases: &[Lease], me: &str, now: i64) -> Vec<String> {
+    let alive: HashMap<&str, &Lease> = leases
+        .iter()
+        .filter(|l| l.expires_at > now)
+        .map(|l| (l.symbol.as_str(), l))
+        .collect();
+    let mut holders: HashSet<&str> = alive.values().map(|l| l.holder.as_str()).collect();
+    holders.insert(me);
+    let target = all.len().div_ceil(holders.len());
+    let mut mine: Vec<String> = all
+        .iter()
+        .filter(|s| alive.get(s.as_str()).is_some_and(|l| l.holder == me))
+        .cloned()
+        .collect();
+    for symbol in all {
+        if mine.len() >= target {
+            break;
+        }
+        if !alive.contains_key(symbol.as_str()) && !mine.contains(symbol) {
+            mine.push(symbol.clone());
+        }
+    }
+    mine.sort();
+    mine
+}
+
+/// Lease-table coordination against ClickHouse.
+pub struct Coordinator {
+    db: ClickHouse,
+    me: String,
+    ttl_ms: i64,
+}
+
+impl Coordinator {
+    /// Build a coordinator for this host.
+    pub fn new(db: ClickHouse, cfg: &ShardingConfig) -> Self {
+        Self {
+            db,
+            me: cfg.host_id(),
+            ttl_ms: (cfg.ttl_secs as i64) * 1000,
+        }
+    }
+
+    /// This host's identity.
+    pub fn host_id(&self) -> &str {
+        &self.me
+    }
+
+    /// Create the lease table.
+    pub async fn ensure_schema(&self) -> Result<()> {
+        self.db
+            .execute(
+                "CREATE TABLE IF NOT EXISTS collector_leases (\
+                 symbol String, holder String, expires_at Int64, updated_at Int64) \
+                 ENGINE = ReplacingMergeTree(updated_at) ORDER BY symbol",
+            )
+            .await
+    }
+
+    /// Current latest lease per symbol.
+    async fn load_leases(&self) -> Result<Vec<Lease>> {
+        self.db
+            .query_rows(
+                "SELECT symbol, argMax(holder, updated_at) AS holder, \
+                 argMax(expires_at, updated_at) AS expires_at, \
+                 max(updated_at) AS updated_at \
+                 FROM collector_leases GROUP BY symbol",
+            )
+            .await
+    }
+
+    /// Claim/renew this host's share of the universe; returns the
+    /// symbols this host now holds.
+    ///
+    /// The read-then-write above has no compare-and-set behind it, so
+    /// two hosts can both see a symbol as free and both write a claim
+    /// for it. After writing, re-read the table and keep only the
+    /// symbols where our own claim is the one that stuck; a host that
+    /// lost the race drops the symbol immediately instead of believing
+    /// it holds a lease it doesn't.
+    pub async fn claim_and_renew(&self, all: &[String], now: i64) -> Result<Vec<String>> {
+        let leases = self.load_leases().await?;
+        let mine = plan_claims(all, &leases, &self.me, now);
+        let rows: Vec<Lease> = mine
+            .iter()
+            .map(|symbol| Lease {
+                symbol: symbol.clone(),
+                holder: self.me.clone(),
+                expires_at: now + self.ttl_ms,
+                updated_at: now,
+            })
+            .collect();
+        self.db.insert_rows("collector_leases", &rows).await?;
+
+        let confirmed = self.load_leases().await?;
+        let holders: HashMap<&str, &Lease> =
+            confirmed.iter().map(|l| (l.symbol.as_str(), l)).collect();
+        let won: Vec<String> = mine
+            .into_iter()
+            .filter(|symbol| holders.get(symbol.as_str()).is_some_and(|l| l.holder == self.me))
+            .collect();
+        if won.len() != rows.len() {
+            tracing::warn!(
+                host = self.me,
+                claimed = rows.len(),
+                confirmed = won.len(),
+                "shard claim raced with another host; dropped contested symbols"
+            );
+        }
+        Ok(won)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lease(symbol: &str, holder: &str, expires_at: i64) -> Lease {
+        Lease {
+            symbol: symbol.to_string(),
+            holder: holder.to_string(),
+            expires_at,
+            updated_at: 0,
+        }
+    }
+
+    fn universe() -> Vec<String> {
+        ["AUSDT", "BUSDT", "CUSDT", "DUSDT"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn single_host_claims_everything() {
+        let mine = plan_claims(&universe(), &[], "host-a", 1_000);
+        assert_eq!(mine.len(), 4);
+    }
+
+    #[test]
+    fn two_hosts_split_fairly_without_double_collecting() {
+        let leases = vec![
+            lease("AUSDT", "host-b", 10_000),
+            lease("BUSDT", "host-b", 10_000),
+        ];
+        let mine = plan_claims(&universe(), &leases, "host-a", 1_000);
+        // Fair share of 4 across 2 hosts is 2; host-b's symbols are
+        // untouchable.
+        assert_eq!(mine, vec!["CUSDT".to_string(), "DUSDT".to_string()]);
+    }
+
+    #[test]
+    fn expired_leases_are_reclaimed() {
+        let leases = vec![
+            lease("AUSDT", "host-b", 500),
+            lease("BUSDT", "host-b", 500),
+        ];
+        // host-b stopped heartbeating: its leases expired and host-a
+        // absorbs the whole universe.
+        let mine = plan_claims(&universe(), &leases, "host-a", 1_000);
+        assert_eq!(mine.len(), 4);
+    }
+
+    #[test]
+    fn own_leases_are_kept_on_renewal() {
+        let leases = vec![
+            lease("AUSDT", "host-a", 10_000),
+            lease("BUSDT", "host-b", 10_000),
+            lease("CUSDT", "host-b", 10_000),
+        ];
+        let mine = plan_claims(&universe(), &leases, "host-a", 1_000);
+        assert!(mine.contains(&"AUSDT".to_string()));
+        assert!(mine.contains(&"DUSDT".to_string()));
+        assert_eq!(mine.len(), 2);
+    }
+}
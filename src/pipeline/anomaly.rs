@@ -0,0 +1,260 @@
+//! Ingest-path anomaly detection.
+//!
+//! Obviously-broken rows (zero prices, inverted candles, out-of-order
+//! open times, absurd jumps) must never silently reach the archive.
+//! The detector replaces each offending event with a
+//! [`Quarantine`](super::Quarantine) record plus an [`Alert`], so the
+//! sinks route it to the quarantine table instead of the real one.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Alert, Event, Processor, Quarantine};
+use crate::model::{Kline, Trade};
+
+/// How validation failures are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationMode {
+    /// Quarantine bad rows and keep going.
+    #[default]
+    Lenient,
+    /// Mark violations strict; the collector aborts the batch.
+    Strict,
+}
+
+/// Tuning for the anomaly detector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// Maximum tolerated move versus the previous price, in percent.
+    /// Larger single-step moves are quarantined as absurd jumps.
+    #[serde(default = "default_max_jump_pct")]
+    pub max_jump_pct: f64,
+
+    /// Strict or lenient handling of violations.
+    #[serde(default)]
+    pub mode: ValidationMode,
+}
+
+fn default_max_jump_pct() -> f64 {
+    25.0
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            max_jump_pct: default_max_jump_pct(),
+            mode: ValidationMode::default(),
+        }
+    }
+}
+
+/// Stateful per-symbol anomaly checks run on the hot path.
+pub struct AnomalyDetector {
+    config: AnomalyConfig,
+    last_trade_price: HashMap<String, f64>,
+    last_open_time: HashMap<(String, String), i64>,
+    last_close: HashMap<(String, String), f64>,
+}
+
+impl AnomalyDetector {
+    /// Create a detector with the given tuning.
+    pub fn new(config: AnomalyConfig) -> Self {
+        Self {
+            config,
+            last_trade_price: HashMap::new(),
+            last_open_time: HashMap::new(),
+            last_close: HashMap::new(),
+        }
+    }
+
+    fn jump_pct(previous: f64, current: f64) -> f64 {
+        if previous <= 0.0 {
+            return 0.0;
+        }
+        ((current - previous) / previous).abs() * 100.0
+    }
+
+    fn check_trade(&mut self, t: &Trade) -> Option<String> {
+        if t.price <= 0.0 {
+            return Some(format!("non-positive price {}", t.price));
+        }
+        if t.qty <= 0.0 {
+            return Some(format!("non-positive qty {}", t.qty));
+        }
+        if let Some(&prev) = self.last_trade_price.get(&t.symbol) {
+            let jump = Self::jump_pct(prev, t.price);
+            if jump > self.config.max_jump_pct {
+                return Some(format!(
+                    "price jumped {jump:.1}% ({prev} -> {})",
+                    t.price
+                ));
+            }
+        }
+        self.last_trade_price.insert(t.symbol.clone(), t.price);
+        None
+    }
+
+    fn check_kline(&mut self, k: &Kline) -> Option<String> {
+        if k.open <= 0.0 || k.high <= 0.0 || k.low <= 0.0 || k.close <= 0.0 {
+            return Some("non-positive price field".to_string());
+        }
+        if k.high < k.low {
+            return Some(format!("high {} below low {}", k.high, k.low));
+        }
+        if k.open > k.high || k.open < k.low || k.close > k.high || k.close < k.low {
+            return Some("open/close outside high-low range".to_string());
+        }
+        let key = (k.symbol.clone(), k.interval.clone());
+        if let Some(&last) = self.last_open_time.get(&key) {
+            if k.open_time <= last {
+                return Some(format!(
+                    "open_time {} not after previous {last}",
+                    k.open_time
+                ));
+            }
+        }
+        if let Some(&prev_close) = self.last_close.get(&key) {
+            let jump = Self::jump_pct(prev_close, k.open);
+            if jump > self.config.max_jump_pct {
+                return Some(format!(
+                    "open jumped {jump:.1}% from previous close {prev_close}"
+                ));
+            }
+        }
+        self.last_open_time.insert(key.clone(), k.open_time);
+        self.last_close.insert(key, k.close);
+        None
+    }
+
+    fn quarantine(&self, event: &Event, reason: String) -> Vec<Event> {
+        let payload = serde_json::to_string(event).unwrap_or_default();
+        let alert = Alert {
+            symbol: event.symbol().to_string(),
+            source: "anomaly".to_string(),
+            message: format!("quarantined: {reason}"),
+            time: event.time(),
+        };
+        vec![
+            Event::Quarantined(Quarantine {
+                symbol: event.symbol().to_string(),
+                reason,
+                time: event.time(),
+                payload,
+                strict: self.config.mode == ValidationMode::Strict,
+            }),
+            Event::Alert(alert),
+        ]
+    }
+}
+
+impl Processor for AnomalyDetector {
+    fn name(&self) -> &str {
+        "anomaly"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let reason = match &event {
+            Event::Trade(t) => self.check_trade(t),
+            Event::Kline(k) => self.check_kline(k),
+            _ => None,
+        };
+        match reason {
+            Some(reason) => self.quarantine(&event, reason),
+            None => vec![event],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detector() -> AnomalyDetector {
+        AnomalyDetector::new(AnomalyConfig::default())
+    }
+
+    fn trade(price: f64, qty: f64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price,
+            qty,
+            trade_time: 1_700_000_000_000,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn kline(open_time: i64, open: f64, high: f64, low: f64, close: f64) -> Event {
+        Event::Kline(Kline {
+            symbol: "BTCUSDT".into(),
+            interval: "1m".into(),
+            open_time,
+            close_time: open_time + 59_999,
+            open,
+            high,
+            low,
+            close,
+            volume: 1.0,
+            quote_volume: 1.0,
+            trade_count: 1,
+        })
+    }
+
+    fn is_quarantined(out: &[Event]) -> bool {
+        matches!(out.first(), Some(Event::Quarantined(_)))
+    }
+
+    #[test]
+    fn clean_events_pass() {
+        let mut d = detector();
+        assert_eq!(d.process(trade(50_000.0, 1.0)).len(), 1);
+        assert_eq!(d.process(kline(0, 10.0, 11.0, 9.0, 10.5)).len(), 1);
+    }
+
+    #[test]
+    fn zero_price_is_quarantined() {
+        let out = detector().process(trade(0.0, 1.0));
+        assert_eq!(out.len(), 2);
+        assert!(is_quarantined(&out));
+        assert!(matches!(&out[1], Event::Alert(_)));
+    }
+
+    #[test]
+    fn inverted_candle_is_quarantined() {
+        assert!(is_quarantined(&detector().process(kline(0, 10.0, 9.0, 11.0, 10.0))));
+    }
+
+    #[test]
+    fn out_of_order_candle_is_quarantined() {
+        let mut d = detector();
+        assert!(!is_quarantined(&d.process(kline(120_000, 10.0, 11.0, 9.0, 10.0))));
+        assert!(is_quarantined(&d.process(kline(60_000, 10.0, 11.0, 9.0, 10.0))));
+    }
+
+    #[test]
+    fn absurd_jump_is_quarantined() {
+        let mut d = detector();
+        assert!(!is_quarantined(&d.process(trade(100.0, 1.0))));
+        assert!(is_quarantined(&d.process(trade(200.0, 1.0))));
+        // The bad print must not poison the baseline for the next tick.
+        assert!(!is_quarantined(&d.process(trade(101.0, 1.0))));
+    }
+
+    #[test]
+    fn strict_mode_marks_violations() {
+        let mut strict = AnomalyDetector::new(AnomalyConfig {
+            mode: ValidationMode::Strict,
+            ..AnomalyConfig::default()
+        });
+        match strict.process(trade(0.0, 1.0)).first() {
+            Some(Event::Quarantined(q)) => assert!(q.strict),
+            other => panic!("expected quarantine, got {other:?}"),
+        }
+        match detector().process(trade(0.0, 1.0)).first() {
+            Some(Event::Quarantined(q)) => assert!(!q.strict),
+            other => panic!("expected quarantine, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Sampled best-bid/offer recording.
+//!
+//! Persisting every `bookTicker` update is ruinously chatty; sampling
+//! the maintained top-of-book at a fixed period (e.g. `100ms` or `1s`)
+//! is far cheaper to store and sufficient for most research. The
+//! sampler keeps the latest BBO per symbol and emits it once per
+//! period, timestamped by the update that crossed the boundary.
+
+use std::collections::HashMap;
+
+use super::{Event, Processor};
+use crate::error::{Error, Result};
+use crate::model::Bbo;
+
+/// Parse a sampling period like `100ms`, `1s` or `5s`.
+pub fn parse_period_ms(period: &str) -> Result<i64> {
+    let bad = || Error::Pipeline(format!("bad sampling period `{period}` (use e.g. 100ms, 1s)"));
+    if let Some(ms) = period.strip_suffix("ms") {
+        let ms: i64 = ms.parse().map_err(|_| bad())?;
+        if ms <= 0 {
+            return Err(bad());
+        }
+        return Ok(ms);
+    }
+    if let Some(secs) = period.strip_suffix('s') {
+        let secs: i64 = secs.parse().map_err(|_| bad())?;
+        if secs <= 0 {
+            return Err(bad());
+        }
+        return Ok(secs * 1000);
+    }
+    Err(bad())
+}
+
+/// Downsamples the BBO stream to a fixed period per symbol.
+pub struct BboSampler {
+    period_ms: i64,
+    latest: HashMap<String, Bbo>,
+    last_emitted: HashMap<String, i64>,
+}
+
+impl BboSampler {
+    /// Create a sampler with the given period string.
+    pub fn new(period: &str) -> Result<Self> {
+        Ok(Self {
+            period_ms: parse_period_ms(period)?,
+            latest: HashMap::new(),
+            last_emitted: HashMap::new(),
+        })
+    }
+}
+
+impl Processor for BboSampler {
+    fn name(&self) -> &str {
+        "bbo_sampler"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let Event::Bbo(bbo) = event else {
+            return vec![event];
+        };
+        let symbol = bbo.symbol.clone();
+        let time = bbo.time;
+        self.latest.insert(symbol.clone(), bbo);
+        let due = match self.last_emitted.get(&symbol) {
+            Some(&last) => time - last >= self.period_ms,
+            None => true,
+        };
+        if due {
+            self.last_emitted.insert(symbol.clone(), time);
+            vec![Event::Bbo(self.latest[&symbol].clone())]
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbo(time: i64, bid: f64) -> Event {
+        Event::Bbo(Bbo {
+            symbol: "BTCUSDT".into(),
+            bid_price: bid,
+            bid_qty: 1.0,
+            ask_price: bid + 0.1,
+            ask_qty: 1.0,
+            time,
+        })
+    }
+
+    #[test]
+    fn periods_parse() {
+        assert_eq!(parse_period_ms("100ms").unwrap(), 100);
+        assert_eq!(parse_period_ms("1s").unwrap(), 1_000);
+        assert!(parse_period_ms("0ms").is_err());
+        assert!(parse_period_ms("fast").is_err());
+    }
+
+    #[test]
+    fn samples_latest_bbo_per_period() {
+        let mut s = BboSampler::new("1s").unwrap();
+        // First update emits immediately.
+        assert_eq!(s.process(bbo(0, 100.0)).len(), 1);
+        // Updates inside the period are swallowed...
+        assert!(s.process(bbo(300, 101.0)).is_empty());
+        assert!(s.process(bbo(700, 102.0)).is_empty());
+        // ...and the boundary-crossing update emits the latest state.
+        let out = s.process(bbo(1_100, 103.0));
+        assert_eq!(out.len(), 1);
+        match &out[0] {
+            Event::Bbo(b) => assert_eq!(b.bid_price, 103.0),
+            other => panic!("expected bbo, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Builds candles from the live trade flow.
+
+use std::collections::HashMap;
+
+use super::{Event, Processor};
+use crate::error::Result;
+use crate::model::{Interval, Kline, Trade};
+
+/// Aggregates trades into fixed-interval candles — one or several
+/// intervals concurrently in the same process (`candle_builder(1m)` or
+/// `candle_builder(1m,1h,1d)`), each with separate per-symbol state.
+/// A candle is emitted once the first trade beyond its window arrives.
+/// Trades are consumed; other events pass through untouched.
+pub struct CandleBuilder {
+    intervals: Vec<Interval>,
+    // In-progress candles keyed by (symbol, interval).
+    open: HashMap<(String, Interval), Kline>,
+}
+
+impl CandleBuilder {
+    /// Create a builder for a comma-separated interval list
+    /// (e.g. `1m` or `1m,1h`).
+    pub fn new(intervals: &str) -> Result<Self> {
+        let mut parsed: Vec<Interval> = intervals
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<_>>()?;
+        parsed.sort();
+        parsed.dedup();
+        if parsed.is_empty() {
+            return Err(crate::error::Error::Pipeline(
+                "candle_builder needs at least one interval".to_string(),
+            ));
+        }
+        Ok(Self {
+            intervals: parsed,
+            open: HashMap::new(),
+        })
+    }
+
+    fn apply(&mut self, t: &Trade, interval: Interval) -> Option<Kline> {
+        let interval_ms = interval.ms();
+        let open_time = t.trade_time - t.trade_time.rem_euclid(interval_ms);
+        let key = (t.symbol.clone(), interval);
+        let mut closed = None;
+        match self.open.get_mut(&key) {
+            Some(candle) if candle.open_time == open_time => {
+                candle.high = candle.high.max(t.price);
+                candle.low = candle.low.min(t.price);
+                candle.close = t.price;
+                candle.volume += t.qty;
+                candle.quote_volume += t.notional();
+                candle.trade_count += 1;
+                return None;
+            }
+            Some(_) => {
+                closed = self.open.remove(&key);
+            }
+            None => {}
+        }
+        self.open.insert(
+            key,
+            Kline {
+                symbol: t.symbol.clone(),
+                interval: interval.as_str().to_string(),
+                open_time,
+                close_time: open_time + interval_ms - 1,
+                open: t.price,
+                high: t.price,
+                low: t.price,
+                close: t.price,
+                volume: t.qty,
+                quote_volume: t.notional(),
+                trade_count: 1,
+            },
+        );
+        closed
+    }
+}
+
+impl Processor for CandleBuilder {
+    fn name(&self) -> &str {
+        "candle_builder"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        match event {
+            Event::Trade(t) => {
+                let intervals = self.intervals.clone();
+                intervals
+                    .into_iter()
+                    .filter_map(|interval| self.apply(&t, interval).map(Event::Kline))
+                    .collect()
+            }
+            other => vec![other],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(time: i64, price: f64, qty: f64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: time,
+            price,
+            qty,
+            trade_time: time,
+            is_buyer_maker: false,
+        })
+    }
+
+    #[test]
+    fn emits_candle_when_window_rolls() {
+        let mut b = CandleBuilder::new("1m").unwrap();
+        assert!(b.process(trade(60_000, 100.0, 1.0)).is_empty());
+        assert!(b.process(trade(90_000, 110.0, 2.0)).is_empty());
+        assert!(b.process(trade(95_000, 95.0, 1.0)).is_empty());
+        let out = b.process(trade(120_000, 120.0, 1.0));
+        assert_eq!(out.len(), 1);
+        match &out[0] {
+            Event::Kline(k) => {
+                assert_eq!(k.open_time, 60_000);
+                assert_eq!(k.close_time, 119_999);
+                assert_eq!(k.open, 100.0);
+                assert_eq!(k.high, 110.0);
+                assert_eq!(k.low, 95.0);
+                assert_eq!(k.close, 95.0);
+                assert_eq!(k.volume, 4.0);
+                assert_eq!(k.trade_count, 3);
+            }
+            other => panic!("expected kline, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_interval() {
+        assert!(CandleBuilder::new("7q").is_err());
+    }
+
+    #[test]
+    fn builds_multiple_intervals_concurrently() {
+        let mut b = CandleBuilder::new("1m, 5m").unwrap();
+        // Fill minute 0..5 with one trade each.
+        for i in 0..5 {
+            let out = b.process(trade(i * 60_000, 100.0 + i as f64, 1.0));
+            // Each rolled minute closes exactly one 1m candle.
+            assert_eq!(out.len(), usize::from(i > 0));
+        }
+        // Crossing into minute 5 closes both the 1m and the 5m candle.
+        let out = b.process(trade(5 * 60_000, 110.0, 1.0));
+        assert_eq!(out.len(), 2);
+        let intervals: Vec<&str> = out
+            .iter()
+            .map(|e| match e {
+                Event::Kline(k) => k.interval.as_str(),
+                other => panic!("expected kline, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(intervals, vec!["1m", "5m"]);
+    }
+}
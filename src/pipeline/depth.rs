@@ -0,0 +1,144 @@
+//! Configurable L2 depth recording.
+//!
+//! Trims incoming order book snapshots to the top N levels, optionally
+//! drops levels outside a price band around the mid, and samples the
+//! result at a fixed period — `depth_recorder(10,1s)` or
+//! `depth_recorder(20,100ms,0.5)` for a ±0.5% band.
+
+use std::collections::HashMap;
+
+use super::bbo::parse_period_ms;
+use super::{Event, Processor};
+use crate::error::{Error, Result};
+use crate::model::DepthSnapshot;
+
+/// Trims, band-filters and samples depth snapshots.
+pub struct DepthRecorder {
+    top_n: usize,
+    period_ms: i64,
+    band_pct: Option<f64>,
+    last_emitted: HashMap<String, i64>,
+}
+
+impl DepthRecorder {
+    /// Parse a stage argument like `10,1s` or `20,100ms,0.5`.
+    pub fn from_arg(arg: &str) -> Result<Self> {
+        let parts: Vec<&str> = arg.split(',').map(str::trim).collect();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(Error::Pipeline(
+                "depth_recorder needs `N,period[,band_pct]`, e.g. `10,1s`".to_string(),
+            ));
+        }
+        let top_n: usize = parts[0]
+            .parse()
+            .map_err(|_| Error::Pipeline(format!("bad depth level count `{}`", parts[0])))?;
+        if top_n == 0 {
+            return Err(Error::Pipeline("depth level count must be positive".to_string()));
+        }
+        let period_ms = parse_period_ms(parts[1])?;
+        let band_pct = parts
+            .get(2)
+            .map(|p| {
+                p.parse::<f64>()
+                    .map_err(|_| Error::Pipeline(format!("bad band percent `{p}`")))
+            })
+            .transpose()?;
+        Ok(Self {
+            top_n,
+            period_ms,
+            band_pct,
+            last_emitted: HashMap::new(),
+        })
+    }
+
+    fn shape(&self, mut depth: DepthSnapshot) -> DepthSnapshot {
+        if let Some(band) = self.band_pct {
+            let mid = match (depth.bids.first(), depth.asks.first()) {
+                (Some(&(bid, _)), Some(&(ask, _))) => (bid + ask) / 2.0,
+                _ => 0.0,
+            };
+            if mid > 0.0 {
+                let lo = mid * (1.0 - band / 100.0);
+                let hi = mid * (1.0 + band / 100.0);
+                depth.bids.retain(|&(p, _)| p >= lo);
+                depth.asks.retain(|&(p, _)| p <= hi);
+            }
+        }
+        depth.bids.truncate(self.top_n);
+        depth.asks.truncate(self.top_n);
+        depth
+    }
+}
+
+impl Processor for DepthRecorder {
+    fn name(&self) -> &str {
+        "depth_recorder"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let Event::Depth(depth) = event else {
+            return vec![event];
+        };
+        let due = match self.last_emitted.get(&depth.symbol) {
+            Some(&last) => depth.time - last >= self.period_ms,
+            None => true,
+        };
+        if !due {
+            return Vec::new();
+        }
+        self.last_emitted.insert(depth.symbol.clone(), depth.time);
+        vec![Event::Depth(self.shape(depth))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(time: i64, levels: usize) -> Event {
+        Event::Depth(DepthSnapshot {
+            symbol: "BTCUSDT".into(),
+            time,
+            bids: (0..levels).map(|i| (100.0 - i as f64, 1.0)).collect(),
+            asks: (0..levels).map(|i| (100.1 + i as f64, 1.0)).collect(),
+        })
+    }
+
+    #[test]
+    fn trims_and_samples() {
+        let mut r = DepthRecorder::from_arg("3,1s").unwrap();
+        let out = r.process(depth(0, 10));
+        assert_eq!(out.len(), 1);
+        match &out[0] {
+            Event::Depth(d) => {
+                assert_eq!(d.bids.len(), 3);
+                assert_eq!(d.asks.len(), 3);
+            }
+            other => panic!("expected depth, got {other:?}"),
+        }
+        // Inside the sampling period: swallowed.
+        assert!(r.process(depth(500, 10)).is_empty());
+        assert_eq!(r.process(depth(1_500, 10)).len(), 1);
+    }
+
+    #[test]
+    fn band_filter_drops_far_levels() {
+        let mut r = DepthRecorder::from_arg("10,1s,2.0").unwrap();
+        let out = r.process(depth(0, 10));
+        match &out[0] {
+            // Mid ~100.05; ±2% keeps prices within ~[98.05, 102.05].
+            Event::Depth(d) => {
+                assert_eq!(d.bids.len(), 2);
+                assert_eq!(d.asks.len(), 2);
+            }
+            other => panic!("expected depth, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn bad_args_are_rejected() {
+        assert!(DepthRecorder::from_arg("10").is_err());
+        assert!(DepthRecorder::from_arg("0,1s").is_err());
+        assert!(DepthRecorder::from_arg("5,fast").is_err());
+    }
+}
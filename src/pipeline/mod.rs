@@ -0,0 +1,200 @@
+//! The event pipeline: processors run in order over every incoming event
+//! and may drop, rewrite or emit additional events.
+
+pub mod anomaly;
+pub mod bbo;
+pub mod candle;
+pub mod depth;
+pub mod rolling;
+pub mod script;
+pub mod spec;
+pub mod stitch;
+pub mod watchlist;
+pub mod whale;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{Kline, Trade};
+
+/// An alert raised by a processor (threshold crossed, anomaly found, ...).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    /// Symbol the alert refers to, if any.
+    pub symbol: String,
+    /// Name of the processor or script that raised it.
+    pub source: String,
+    /// Human-readable alert text.
+    pub message: String,
+    /// Alert timestamp in epoch milliseconds.
+    pub time: i64,
+}
+
+/// A row pulled off the normal path by the anomaly detector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quarantine {
+    /// Symbol the offending row refers to.
+    pub symbol: String,
+    /// Why the row was quarantined.
+    pub reason: String,
+    /// Timestamp of the offending row in epoch milliseconds.
+    pub time: i64,
+    /// The original event, serialized as JSON.
+    pub payload: String,
+    /// Whether the detector ran in strict mode; strict violations
+    /// abort the batch instead of continuing.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// Anything that flows through the pipeline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    /// A single executed trade.
+    Trade(Trade),
+    /// A (usually closed) candle.
+    Kline(Kline),
+    /// A top-of-book snapshot.
+    Bbo(crate::model::Bbo),
+    /// An order book snapshot (top N levels).
+    Depth(crate::model::DepthSnapshot),
+    /// A rolling 24h ticker update.
+    Ticker(crate::model::MiniTicker),
+    /// Derived rolling-window statistics.
+    Rolling(crate::model::RollingStats),
+    /// An alert raised somewhere upstream.
+    Alert(Alert),
+    /// An offending row routed to quarantine instead of the archive.
+    Quarantined(Quarantine),
+}
+
+impl Event {
+    /// Symbol the event refers to.
+    pub fn symbol(&self) -> &str {
+        match self {
+            Event::Trade(t) => &t.symbol,
+            Event::Kline(k) => &k.symbol,
+            Event::Bbo(b) => &b.symbol,
+            Event::Depth(d) => &d.symbol,
+            Event::Ticker(t) => &t.symbol,
+            Event::Rolling(r) => &r.symbol,
+            Event::Alert(a) => &a.symbol,
+            Event::Quarantined(q) => &q.symbol,
+        }
+    }
+
+    /// Event timestamp in epoch milliseconds.
+    pub fn time(&self) -> i64 {
+        match self {
+            Event::Trade(t) => t.trade_time,
+            Event::Kline(k) => k.close_time,
+            Event::Bbo(b) => b.time,
+            Event::Depth(d) => d.time,
+            Event::Ticker(t) => t.time,
+            Event::Rolling(r) => r.time,
+            Event::Alert(a) => a.time,
+            Event::Quarantined(q) => q.time,
+        }
+    }
+}
+
+/// A pipeline stage. Processors run synchronously on the hot path, so
+/// implementations should avoid blocking I/O.
+pub trait Processor: Send {
+    /// Name used in logs and alert attribution.
+    fn name(&self) -> &str;
+
+    /// Process one event. Return an empty vector to drop it, the event
+    /// itself (possibly rewritten) to pass it on, or several events to
+    /// fan out (e.g. the original plus an [`Alert`]).
+    fn process(&mut self, event: Event) -> Vec<Event>;
+}
+
+/// An ordered chain of processors.
+#[derive(Default)]
+pub struct Pipeline {
+    processors: Vec<Box<dyn Processor>>,
+}
+
+impl Pipeline {
+    /// Create an empty pipeline that passes every event through unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a processor to the end of the chain.
+    pub fn push(&mut self, processor: Box<dyn Processor>) {
+        self.processors.push(processor);
+    }
+
+    /// Number of processors in the chain.
+    pub fn len(&self) -> usize {
+        self.processors.len()
+    }
+
+    /// Whether the chain is empty.
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+
+    /// Run one event through every processor in order. Events emitted by
+    /// a processor are fed to the *following* stages only, so a stage
+    /// never sees its own output.
+    pub fn run(&mut self, event: Event) -> Vec<Event> {
+        let mut current = vec![event];
+        for processor in &mut self.processors {
+            let mut next = Vec::with_capacity(current.len());
+            for ev in current {
+                next.extend(processor.process(ev));
+            }
+            if next.is_empty() {
+                return next;
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DropAll;
+
+    impl Processor for DropAll {
+        fn name(&self) -> &str {
+            "drop_all"
+        }
+
+        fn process(&mut self, _event: Event) -> Vec<Event> {
+            Vec::new()
+        }
+    }
+
+    fn trade() -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price: 50_000.0,
+            qty: 0.5,
+            trade_time: 1_700_000_000_000,
+            is_buyer_maker: false,
+        })
+    }
+
+    #[test]
+    fn empty_pipeline_passes_through() {
+        let mut p = Pipeline::new();
+        assert_eq!(p.run(trade()), vec![trade()]);
+    }
+
+    #[test]
+    fn dropped_events_do_not_reach_later_stages() {
+        let mut p = Pipeline::new();
+        p.push(Box::new(DropAll));
+        assert!(p.run(trade()).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+//! Rolling-window statistics from the ticker stream.
+//!
+//! From `@miniTicker`/`@ticker` updates the builder maintains 1h, 4h
+//! and 24h windows per symbol and emits high, low, traded volume and
+//! return at minute resolution, so screening queries get precomputed
+//! answers instead of scanning raw data.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{Event, Processor};
+use crate::model::{MiniTicker, RollingStats};
+
+/// The maintained windows, as (label, milliseconds).
+const WINDOWS: [(&str, i64); 3] = [
+    ("1h", 3_600_000),
+    ("4h", 14_400_000),
+    ("24h", 86_400_000),
+];
+const EMIT_EVERY_MS: i64 = 60_000;
+
+#[derive(Default)]
+struct SymbolState {
+    // (time, price, volume traded since the previous update).
+    samples: VecDeque<(i64, f64, f64)>,
+    last_cumulative_volume: Option<f64>,
+    last_emitted: Option<i64>,
+}
+
+/// Maintains per-symbol rolling windows over ticker updates.
+#[derive(Default)]
+pub struct RollingStatsBuilder {
+    state: HashMap<String, SymbolState>,
+}
+
+impl RollingStatsBuilder {
+    /// An empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn apply(&mut self, t: &MiniTicker) -> Vec<RollingStats> {
+        let state = self.state.entry(t.symbol.clone()).or_default();
+        // The venue reports cumulative 24h volume; the delta between
+        // consecutive updates is what traded since the last tick (the
+        // daily roll clamps to zero).
+        let delta = match state.last_cumulative_volume {
+            Some(prev) => (t.volume - prev).max(0.0),
+            None => 0.0,
+        };
+        state.last_cumulative_volume = Some(t.volume);
+        state.samples.push_back((t.time, t.close, delta));
+        let max_window = WINDOWS[WINDOWS.len() - 1].1;
+        while let Some(&(time, _, _)) = state.samples.front() {
+            if t.time - time > max_window {
+                state.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+        if let Some(last) = state.last_emitted {
+            if t.time - last < EMIT_EVERY_MS {
+                return Vec::new();
+            }
+        }
+        state.last_emitted = Some(t.time);
+        WINDOWS
+            .iter()
+            .filter_map(|&(label, window_ms)| {
+                let samples: Vec<&(i64, f64, f64)> = state
+                    .samples
+                    .iter()
+                    .filter(|&&(time, _, _)| t.time - time <= window_ms)
+                    .collect();
+                let first = samples.first()?;
+                let high = samples.iter().map(|s| s.1).fold(f64::MIN, f64::max);
+                let low = samples.iter().map(|s| s.1).fold(f64::MAX, f64::min);
+                let volume: f64 = samples.iter().map(|s| s.2).sum();
+                let return_pct = if first.1 > 0.0 {
+                    (t.close - first.1) / first.1 * 100.0
+                } else {
+                    0.0
+                };
+                Some(RollingStats {
+                    symbol: t.symbol.clone(),
+                    time: t.time,
+                    window: label.to_string(),
+                    high,
+                    low,
+                    volume,
+                    return_pct,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Processor for RollingStatsBuilder {
+    fn name(&self) -> &str {
+        "rolling_stats"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let Event::Ticker(ticker) = &event else {
+            return vec![event];
+        };
+        self.apply(ticker).into_iter().map(Event::Rolling).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticker(time: i64, close: f64, cumulative_volume: f64) -> Event {
+        Event::Ticker(MiniTicker {
+            symbol: "BTCUSDT".into(),
+            time,
+            close,
+            high: close,
+            low: close,
+            volume: cumulative_volume,
+        })
+    }
+
+    #[test]
+    fn emits_minute_resolution_windows() {
+        let mut b = RollingStatsBuilder::new();
+        // First update emits (all three windows with one sample).
+        let out = b.process(ticker(0, 100.0, 1_000.0));
+        assert_eq!(out.len(), 3);
+        // Sub-minute updates are swallowed.
+        assert!(b.process(ticker(30_000, 101.0, 1_010.0)).is_empty());
+        // The next minute emits with the accumulated state.
+        let out = b.process(ticker(60_000, 102.0, 1_030.0));
+        assert_eq!(out.len(), 3);
+        match &out[0] {
+            Event::Rolling(r) => {
+                assert_eq!(r.window, "1h");
+                assert_eq!(r.high, 102.0);
+                assert_eq!(r.low, 100.0);
+                // 10 + 20 traded since the first update.
+                assert!((r.volume - 30.0).abs() < 1e-9);
+                assert!((r.return_pct - 2.0).abs() < 1e-9);
+            }
+            other => panic!("expected rolling stats, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn daily_volume_roll_clamps_to_zero() {
+        let mut b = RollingStatsBuilder::new();
+        b.process(ticker(0, 100.0, 5_000.0));
+        // Cumulative volume reset (new UTC day): the delta clamps.
+        let out = b.process(ticker(60_000, 100.0, 10.0));
+        match &out[0] {
+            Event::Rolling(r) => assert_eq!(r.volume, 0.0),
+            other => panic!("expected rolling stats, got {other:?}"),
+        }
+    }
+}
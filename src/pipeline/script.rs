@@ -0,0 +1,243 @@
+//! User scripts (Rhai) as pipeline stages.
+//!
+//! Scripts are small expressions from the config file evaluated against
+//! every matching event, with the event bound as the map `e` (plus the
+//! helper variable `notional` for trades). Three actions are supported:
+//!
+//! * `filter` — the script returns a bool; `false` drops the event.
+//! * `transform` — the script returns a (possibly modified) copy of `e`
+//!   which replaces the event.
+//! * `alert` — a `true` or string result raises an [`Alert`] alongside
+//!   the unchanged event.
+
+use rhai::{Dynamic, Engine, Scope, AST};
+use serde::{Deserialize, Serialize};
+
+use super::{Alert, Event, Processor};
+use crate::error::{Error, Result};
+
+/// What the script's result is used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptAction {
+    /// Keep the event only if the script returns `true`.
+    Filter,
+    /// Replace the event with the returned map.
+    Transform,
+    /// Raise an alert when the script returns `true` or a message string.
+    Alert,
+}
+
+/// Which events a script runs on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptOn {
+    /// Trade events only.
+    Trade,
+    /// Kline events only.
+    Kline,
+    /// Every event kind.
+    #[default]
+    All,
+}
+
+/// One `[[script]]` entry from the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptConfig {
+    /// Name used in logs and alert attribution.
+    pub name: String,
+    /// What to do with the script's result.
+    pub action: ScriptAction,
+    /// Which event kinds the script sees.
+    #[serde(default)]
+    pub on: ScriptOn,
+    /// Rhai source text.
+    pub code: String,
+}
+
+/// A compiled user script running as a pipeline stage.
+pub struct ScriptProcessor {
+    name: String,
+    action: ScriptAction,
+    on: ScriptOn,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptProcessor {
+    /// Compile a script from its config entry.
+    pub fn compile(cfg: &ScriptConfig) -> Result<Self> {
+        let mut engine = Engine::new();
+        // Scripts are untrusted config, not programs: keep them small.
+        engine.set_max_operations(100_000);
+        engine.set_max_expr_depths(32, 32);
+        let ast = engine.compile(&cfg.code).map_err(|e| Error::Script {
+            name: cfg.name.clone(),
+            message: e.to_string(),
+        })?;
+        Ok(Self {
+            name: cfg.name.clone(),
+            action: cfg.action,
+            on: cfg.on,
+            engine,
+            ast,
+        })
+    }
+
+    fn applies_to(&self, event: &Event) -> bool {
+        match (self.on, event) {
+            (ScriptOn::All, _) => matches!(event, Event::Trade(_) | Event::Kline(_)),
+            (ScriptOn::Trade, Event::Trade(_)) => true,
+            (ScriptOn::Kline, Event::Kline(_)) => true,
+            _ => false,
+        }
+    }
+
+    fn eval(&self, event: &Event) -> Result<Dynamic> {
+        let mut scope = Scope::new();
+        let bound = rhai::serde::to_dynamic(event).map_err(|e| self.runtime_error(&e.to_string()))?;
+        scope.push_dynamic("e", bound);
+        if let Event::Trade(t) = event {
+            scope.push("notional", t.notional());
+        }
+        self.engine
+            .eval_ast_with_scope::<Dynamic>(&mut scope, &self.ast)
+            .map_err(|e| self.runtime_error(&e.to_string()))
+    }
+
+    fn runtime_error(&self, message: &str) -> Error {
+        Error::Script {
+            name: self.name.clone(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl Processor for ScriptProcessor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        if !self.applies_to(&event) {
+            return vec![event];
+        }
+        let result = match self.eval(&event) {
+            Ok(v) => v,
+            Err(e) => {
+                // A broken script must never take down collection: log
+                // and pass the event through untouched.
+                tracing::warn!(script = %self.name, error = %e, "script evaluation failed");
+                return vec![event];
+            }
+        };
+        match self.action {
+            ScriptAction::Filter => {
+                if result.as_bool().unwrap_or(true) {
+                    vec![event]
+                } else {
+                    Vec::new()
+                }
+            }
+            ScriptAction::Transform => match rhai::serde::from_dynamic::<Event>(&result) {
+                Ok(replaced) => vec![replaced],
+                Err(e) => {
+                    tracing::warn!(script = %self.name, error = %e, "transform result ignored");
+                    vec![event]
+                }
+            },
+            ScriptAction::Alert => {
+                let message = if result.is_string() {
+                    Some(result.into_string().expect("checked string"))
+                } else if result.as_bool().unwrap_or(false) {
+                    Some(format!("script `{}` triggered", self.name))
+                } else {
+                    None
+                };
+                match message {
+                    Some(message) => {
+                        let alert = Event::Alert(Alert {
+                            symbol: event.symbol().to_string(),
+                            source: self.name.clone(),
+                            message,
+                            time: event.time(),
+                        });
+                        vec![event, alert]
+                    }
+                    None => vec![event],
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Trade;
+
+    fn trade(price: f64, qty: f64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 7,
+            price,
+            qty,
+            trade_time: 1_700_000_000_000,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn processor(action: ScriptAction, code: &str) -> ScriptProcessor {
+        ScriptProcessor::compile(&ScriptConfig {
+            name: "test".into(),
+            action,
+            on: ScriptOn::Trade,
+            code: code.into(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn filter_drops_small_notional() {
+        let mut p = processor(ScriptAction::Filter, "notional >= 100000.0");
+        assert!(p.process(trade(50_000.0, 0.001)).is_empty());
+        assert_eq!(p.process(trade(50_000.0, 3.0)).len(), 1);
+    }
+
+    #[test]
+    fn transform_rewrites_fields() {
+        let mut p = processor(ScriptAction::Transform, "e.symbol = e.symbol + \"-PERP\"; e");
+        let out = p.process(trade(50_000.0, 1.0));
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].symbol(), "BTCUSDT-PERP");
+    }
+
+    #[test]
+    fn alert_emits_alongside_event() {
+        let mut p = processor(ScriptAction::Alert, "if e.price > 60000.0 { \"price high\" } else { false }");
+        assert_eq!(p.process(trade(50_000.0, 1.0)).len(), 1);
+        let out = p.process(trade(70_000.0, 1.0));
+        assert_eq!(out.len(), 2);
+        match &out[1] {
+            Event::Alert(a) => assert_eq!(a.message, "price high"),
+            other => panic!("expected alert, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn compile_error_is_reported() {
+        let bad = ScriptProcessor::compile(&ScriptConfig {
+            name: "broken".into(),
+            action: ScriptAction::Filter,
+            on: ScriptOn::All,
+            code: "if (".into(),
+        });
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn runtime_error_passes_event_through() {
+        let mut p = processor(ScriptAction::Filter, "throw \"boom\"");
+        assert_eq!(p.process(trade(50_000.0, 1.0)).len(), 1);
+    }
+}
@@ -0,0 +1,343 @@
+//! Declarative pipeline definitions.
+//!
+//! A pipeline is declared in config as a single arrow string:
+//!
+//! ```text
+//! pipelines = ["btcusdt@trade -> candle_builder(1m) -> [clickhouse, csv]"]
+//! ```
+//!
+//! The first segment names the source stream, the last the sink(s), and
+//! everything in between is a processor stage. `→` is accepted as an
+//! alias for `->`.
+
+use serde::{Deserialize, Serialize};
+
+use super::candle::CandleBuilder;
+use super::script::ScriptProcessor;
+use super::Pipeline;
+use crate::config::Config;
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+use crate::sink::clickhouse::ClickHouseSink;
+use crate::sink::console::ConsoleSink;
+use crate::sink::csv::CsvSink;
+use crate::sink::Sink;
+
+/// The stream feeding a pipeline, e.g. `btcusdt@trade`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamSource {
+    /// Lowercase exchange symbol, e.g. `btcusdt`.
+    pub symbol: String,
+    /// Stream name after the `@`, e.g. `trade` or `kline_1m`.
+    pub stream: String,
+}
+
+/// One processor stage, e.g. `candle_builder(1m)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageSpec {
+    /// Stage name.
+    pub name: String,
+    /// Optional parenthesised argument.
+    pub arg: Option<String>,
+}
+
+/// A parsed pipeline definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineSpec {
+    /// Source stream.
+    pub source: StreamSource,
+    /// Processor stages in order.
+    pub stages: Vec<StageSpec>,
+    /// Sink names.
+    pub sinks: Vec<String>,
+}
+
+impl PipelineSpec {
+    /// Parse an arrow definition string.
+    pub fn parse(def: &str) -> Result<Self> {
+        let normalized = def.replace('→', "->");
+        let segments: Vec<&str> = normalized
+            .split("->")
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .collect();
+        if segments.len() < 2 {
+            return Err(Error::Pipeline(format!(
+                "`{def}`: expected at least `source -> sink`"
+            )));
+        }
+        let source = parse_source(segments[0], def)?;
+        let sinks = parse_sinks(segments[segments.len() - 1]);
+        if sinks.is_empty() {
+            return Err(Error::Pipeline(format!("`{def}`: no sinks named")));
+        }
+        let stages = segments[1..segments.len() - 1]
+            .iter()
+            .map(|s| parse_stage(s))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            source,
+            stages,
+            sinks,
+        })
+    }
+}
+
+fn parse_source(segment: &str, def: &str) -> Result<StreamSource> {
+    let (symbol, stream) = segment.split_once('@').ok_or_else(|| {
+        Error::Pipeline(format!("`{def}`: source must look like `btcusdt@trade`"))
+    })?;
+    Ok(StreamSource {
+        symbol: symbol.trim().to_lowercase(),
+        stream: stream.trim().to_lowercase(),
+    })
+}
+
+fn parse_sinks(segment: &str) -> Vec<String> {
+    segment
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn parse_stage(segment: &str) -> Result<StageSpec> {
+    match segment.split_once('(') {
+        None => Ok(StageSpec {
+            name: segment.to_lowercase(),
+            arg: None,
+        }),
+        Some((name, rest)) => {
+            let arg = rest.trim_end_matches(')').trim();
+            Ok(StageSpec {
+                name: name.trim().to_lowercase(),
+                arg: if arg.is_empty() {
+                    None
+                } else {
+                    Some(arg.to_string())
+                },
+            })
+        }
+    }
+}
+
+/// A fully wired pipeline: source description, processor chain, sinks.
+pub struct Flow {
+    /// Stream to subscribe to.
+    pub source: StreamSource,
+    /// Processor chain.
+    pub pipeline: Pipeline,
+    /// Destinations for whatever the chain emits.
+    pub sinks: Vec<Box<dyn Sink>>,
+}
+
+/// Wire a parsed spec against the config: resolve stage and sink names
+/// into live objects.
+pub fn build_flow(cfg: &Config, spec: &PipelineSpec, http: &reqwest::Client) -> Result<Flow> {
+    let mut pipeline = Pipeline::new();
+    for stage in &spec.stages {
+        pipeline.push(build_stage(cfg, stage)?);
+    }
+    let tier_map = crate::tiers::TierMap::new(cfg.tiers.clone());
+    let tier = tier_map.get(&spec.source.symbol);
+    let mut sinks: Vec<Box<dyn Sink>> = Vec::with_capacity(spec.sinks.len());
+    for name in &spec.sinks {
+        // A tier may restrict which sinks its symbols reach.
+        if let Some(allowed) = tier.and_then(|t| t.sinks.as_ref()) {
+            if !allowed.iter().any(|a| a.eq_ignore_ascii_case(name)) {
+                tracing::debug!(
+                    symbol = spec.source.symbol,
+                    sink = name,
+                    "sink excluded by tier"
+                );
+                continue;
+            }
+        }
+        let sink = build_sink(cfg, name, http)?;
+        // Wrap every sink in the micro-batcher when `[batch]` is
+        // configured (tier bounds win); the console stays unbatched so
+        // dry runs echo events as they happen.
+        let settings = match (tier.and_then(|t| t.batch), &cfg.batch) {
+            (Some(batch), _) => Some(batch),
+            (None, Some(batch)) => Some(batch.settings_for(name)),
+            (None, None) => None,
+        };
+        let sink = match settings {
+            Some(settings) if name != "console" => Box::new(
+                crate::sink::batch::BatchingSink::new(sink, settings),
+            ),
+            _ => sink,
+        };
+        sinks.push(sink);
+    }
+    if sinks.is_empty() {
+        return Err(Error::Pipeline(format!(
+            "`{}`: every sink was excluded by its tier",
+            spec.source.symbol
+        )));
+    }
+    Ok(Flow {
+        source: spec.source.clone(),
+        pipeline,
+        sinks,
+    })
+}
+
+fn build_stage(cfg: &Config, stage: &StageSpec) -> Result<Box<dyn super::Processor>> {
+    match stage.name.as_str() {
+        "anomaly" => Ok(Box::new(super::anomaly::AnomalyDetector::new(
+            cfg.anomaly.clone().unwrap_or_default(),
+        ))),
+        "candle_builder" => {
+            let interval = stage.arg.as_deref().unwrap_or("1m");
+            Ok(Box::new(CandleBuilder::new(interval)?))
+        }
+        "bbo_sampler" => {
+            let period = stage.arg.as_deref().unwrap_or("1s");
+            Ok(Box::new(super::bbo::BboSampler::new(period)?))
+        }
+        "depth_recorder" => {
+            let arg = stage.arg.as_deref().unwrap_or("10,1s");
+            Ok(Box::new(super::depth::DepthRecorder::from_arg(arg)?))
+        }
+        "rolling_stats" => Ok(Box::new(super::rolling::RollingStatsBuilder::new())),
+        "whale" => Ok(Box::new(super::whale::WhaleTagger::new(cfg.whale.clone()))),
+        "watchlist" => Ok(Box::new(super::watchlist::WatchlistProcessor::new(
+            cfg.watch.clone(),
+        )?)),
+        "script" => {
+            let name = stage.arg.as_deref().ok_or_else(|| {
+                Error::Pipeline("`script` stage needs a name argument".to_string())
+            })?;
+            let script = cfg
+                .scripts
+                .iter()
+                .find(|s| s.name == name)
+                .ok_or_else(|| Error::Pipeline(format!("no [[script]] named `{name}`")))?;
+            Ok(Box::new(ScriptProcessor::compile(script)?))
+        }
+        other => Err(Error::Pipeline(format!(
+            "unknown stage `{other}` (known: anomaly, bbo_sampler, candle_builder, depth_recorder, rolling_stats, script, watchlist, whale)"
+        ))),
+    }
+}
+
+fn build_sink(cfg: &Config, name: &str, http: &reqwest::Client) -> Result<Box<dyn Sink>> {
+    let style = cfg.output.time_style()?;
+    match name {
+        "console" => Ok(Box::new(ConsoleSink::new(style))),
+        "ticker" => {
+            let window = cfg.output.ticker_window.as_deref().unwrap_or("1s");
+            let window_ms = crate::pipeline::bbo::parse_period_ms(window)?;
+            Ok(Box::new(
+                crate::sink::ticker::TickerSink::new(window_ms).with_color(cfg.output.color),
+            ))
+        }
+        "csv" => Ok(Box::new(CsvSink::new(&cfg.data_dir).with_style(style))),
+        "clickhouse" => {
+            let ch = cfg.clickhouse.clone().ok_or_else(|| {
+                Error::Pipeline("sink `clickhouse` needs a [clickhouse] config section".to_string())
+            })?;
+            // Every batch goes to the primary and every replica, each
+            // with its own WAL when `[wal]` is present.
+            let mut targets: Vec<(String, crate::db::ClickHouseConfig)> =
+                vec![("primary".to_string(), ch.clone())];
+            for (idx, replica) in ch.replica_configs().into_iter().enumerate() {
+                targets.push((format!("replica-{idx}"), replica));
+            }
+            let mut sinks: Vec<Box<dyn Sink>> = Vec::with_capacity(targets.len());
+            for (label, target) in targets {
+                let sink: Box<dyn Sink> =
+                    Box::new(ClickHouseSink::new(ClickHouse::new(target, http.clone())));
+                let sink = match &cfg.wal {
+                    Some(wal) => {
+                        let base = wal
+                            .dir
+                            .clone()
+                            .unwrap_or_else(|| format!("{}/wal", cfg.data_dir));
+                        Box::new(crate::sink::wal::WalSink::with_retries(
+                            sink,
+                            format!("{base}/{label}"),
+                            wal.max_mb * 1024 * 1024,
+                            wal.max_replay_retries,
+                        )?)
+                    }
+                    None => sink,
+                };
+                sinks.push(sink);
+            }
+            if sinks.len() == 1 {
+                Ok(sinks.pop().expect("one sink"))
+            } else {
+                Ok(Box::new(crate::sink::fanout::FanoutSink::new(
+                    "clickhouse",
+                    sinks,
+                )))
+            }
+        }
+        other => Err(Error::Pipeline(format!(
+            "unknown sink `{other}` (known: console, csv, clickhouse, ticker)"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_definition() {
+        let spec =
+            PipelineSpec::parse("BTCUSDT@trade → candle_builder(1m) → [clickhouse, csv]").unwrap();
+        assert_eq!(spec.source.symbol, "btcusdt");
+        assert_eq!(spec.source.stream, "trade");
+        assert_eq!(spec.stages.len(), 1);
+        assert_eq!(spec.stages[0].name, "candle_builder");
+        assert_eq!(spec.stages[0].arg.as_deref(), Some("1m"));
+        assert_eq!(spec.sinks, vec!["clickhouse", "csv"]);
+    }
+
+    #[test]
+    fn parses_minimal_definition() {
+        let spec = PipelineSpec::parse("ethusdt@trade -> console").unwrap();
+        assert!(spec.stages.is_empty());
+        assert_eq!(spec.sinks, vec!["console"]);
+    }
+
+    #[test]
+    fn rejects_missing_sink() {
+        assert!(PipelineSpec::parse("btcusdt@trade").is_err());
+        assert!(PipelineSpec::parse("trade -> console").is_err());
+    }
+
+    #[test]
+    fn builds_flow_from_config() {
+        let cfg = Config::parse(
+            r#"
+            pipelines = ["btcusdt@trade -> candle_builder(5m) -> [console, csv]"]
+
+            [[script]]
+            name = "noop"
+            action = "filter"
+            code = "true"
+            "#,
+        )
+        .unwrap();
+        let spec = PipelineSpec::parse(&cfg.pipelines[0]).unwrap();
+        let flow = build_flow(&cfg, &spec, &reqwest::Client::new()).unwrap();
+        assert_eq!(flow.pipeline.len(), 1);
+        assert_eq!(flow.sinks.len(), 2);
+    }
+
+    #[test]
+    fn unknown_stage_and_sink_are_errors() {
+        let cfg = Config::default();
+        let http = reqwest::Client::new();
+        let bad_stage = PipelineSpec::parse("btcusdt@trade -> frobnicate -> console").unwrap();
+        assert!(build_flow(&cfg, &bad_stage, &http).is_err());
+        let bad_sink = PipelineSpec::parse("btcusdt@trade -> kafka").unwrap();
+        assert!(build_flow(&cfg, &bad_sink, &http).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Stitches REST-backfilled history and live kline streams into one
+//! continuous series.
+//!
+//! Around startup the two sources overlap: the backfill ends with the
+//! candles the freshly subscribed stream is about to deliver again.
+//! The stitcher tracks the last emitted open time per symbol/interval
+//! and drops anything at or before it, so downstream sinks see each
+//! candle exactly once and in order.
+
+use std::collections::HashMap;
+
+use super::{Event, Processor};
+
+/// Deduplicates and orders kline events per symbol/interval.
+#[derive(Default)]
+pub struct KlineStitcher {
+    last_open: HashMap<(String, String), i64>,
+}
+
+impl KlineStitcher {
+    /// An empty stitcher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that history up to `last_open_time` was already emitted
+    /// (e.g. by the startup backfill), so the live stream's overlap is
+    /// swallowed.
+    pub fn prime(&mut self, symbol: &str, interval: &str, last_open_time: i64) {
+        self.last_open
+            .insert((symbol.to_uppercase(), interval.to_string()), last_open_time);
+    }
+
+    /// Last emitted open time for a series, if any.
+    pub fn last_open(&self, symbol: &str, interval: &str) -> Option<i64> {
+        self.last_open
+            .get(&(symbol.to_uppercase(), interval.to_string()))
+            .copied()
+    }
+}
+
+impl Processor for KlineStitcher {
+    fn name(&self) -> &str {
+        "stitch"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let Event::Kline(k) = &event else {
+            return vec![event];
+        };
+        let key = (k.symbol.to_uppercase(), k.interval.clone());
+        match self.last_open.get(&key) {
+            Some(&last) if k.open_time <= last => {
+                tracing::debug!(
+                    symbol = k.symbol,
+                    interval = k.interval,
+                    open_time = k.open_time,
+                    "dropping duplicate candle from overlap window"
+                );
+                Vec::new()
+            }
+            _ => {
+                self.last_open.insert(key, k.open_time);
+                vec![event]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Kline;
+
+    fn kline(open_time: i64) -> Event {
+        Event::Kline(Kline {
+            symbol: "BTCUSDT".into(),
+            interval: "1m".into(),
+            open_time,
+            close_time: open_time + 59_999,
+            open: 1.0,
+            high: 1.0,
+            low: 1.0,
+            close: 1.0,
+            volume: 1.0,
+            quote_volume: 1.0,
+            trade_count: 1,
+        })
+    }
+
+    #[test]
+    fn drops_overlap_after_priming() {
+        let mut s = KlineStitcher::new();
+        s.prime("btcusdt", "1m", 120_000);
+        assert!(s.process(kline(60_000)).is_empty());
+        assert!(s.process(kline(120_000)).is_empty());
+        assert_eq!(s.process(kline(180_000)).len(), 1);
+        // And re-deliveries of live candles are dropped too.
+        assert!(s.process(kline(180_000)).is_empty());
+        assert_eq!(s.last_open("BTCUSDT", "1m"), Some(180_000));
+    }
+
+    #[test]
+    fn independent_series_do_not_interfere() {
+        let mut s = KlineStitcher::new();
+        assert_eq!(s.process(kline(60_000)).len(), 1);
+        let mut other = match kline(60_000) {
+            Event::Kline(k) => k,
+            _ => unreachable!(),
+        };
+        other.interval = "1h".into();
+        assert_eq!(s.process(Event::Kline(other)).len(), 1);
+    }
+}
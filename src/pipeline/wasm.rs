@@ -0,0 +1,235 @@
+//! Sandboxed WASM modules as pipeline processors.
+//!
+//! Plugins are ordinary `.wasm` files speaking a minimal ABI so they can
+//! be produced by any toolchain:
+//!
+//! * `memory` — exported linear memory.
+//! * `alloc(len: i32) -> i32` — return a pointer the host may write
+//!   `len` bytes to. Called once per event before `process`.
+//! * `process(ptr: i32, len: i32) -> i64` — receives one event as JSON.
+//!   Return `0` to pass the event through unchanged, or a pointer/length
+//!   pair packed as `(ptr << 32) | len` naming a JSON **array** of
+//!   output events (`[]` drops the event).
+//!
+//! Modules run with a fuel budget per call, so a buggy or hostile plugin
+//! can stall only itself, never the collector. The `process` return
+//! value is likewise untrusted: its length is clamped against the
+//! module's own memory before the host allocates a buffer for it, so a
+//! plugin can't OOM the collector by packing a huge length either.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use wasmi::{Engine, Instance, Linker, Module, Store, TypedFunc};
+
+use super::{Event, Processor};
+use crate::error::{Error, Result};
+
+/// Default fuel budget per `process` call.
+const DEFAULT_FUEL: u64 = 10_000_000;
+
+/// One `[[wasm_plugin]]` entry from the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasmPluginConfig {
+    /// Name used in logs.
+    pub name: String,
+    /// Path to the compiled `.wasm` module.
+    pub path: String,
+    /// Fuel budget per event; bounds plugin CPU time.
+    #[serde(default = "default_fuel")]
+    pub fuel: u64,
+}
+
+fn default_fuel() -> u64 {
+    DEFAULT_FUEL
+}
+
+/// A loaded WASM plugin running as a pipeline stage.
+pub struct WasmProcessor {
+    name: String,
+    fuel: u64,
+    store: Store<()>,
+    instance: Instance,
+    alloc: TypedFunc<i32, i32>,
+    process: TypedFunc<(i32, i32), i64>,
+}
+
+impl WasmProcessor {
+    /// Load a plugin from its config entry.
+    pub fn load(cfg: &WasmPluginConfig) -> Result<Self> {
+        let bytes = std::fs::read(Path::new(&cfg.path))
+            .map_err(|e| Error::Config(format!("wasm plugin `{}`: {}: {e}", cfg.name, cfg.path)))?;
+        Self::from_bytes(&cfg.name, &bytes, cfg.fuel)
+    }
+
+    /// Instantiate a plugin from raw module bytes.
+    pub fn from_bytes(name: &str, bytes: &[u8], fuel: u64) -> Result<Self> {
+        let plugin_error = |message: String| Error::Script {
+            name: name.to_string(),
+            message,
+        };
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config);
+        let module = Module::new(&engine, bytes).map_err(|e| plugin_error(e.to_string()))?;
+        let mut store = Store::new(&engine, ());
+        store.add_fuel(fuel).map_err(|e| plugin_error(e.to_string()))?;
+        let linker = Linker::new(&engine);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .map_err(|e| plugin_error(e.to_string()))?;
+        let alloc = instance
+            .get_typed_func::<i32, i32>(&store, "alloc")
+            .map_err(|e| plugin_error(format!("missing `alloc` export: {e}")))?;
+        let process = instance
+            .get_typed_func::<(i32, i32), i64>(&store, "process")
+            .map_err(|e| plugin_error(format!("missing `process` export: {e}")))?;
+        Ok(Self {
+            name: name.to_string(),
+            fuel,
+            store,
+            instance,
+            alloc,
+            process,
+        })
+    }
+
+    fn call(&mut self, event: &Event) -> Result<Option<Vec<Event>>> {
+        let plugin_error = |message: String| Error::Script {
+            name: self.name.clone(),
+            message,
+        };
+        let input = serde_json::to_vec(event)?;
+        // Top up so every call starts with at least one full budget.
+        self.store
+            .add_fuel(self.fuel)
+            .map_err(|e| plugin_error(e.to_string()))?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, input.len() as i32)
+            .map_err(|e| plugin_error(e.to_string()))?;
+        let memory = self
+            .instance
+            .get_memory(&self.store, "memory")
+            .ok_or_else(|| plugin_error("missing `memory` export".into()))?;
+        memory
+            .write(&mut self.store, ptr as usize, &input)
+            .map_err(|e| plugin_error(e.to_string()))?;
+        let packed = self
+            .process
+            .call(&mut self.store, (ptr, input.len() as i32))
+            .map_err(|e| plugin_error(e.to_string()))?;
+        if packed == 0 {
+            return Ok(None);
+        }
+        let out_ptr = (packed as u64 >> 32) as usize;
+        let out_len = (packed as u64 & 0xffff_ffff) as usize;
+        // A hostile or buggy plugin can pack an arbitrary length (up to
+        // ~4.29 GiB) into the return value; bound it by the module's own
+        // memory before allocating, rather than trusting it outright.
+        let mem_size = memory.data(&self.store).len();
+        if out_len > mem_size {
+            return Err(plugin_error(format!(
+                "process returned an output length {out_len} larger than \
+                 the module's memory ({mem_size} bytes)"
+            )));
+        }
+        let mut buf = vec![0u8; out_len];
+        memory
+            .read(&self.store, out_ptr, &mut buf)
+            .map_err(|e| plugin_error(e.to_string()))?;
+        let events: Vec<Event> = serde_json::from_slice(&buf)?;
+        Ok(Some(events))
+    }
+}
+
+impl Processor for WasmProcessor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        match self.call(&event) {
+            Ok(None) => vec![event],
+            Ok(Some(events)) => events,
+            Err(e) => {
+                // Same contract as scripts: a faulty plugin logs and the
+                // event flows on unchanged.
+                tracing::warn!(plugin = %self.name, error = %e, "wasm plugin failed");
+                vec![event]
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Trade;
+
+    /// Minimal module honouring the ABI: bump-allocates at 4096 and
+    /// returns a constant from `data` when asked to.
+    fn module(process_body: &str, data: &str) -> Vec<u8> {
+        let wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 1024) "{data}")
+                (func (export "alloc") (param i32) (result i32) i32.const 4096)
+                (func (export "process") (param i32 i32) (result i64) {process_body}))"#
+        );
+        wat::parse_str(&wat).unwrap()
+    }
+
+    fn trade() -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price: 50_000.0,
+            qty: 1.0,
+            trade_time: 1_700_000_000_000,
+            is_buyer_maker: false,
+        })
+    }
+
+    #[test]
+    fn zero_result_passes_event_through() {
+        let bytes = module("i64.const 0", "");
+        let mut p = WasmProcessor::from_bytes("pass", &bytes, DEFAULT_FUEL).unwrap();
+        assert_eq!(p.process(trade()), vec![trade()]);
+    }
+
+    #[test]
+    fn empty_array_drops_event() {
+        // (1024 << 32) | 2 names the two-byte "[]" in the data section.
+        let packed = (1024u64 << 32) | 2;
+        let bytes = module(&format!("i64.const {packed}"), "[]");
+        let mut p = WasmProcessor::from_bytes("drop", &bytes, DEFAULT_FUEL).unwrap();
+        assert!(p.process(trade()).is_empty());
+    }
+
+    #[test]
+    fn runaway_plugin_exhausts_fuel_and_event_survives() {
+        let bytes = module("(loop br 0) i64.const 0", "");
+        let mut p = WasmProcessor::from_bytes("spin", &bytes, 1_000).unwrap();
+        assert_eq!(p.process(trade()), vec![trade()]);
+    }
+
+    #[test]
+    fn missing_export_is_a_load_error() {
+        let bytes = wat::parse_str(r#"(module (memory (export "memory") 1))"#).unwrap();
+        assert!(WasmProcessor::from_bytes("bad", &bytes, DEFAULT_FUEL).is_err());
+    }
+
+    #[test]
+    fn oversized_output_length_is_rejected_without_allocating() {
+        // Packs a length far larger than the module's one-page (64 KiB)
+        // memory instead of a real pointer/length pair.
+        let packed = (1024u64 << 32) | 0xffff_ffff;
+        let bytes = module(&format!("i64.const {packed}"), "");
+        let mut p = WasmProcessor::from_bytes("greedy", &bytes, DEFAULT_FUEL).unwrap();
+        // The plugin errors internally and the event passes through
+        // unchanged, same contract as any other plugin failure.
+        assert_eq!(p.process(trade()), vec![trade()]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Price watchlist alerts.
+//!
+//! Rules live in config and are evaluated against the live stream:
+//!
+//! ```text
+//! [[watch]]
+//! symbol = "BTCUSDT"
+//! crosses = 100000.0
+//!
+//! [[watch]]
+//! symbol = "ETHUSDT"
+//! move_pct = 3.0
+//! window = "15m"
+//! ```
+//!
+//! Triggered rules emit an [`Alert`] alongside the event, which the
+//! normal alerting sinks pick up.
+
+use std::collections::{HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Alert, Event, Processor};
+use crate::error::{Error, Result};
+use crate::model::interval_ms;
+
+/// One `[[watch]]` entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchRule {
+    /// Symbol the rule applies to.
+    pub symbol: String,
+    /// Alert when the price crosses this level (either direction).
+    #[serde(default)]
+    pub crosses: Option<f64>,
+    /// Alert when the price moves this many percent within `window`.
+    #[serde(default)]
+    pub move_pct: Option<f64>,
+    /// Window for `move_pct`, e.g. `15m`.
+    #[serde(default)]
+    pub window: Option<String>,
+}
+
+struct MoveState {
+    window_ms: i64,
+    history: VecDeque<(i64, f64)>,
+}
+
+/// Evaluates watch rules against the live price stream.
+pub struct WatchlistProcessor {
+    rules: Vec<WatchRule>,
+    last_price: HashMap<String, f64>,
+    moves: HashMap<usize, MoveState>,
+}
+
+impl WatchlistProcessor {
+    /// Compile config rules, validating windows up front.
+    pub fn new(rules: Vec<WatchRule>) -> Result<Self> {
+        let mut moves = HashMap::new();
+        for (idx, rule) in rules.iter().enumerate() {
+            if rule.crosses.is_none() && rule.move_pct.is_none() {
+                return Err(Error::Config(format!(
+                    "watch rule for {} needs `crosses` or `move_pct`",
+                    rule.symbol
+                )));
+            }
+            if let Some(pct) = rule.move_pct {
+                if pct <= 0.0 {
+                    return Err(Error::Config(format!(
+                        "watch rule for {}: move_pct must be positive",
+                        rule.symbol
+                    )));
+                }
+                let window = rule.window.as_deref().unwrap_or("15m");
+                let window_ms = interval_ms(window).ok_or_else(|| {
+                    Error::Config(format!(
+                        "watch rule for {}: unknown window `{window}`",
+                        rule.symbol
+                    ))
+                })?;
+                moves.insert(
+                    idx,
+                    MoveState {
+                        window_ms,
+                        history: VecDeque::new(),
+                    },
+                );
+            }
+        }
+        Ok(Self {
+            rules,
+            last_price: HashMap::new(),
+            moves,
+        })
+    }
+
+    fn evaluate(&mut self, symbol: &str, price: f64, time: i64) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        let previous = self.last_price.insert(symbol.to_string(), price);
+        for (idx, rule) in self.rules.iter().enumerate() {
+            if !rule.symbol.eq_ignore_ascii_case(symbol) {
+                continue;
+            }
+            if let (Some(level), Some(prev)) = (rule.crosses, previous) {
+                let crossed_up = prev < level && price >= level;
+                let crossed_down = prev > level && price <= level;
+                if crossed_up || crossed_down {
+                    alerts.push(Alert {
+                        symbol: symbol.to_string(),
+                        source: "watchlist".to_string(),
+                        message: format!(
+                            "crossed {level} ({} -> {price})",
+                            prev
+                        ),
+                        time,
+                    });
+                }
+            }
+            if let Some(pct) = rule.move_pct {
+                let state = self.moves.get_mut(&idx).expect("validated in new");
+                while let Some(&(t, _)) = state.history.front() {
+                    if time - t > state.window_ms {
+                        state.history.pop_front();
+                    } else {
+                        break;
+                    }
+                }
+                if let Some(&(_, baseline)) = state.history.front() {
+                    if baseline > 0.0 {
+                        let moved = (price - baseline) / baseline * 100.0;
+                        if moved.abs() >= pct {
+                            alerts.push(Alert {
+                                symbol: symbol.to_string(),
+                                source: "watchlist".to_string(),
+                                message: format!(
+                                    "moved {moved:+.2}% in {} ({baseline} -> {price})",
+                                    rule.window.as_deref().unwrap_or("15m")
+                                ),
+                                time,
+                            });
+                            // Reset the baseline so one move alerts once.
+                            state.history.clear();
+                        }
+                    }
+                }
+                state.history.push_back((time, price));
+            }
+        }
+        alerts
+    }
+}
+
+impl Processor for WatchlistProcessor {
+    fn name(&self) -> &str {
+        "watchlist"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let (symbol, price, time) = match &event {
+            Event::Trade(t) => (t.symbol.clone(), t.price, t.trade_time),
+            Event::Kline(k) => (k.symbol.clone(), k.close, k.close_time),
+            _ => return vec![event],
+        };
+        let alerts = self.evaluate(&symbol, price, time);
+        let mut out = vec![event];
+        out.extend(alerts.into_iter().map(Event::Alert));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Trade;
+
+    fn trade(price: f64, time: i64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: time,
+            price,
+            qty: 1.0,
+            trade_time: time,
+            is_buyer_maker: false,
+        })
+    }
+
+    #[test]
+    fn cross_alerts_in_both_directions_and_rearms() {
+        let mut w = WatchlistProcessor::new(vec![WatchRule {
+            symbol: "BTCUSDT".into(),
+            crosses: Some(100_000.0),
+            move_pct: None,
+            window: None,
+        }])
+        .unwrap();
+        assert_eq!(w.process(trade(99_000.0, 1)).len(), 1);
+        let up = w.process(trade(100_500.0, 2));
+        assert_eq!(up.len(), 2);
+        assert!(matches!(&up[1], Event::Alert(a) if a.message.contains("crossed 100000")));
+        // Staying above does not re-alert; crossing back down does.
+        assert_eq!(w.process(trade(101_000.0, 3)).len(), 1);
+        assert_eq!(w.process(trade(99_500.0, 4)).len(), 2);
+    }
+
+    #[test]
+    fn move_pct_alerts_within_window_only() {
+        let mut w = WatchlistProcessor::new(vec![WatchRule {
+            symbol: "BTCUSDT".into(),
+            crosses: None,
+            move_pct: Some(2.0),
+            window: Some("1m".into()),
+        }])
+        .unwrap();
+        assert_eq!(w.process(trade(100.0, 0)).len(), 1);
+        // +1% then +2.5% within the window.
+        assert_eq!(w.process(trade(101.0, 10_000)).len(), 1);
+        let hit = w.process(trade(102.5, 20_000));
+        assert_eq!(hit.len(), 2);
+        assert!(matches!(&hit[1], Event::Alert(a) if a.message.contains("+2.50%")));
+        // Baseline reset: the same price a minute later is quiet.
+        assert_eq!(w.process(trade(102.5, 90_000)).len(), 1);
+    }
+
+    #[test]
+    fn bad_rules_fail_at_startup() {
+        assert!(WatchlistProcessor::new(vec![WatchRule {
+            symbol: "BTCUSDT".into(),
+            crosses: None,
+            move_pct: None,
+            window: None,
+        }])
+        .is_err());
+        assert!(WatchlistProcessor::new(vec![WatchRule {
+            symbol: "BTCUSDT".into(),
+            crosses: None,
+            move_pct: Some(3.0),
+            window: Some("nope".into()),
+        }])
+        .is_err());
+    }
+}
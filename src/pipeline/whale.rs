@@ -0,0 +1,130 @@
+//! Large-trade (whale) detection.
+//!
+//! Trades above a configurable notional threshold are tagged with an
+//! [`Alert`] alongside the trade itself — the alert row in storage
+//! marks the print, and the alerting sinks pick it up — instead of
+//! requiring post-hoc SQL over everything:
+//!
+//! ```text
+//! [[whale]]
+//! symbol = "BTCUSDT"
+//! min_notional = 250000.0
+//!
+//! [[whale]]
+//! symbol = "*"
+//! min_notional = 100000.0
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use super::{Alert, Event, Processor};
+
+/// One `[[whale]]` threshold entry; `"*"` is the default for symbols
+/// without their own entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhaleRule {
+    /// Symbol the threshold applies to, or `"*"`.
+    pub symbol: String,
+    /// Quote-notional threshold.
+    pub min_notional: f64,
+}
+
+/// Tags trades above their symbol's notional threshold.
+pub struct WhaleTagger {
+    rules: Vec<WhaleRule>,
+}
+
+impl WhaleTagger {
+    /// Build from config rules.
+    pub fn new(rules: Vec<WhaleRule>) -> Self {
+        Self { rules }
+    }
+
+    fn threshold(&self, symbol: &str) -> Option<f64> {
+        self.rules
+            .iter()
+            .find(|r| r.symbol.eq_ignore_ascii_case(symbol))
+            .or_else(|| self.rules.iter().find(|r| r.symbol == "*"))
+            .map(|r| r.min_notional)
+    }
+}
+
+impl Processor for WhaleTagger {
+    fn name(&self) -> &str {
+        "whale"
+    }
+
+    fn process(&mut self, event: Event) -> Vec<Event> {
+        let Event::Trade(t) = &event else {
+            return vec![event];
+        };
+        let Some(threshold) = self.threshold(&t.symbol) else {
+            return vec![event];
+        };
+        if t.notional() < threshold {
+            return vec![event];
+        }
+        let alert = Alert {
+            symbol: t.symbol.clone(),
+            source: "whale".to_string(),
+            message: format!(
+                "large {} of {:.2} notional ({} @ {})",
+                if t.is_buyer_maker { "sell" } else { "buy" },
+                t.notional(),
+                t.qty,
+                t.price
+            ),
+            time: t.trade_time,
+        };
+        vec![event, Event::Alert(alert)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Trade;
+
+    fn trade(symbol: &str, price: f64, qty: f64) -> Event {
+        Event::Trade(Trade {
+            symbol: symbol.to_string(),
+            trade_id: 1,
+            price,
+            qty,
+            trade_time: 0,
+            is_buyer_maker: false,
+        })
+    }
+
+    #[test]
+    fn per_symbol_thresholds_with_catch_all() {
+        let mut tagger = WhaleTagger::new(vec![
+            WhaleRule {
+                symbol: "BTCUSDT".into(),
+                min_notional: 250_000.0,
+            },
+            WhaleRule {
+                symbol: "*".into(),
+                min_notional: 100_000.0,
+            },
+        ]);
+        // Below the BTC threshold but above the default: only the
+        // symbol rule applies.
+        assert_eq!(tagger.process(trade("BTCUSDT", 50_000.0, 3.0)).len(), 1);
+        let tagged = tagger.process(trade("BTCUSDT", 50_000.0, 6.0));
+        assert_eq!(tagged.len(), 2);
+        assert!(matches!(&tagged[1], Event::Alert(a) if a.source == "whale"));
+        // Other symbols use the catch-all.
+        assert_eq!(tagger.process(trade("ETHUSDT", 2_000.0, 51.0)).len(), 2);
+        assert_eq!(tagger.process(trade("ETHUSDT", 2_000.0, 49.0)).len(), 1);
+    }
+
+    #[test]
+    fn no_matching_rule_means_no_tagging() {
+        let mut tagger = WhaleTagger::new(vec![WhaleRule {
+            symbol: "BTCUSDT".into(),
+            min_notional: 1.0,
+        }]);
+        assert_eq!(tagger.process(trade("ETHUSDT", 1e9, 1.0)).len(), 1);
+    }
+}
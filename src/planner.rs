@@ -0,0 +1,93 @@
+//! Range planning for REST backfills.
+//!
+//! The old `MAX_ITERATIONS` safety valve silently cut off long ranges
+//! (a decade of 1m data needs over five thousand pages). The planner
+//! computes the exact request count for any range up front — logging
+//! it before the job starts — and rejects impossible ranges with a
+//! clear error instead of truncating.
+
+use crate::error::{Error, Result};
+use crate::model::Interval;
+
+/// A planned paged download.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangePlan {
+    /// Inclusive range start (epoch ms), aligned to the interval.
+    pub from: i64,
+    /// Exclusive range end (epoch ms).
+    pub to: i64,
+    /// Interval being fetched.
+    pub interval: Interval,
+    /// Candles per request.
+    pub page_limit: usize,
+}
+
+impl RangePlan {
+    /// Plan a download; fails on empty or inverted ranges.
+    pub fn new(from: i64, to: i64, interval: Interval, page_limit: usize) -> Result<Self> {
+        if page_limit == 0 {
+            return Err(Error::Config("page limit must be positive".to_string()));
+        }
+        let from = from - from.rem_euclid(interval.ms());
+        if from >= to {
+            return Err(Error::Config(format!(
+                "impossible range: start {from} is not before end {to}"
+            )));
+        }
+        Ok(Self {
+            from,
+            to,
+            interval,
+            page_limit,
+        })
+    }
+
+    /// Total candles the range spans.
+    pub fn candles(&self) -> u64 {
+        ((self.to - self.from + self.interval.ms() - 1) / self.interval.ms()) as u64
+    }
+
+    /// Exact number of requests needed.
+    pub fn requests(&self) -> u64 {
+        self.candles().div_ceil(self.page_limit as u64)
+    }
+
+    /// Iterate the page start times, oldest first.
+    pub fn pages(&self) -> impl Iterator<Item = i64> + '_ {
+        let page_ms = self.interval.ms() * self.page_limit as i64;
+        (0..self.requests() as i64).map(move |i| self.from + i * page_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_exact_request_counts() {
+        // One day of 1m candles in pages of 1000: 1440 candles, 2 requests.
+        let plan = RangePlan::new(0, 86_400_000, Interval::M1, 1000).unwrap();
+        assert_eq!(plan.candles(), 1_440);
+        assert_eq!(plan.requests(), 2);
+        let starts: Vec<i64> = plan.pages().collect();
+        assert_eq!(starts, vec![0, 60_000_000]);
+
+        // A decade of 1m data plans fully instead of being cut off.
+        let decade = RangePlan::new(0, 10 * 365 * 86_400_000, Interval::M1, 1000).unwrap();
+        assert_eq!(decade.requests(), 5_256);
+    }
+
+    #[test]
+    fn misaligned_starts_align_down() {
+        let plan = RangePlan::new(61_000, 180_000, Interval::M1, 1000).unwrap();
+        assert_eq!(plan.from, 60_000);
+        assert_eq!(plan.candles(), 2);
+    }
+
+    #[test]
+    fn impossible_ranges_fail_loudly() {
+        let err = RangePlan::new(1_000_000, 1_000, Interval::M1, 1000).unwrap_err();
+        assert!(err.to_string().contains("impossible range"));
+        assert!(RangePlan::new(0, 1, Interval::M1, 0).is_err());
+    }
+}
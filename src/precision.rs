@@ -0,0 +1,145 @@
+//! Precision-aware price and quantity formatting.
+//!
+//! Fixed `%.5f`-style formatting mangles both micro-cap pairs (too few
+//! decimals) and high-priced ones (noise digits). Each symbol's
+//! `tickSize`/`stepSize` from `exchangeInfo` determines how its values
+//! are rounded and rendered instead.
+
+use std::collections::HashMap;
+
+use crate::exchange::symbols::SymbolInfo;
+
+/// Rendering rules for one symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precision {
+    /// Decimals implied by the price tick size.
+    pub price_decimals: u32,
+    /// Decimals implied by the quantity step size.
+    pub qty_decimals: u32,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        // A sane middle ground when no filters are known.
+        Self {
+            price_decimals: 8,
+            qty_decimals: 8,
+        }
+    }
+}
+
+/// Decimal places implied by a filter step like `0.00100000`.
+fn decimals_of(step: f64) -> u32 {
+    if step <= 0.0 {
+        return 8;
+    }
+    let mut value = step;
+    for decimals in 0..=8 {
+        if (value - value.round()).abs() < 1e-9 {
+            return decimals;
+        }
+        value *= 10.0;
+    }
+    8
+}
+
+impl Precision {
+    /// Derive rules from a symbol's exchange filters.
+    pub fn from_info(info: &SymbolInfo) -> Self {
+        Self {
+            price_decimals: info.tick_size.map_or(8, decimals_of),
+            qty_decimals: info.step_size.map_or(8, decimals_of),
+        }
+    }
+
+    fn round_to(value: f64, decimals: u32) -> f64 {
+        let factor = 10f64.powi(decimals as i32);
+        (value * factor).round() / factor
+    }
+
+    /// Round a price to the symbol's tick precision.
+    pub fn round_price(&self, price: f64) -> f64 {
+        Self::round_to(price, self.price_decimals)
+    }
+
+    /// Round a quantity to the symbol's step precision.
+    pub fn round_qty(&self, qty: f64) -> f64 {
+        Self::round_to(qty, self.qty_decimals)
+    }
+
+    /// Render a price with exactly the symbol's decimals.
+    pub fn format_price(&self, price: f64) -> String {
+        format!("{:.*}", self.price_decimals as usize, price)
+    }
+
+    /// Render a quantity with exactly the symbol's decimals.
+    pub fn format_qty(&self, qty: f64) -> String {
+        format!("{:.*}", self.qty_decimals as usize, qty)
+    }
+}
+
+/// Per-symbol precision lookup with a default fallback.
+#[derive(Debug, Clone, Default)]
+pub struct PrecisionMap {
+    by_symbol: HashMap<String, Precision>,
+}
+
+impl PrecisionMap {
+    /// Build the map from an exchangeInfo symbol universe.
+    pub fn from_infos(infos: &[SymbolInfo]) -> Self {
+        Self {
+            by_symbol: infos
+                .iter()
+                .map(|i| (i.symbol.clone(), Precision::from_info(i)))
+                .collect(),
+        }
+    }
+
+    /// Rules for one symbol (default precision when unknown).
+    pub fn get(&self, symbol: &str) -> Precision {
+        self.by_symbol.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// Whether any symbol-specific rules are loaded.
+    pub fn is_empty(&self) -> bool {
+        self.by_symbol.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(tick: f64, step: f64) -> SymbolInfo {
+        SymbolInfo {
+            symbol: "BTCUSDT".into(),
+            status: "TRADING".into(),
+            base_asset: "BTC".into(),
+            quote_asset: "USDT".into(),
+            permissions: Vec::new(),
+            tick_size: Some(tick),
+            step_size: Some(step),
+            min_notional: None,
+        }
+    }
+
+    #[test]
+    fn decimals_follow_filter_steps() {
+        let p = Precision::from_info(&info(0.01, 0.00001));
+        assert_eq!(p.price_decimals, 2);
+        assert_eq!(p.qty_decimals, 5);
+        assert_eq!(p.format_price(50_000.126), "50000.13");
+        assert_eq!(p.format_qty(0.123456789), "0.12346");
+        assert_eq!(p.round_price(50_000.126), 50_000.13);
+        // Integer steps mean whole units.
+        let whole = Precision::from_info(&info(1.0, 1.0));
+        assert_eq!(whole.format_price(42.7), "43");
+    }
+
+    #[test]
+    fn map_falls_back_to_default() {
+        let map = PrecisionMap::from_infos(&[info(0.1, 0.1)]);
+        assert_eq!(map.get("BTCUSDT").price_decimals, 1);
+        assert_eq!(map.get("UNKNOWN").price_decimals, 8);
+    }
+}
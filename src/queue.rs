@@ -0,0 +1,320 @@
+//! Bounded intake queue with explicit overflow behaviour.
+//!
+//! The channel between the WebSocket reader and the processing loop
+//! used to be a fixed `mpsc(32)` that silently applied backpressure.
+//! [`EventQueue`] makes the capacity configurable and the lag strategy
+//! explicit: block the producer, drop the oldest entry (counted), or
+//! spill overflow to disk and replay it once the consumer catches up.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::error::{Error, Result};
+
+/// What to do when the queue is full and the producer keeps sending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Apply backpressure: the producer waits for free capacity.
+    #[default]
+    Block,
+    /// Drop the oldest queued entry and count it.
+    DropOldest,
+    /// Append overflow to a disk file and replay it later.
+    SpillToDisk,
+}
+
+/// The `[channel]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    /// In-memory queue capacity.
+    #[serde(default = "default_capacity")]
+    pub capacity: usize,
+    /// Overflow policy once the queue is full.
+    #[serde(default)]
+    pub overflow: OverflowPolicy,
+    /// Spill file directory (`spill_to_disk` only).
+    #[serde(default)]
+    pub spill_dir: Option<String>,
+}
+
+fn default_capacity() -> usize {
+    1_024
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: default_capacity(),
+            overflow: OverflowPolicy::default(),
+            spill_dir: None,
+        }
+    }
+}
+
+/// Counters surfaced for monitoring.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct QueueMetrics {
+    /// Current backlog length.
+    pub backlog: usize,
+    /// Entries dropped by `drop_oldest`.
+    pub dropped: u64,
+    /// Entries spilled to disk.
+    pub spilled: u64,
+}
+
+struct Inner<T> {
+    queue: Mutex<VecDeque<T>>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    spill_path: Option<PathBuf>,
+    closed: AtomicBool,
+    dropped: AtomicU64,
+    spilled: AtomicU64,
+    notify_recv: Notify,
+    notify_send: Notify,
+}
+
+/// A bounded multi-producer single-consumer queue. Cheap to clone.
+pub struct EventQueue<T> {
+    inner: Arc<Inner<T>>,
+}
+
+impl<T> Clone for EventQueue<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Send + 'static> EventQueue<T> {
+    /// Build a queue from its config section.
+    pub fn new(cfg: &ChannelConfig) -> Result<Self> {
+        let spill_path = match (cfg.overflow, &cfg.spill_dir) {
+            (OverflowPolicy::SpillToDisk, Some(dir)) => {
+                std::fs::create_dir_all(dir)?;
+                Some(PathBuf::from(dir).join(format!("spill-{}.jsonl", std::process::id())))
+            }
+            (OverflowPolicy::SpillToDisk, None) => {
+                return Err(Error::Config(
+                    "overflow = \"spill_to_disk\" needs `spill_dir`".to_string(),
+                ))
+            }
+            _ => None,
+        };
+        Ok(Self {
+            inner: Arc::new(Inner {
+                queue: Mutex::new(VecDeque::with_capacity(cfg.capacity)),
+                capacity: cfg.capacity.max(1),
+                policy: cfg.overflow,
+                spill_path,
+                closed: AtomicBool::new(false),
+                dropped: AtomicU64::new(0),
+                spilled: AtomicU64::new(0),
+                notify_recv: Notify::new(),
+                notify_send: Notify::new(),
+            }),
+        })
+    }
+
+    /// Enqueue one item, applying the overflow policy when full.
+    pub async fn send(&self, item: T) -> Result<()> {
+        loop {
+            {
+                let mut queue = self.inner.queue.lock().expect("queue poisoned");
+                if queue.len() < self.inner.capacity {
+                    queue.push_back(item);
+                    drop(queue);
+                    self.inner.notify_recv.notify_one();
+                    return Ok(());
+                }
+                match self.inner.policy {
+                    OverflowPolicy::Block => {}
+                    OverflowPolicy::DropOldest => {
+                        queue.pop_front();
+                        queue.push_back(item);
+                        drop(queue);
+                        self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.inner.notify_recv.notify_one();
+                        return Ok(());
+                    }
+                    OverflowPolicy::SpillToDisk => {
+                        drop(queue);
+                        self.spill(&item)?;
+                        self.inner.spilled.fetch_add(1, Ordering::Relaxed);
+                        return Ok(());
+                    }
+                }
+            }
+            if self.inner.closed.load(Ordering::Acquire) {
+                return Err(Error::Pipeline("queue closed".to_string()));
+            }
+            self.inner.notify_send.notified().await;
+        }
+    }
+
+    /// Dequeue the next item; `None` once closed and drained.
+    pub async fn recv(&self) -> Option<T> {
+        loop {
+            {
+                let mut queue = self.inner.queue.lock().expect("queue poisoned");
+                if let Some(item) = queue.pop_front() {
+                    let has_room = queue.len() < self.inner.capacity / 2;
+                    drop(queue);
+                    self.inner.notify_send.notify_one();
+                    if has_room {
+                        self.replay_spill();
+                    }
+                    return Some(item);
+                }
+            }
+            if self.inner.closed.load(Ordering::Acquire) {
+                return None;
+            }
+            self.inner.notify_recv.notified().await;
+        }
+    }
+
+    /// Close the queue; pending items can still be received.
+    pub fn close(&self) {
+        self.inner.closed.store(true, Ordering::Release);
+        self.inner.notify_recv.notify_waiters();
+        self.inner.notify_send.notify_waiters();
+    }
+
+    /// Current counters.
+    pub fn metrics(&self) -> QueueMetrics {
+        QueueMetrics {
+            backlog: self.inner.queue.lock().expect("queue poisoned").len(),
+            dropped: self.inner.dropped.load(Ordering::Relaxed),
+            spilled: self.inner.spilled.load(Ordering::Relaxed),
+        }
+    }
+
+    fn spill(&self, item: &T) -> Result<()> {
+        let path = self.inner.spill_path.as_ref().expect("spill path checked");
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        serde_json::to_writer(&mut file, item)?;
+        file.write_all(b"\n")?;
+        Ok(())
+    }
+
+    /// Move spilled items back into memory, preserving order, while
+    /// capacity allows.
+    fn replay_spill(&self) {
+        let Some(path) = self.inner.spill_path.as_ref() else {
+            return;
+        };
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return;
+        };
+        if text.is_empty() {
+            return;
+        }
+        let mut remaining: VecDeque<&str> = text.lines().collect();
+        let mut queue = self.inner.queue.lock().expect("queue poisoned");
+        let mut notified = false;
+        while queue.len() < self.inner.capacity {
+            let Some(line) = remaining.pop_front() else {
+                break;
+            };
+            if let Ok(item) = serde_json::from_str(line) {
+                queue.push_back(item);
+                notified = true;
+            }
+        }
+        let remainder: String = remaining.iter().map(|l| format!("{l}\n")).collect();
+        drop(queue);
+        let _ = std::fs::write(path, remainder);
+        if notified {
+            self.inner.notify_recv.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cfg(capacity: usize, overflow: OverflowPolicy, spill_dir: Option<String>) -> ChannelConfig {
+        ChannelConfig {
+            capacity,
+            overflow,
+            spill_dir,
+        }
+    }
+
+    #[tokio::test]
+    async fn block_policy_applies_backpressure() {
+        let q: EventQueue<i32> = EventQueue::new(&cfg(1, OverflowPolicy::Block, None)).unwrap();
+        q.send(1).await.unwrap();
+        let producer = {
+            let q = q.clone();
+            tokio::spawn(async move { q.send(2).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        assert!(!producer.is_finished(), "send should block while full");
+        assert_eq!(q.recv().await, Some(1));
+        producer.await.unwrap().unwrap();
+        assert_eq!(q.recv().await, Some(2));
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_counts_drops() {
+        let q: EventQueue<i32> =
+            EventQueue::new(&cfg(2, OverflowPolicy::DropOldest, None)).unwrap();
+        for i in 1..=4 {
+            q.send(i).await.unwrap();
+        }
+        assert_eq!(q.metrics().dropped, 2);
+        assert_eq!(q.recv().await, Some(3));
+        assert_eq!(q.recv().await, Some(4));
+    }
+
+    #[tokio::test]
+    async fn spill_replays_in_order() {
+        let dir = std::env::temp_dir().join(format!("bzl-spill-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let q: EventQueue<i32> = EventQueue::new(&cfg(
+            2,
+            OverflowPolicy::SpillToDisk,
+            Some(dir.to_string_lossy().into_owned()),
+        ))
+        .unwrap();
+        for i in 1..=5 {
+            q.send(i).await.unwrap();
+        }
+        assert_eq!(q.metrics().spilled, 3);
+        let mut received = Vec::new();
+        for _ in 0..5 {
+            received.push(q.recv().await.unwrap());
+        }
+        assert_eq!(received, vec![1, 2, 3, 4, 5]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn spill_without_dir_is_a_config_error() {
+        assert!(EventQueue::<i32>::new(&cfg(2, OverflowPolicy::SpillToDisk, None)).is_err());
+    }
+
+    #[tokio::test]
+    async fn close_drains_then_ends() {
+        let q: EventQueue<i32> = EventQueue::new(&cfg(4, OverflowPolicy::Block, None)).unwrap();
+        q.send(1).await.unwrap();
+        q.close();
+        assert_eq!(q.recv().await, Some(1));
+        assert_eq!(q.recv().await, None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Pluggable secret sources for credentials.
+//!
+//! Any config field documented as holding a secret (a ClickHouse
+//! password, an exchange API key or secret) accepts either the literal
+//! value or a `scheme:target` reference, resolved once at startup:
+//!
+//! - `env:VAR` — an environment variable
+//! - `file:/path` — the file's contents, trimmed; refused if the file
+//!   is readable by anyone but its owner
+//! - `keyring:service/account` — the OS credential store, via the
+//!   `secret-tool` CLI (GNOME Keyring / KWallet on Linux)
+//! - `vault:mount/path#field` — a HashiCorp Vault KV v2 secret, read
+//!   over its HTTP API (`VAULT_ADDR`/`VAULT_TOKEN` from the environment)
+//!
+//! A value with no recognized scheme prefix is used as-is, so existing
+//! plaintext config files keep working unchanged.
+
+use crate::error::{Error, Result};
+
+/// Resolve `raw` to its actual credential value.
+pub async fn resolve(raw: &str, http: &reqwest::Client) -> Result<String> {
+    match raw.split_once(':') {
+        Some(("env", name)) => resolve_env(name),
+        Some(("file", path)) => resolve_file(path),
+        Some(("keyring", target)) => resolve_keyring(target),
+        Some(("vault", target)) => resolve_vault(target, http).await,
+        _ => Ok(raw.to_string()),
+    }
+}
+
+fn resolve_env(name: &str) -> Result<String> {
+    std::env::var(name).map_err(|_| Error::Secret(format!("environment variable {name} not set")))
+}
+
+fn resolve_file(path: &str) -> Result<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .map_err(|e| Error::Secret(format!("secret file {path}: {e}")))?
+            .permissions()
+            .mode();
+        if mode & 0o077 != 0 {
+            return Err(Error::Secret(format!(
+                "secret file {path} is readable by group or others (mode {mode:o}); \
+                 chmod 600 it first"
+            )));
+        }
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| Error::Secret(format!("secret file {path}: {e}")))?;
+    Ok(text.trim().to_string())
+}
+
+fn resolve_keyring(target: &str) -> Result<String> {
+    let (service, account) = target.split_once('/').ok_or_else(|| {
+        Error::Secret(format!("keyring target `{target}` must be `service/account`"))
+    })?;
+    let output = std::process::Command::new("secret-tool")
+        .args(["lookup", "service", service, "account", account])
+        .output()
+        .map_err(|e| Error::Secret(format!("secret-tool unavailable: {e}")))?;
+    if !output.status.success() {
+        return Err(Error::Secret(format!(
+            "secret-tool lookup for {service}/{account} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+async fn resolve_vault(target: &str, http: &reqwest::Client) -> Result<String> {
+    let (path, field) = target
+        .split_once('#')
+        .ok_or_else(|| Error::Secret(format!("vault target `{target}` must be `path#field`")))?;
+    let addr = std::env::var("VAULT_ADDR")
+        .map_err(|_| Error::Secret("VAULT_ADDR not set".to_string()))?;
+    let token = std::env::var("VAULT_TOKEN")
+        .map_err(|_| Error::Secret("VAULT_TOKEN not set".to_string()))?;
+    let body: serde_json::Value = http
+        .get(format!("{}/v1/{}", addr.trim_end_matches('/'), path))
+        .header("X-Vault-Token", token)
+        .send()
+        .await
+        .map_err(|e| Error::Secret(format!("vault request failed: {e}")))?
+        .error_for_status()
+        .map_err(|e| Error::Secret(format!("vault rejected the request: {e}")))?
+        .json()
+        .await
+        .map_err(|e| Error::Secret(format!("vault response invalid: {e}")))?;
+    // KV v2 nests the payload under data.data; fall back to KV v1's data.
+    let value = body
+        .pointer(&format!("/data/data/{field}"))
+        .or_else(|| body.pointer(&format!("/data/{field}")))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| Error::Secret(format!("vault secret {path} has no field `{field}`")))?;
+    Ok(value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn plain_values_pass_through() {
+        let http = reqwest::Client::new();
+        assert_eq!(resolve("hunter2", &http).await.unwrap(), "hunter2");
+    }
+
+    #[tokio::test]
+    async fn env_scheme_reads_the_variable() {
+        std::env::set_var("BZL_TEST_SECRET_ENV", "s3cr3t");
+        let http = reqwest::Client::new();
+        assert_eq!(
+            resolve("env:BZL_TEST_SECRET_ENV", &http).await.unwrap(),
+            "s3cr3t"
+        );
+        std::env::remove_var("BZL_TEST_SECRET_ENV");
+    }
+
+    #[tokio::test]
+    async fn missing_env_var_is_an_error() {
+        let http = reqwest::Client::new();
+        assert!(resolve("env:BZL_TEST_SECRET_MISSING", &http).await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_scheme_rejects_group_readable_files() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join("bzl_secret_test_group_readable");
+        let mut f = std::fs::File::create(&path).unwrap();
+        writeln!(f, "s3cr3t").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+
+        let http = reqwest::Client::new();
+        let err = resolve(&format!("file:{}", path.display()), &http)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("readable by group"));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_scheme_reads_a_locked_down_file() {
+        use std::io::Write;
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join("bzl_secret_test_locked_down");
+        let mut f = std::fs::File::create(&path).unwrap();
+        writeln!(f, "s3cr3t").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        let http = reqwest::Client::new();
+        assert_eq!(
+            resolve(&format!("file:{}", path.display()), &http)
+                .await
+                .unwrap(),
+            "s3cr3t"
+        );
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,218 @@
+//! Micro-batching between stream intake and sinks.
+//!
+//! Live streams deliver one event at a time, but ClickHouse (and to a
+//! lesser extent files) want fewer, larger writes. [`BatchingSink`]
+//! wraps any sink and holds events until either the size bound or the
+//! age bound is hit. Collectors should also call [`Sink::flush`] on a
+//! timer so a quiet stream cannot strand a partial batch forever.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+
+/// Size/latency bounds for one sink's batches.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BatchSettings {
+    /// Deliver once this many events are buffered.
+    #[serde(default = "default_max_events")]
+    pub max_events: usize,
+    /// Deliver once the oldest buffered event is this old.
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+}
+
+fn default_max_events() -> usize {
+    500
+}
+
+fn default_max_delay_ms() -> u64 {
+    1_000
+}
+
+impl Default for BatchSettings {
+    fn default() -> Self {
+        Self {
+            max_events: default_max_events(),
+            max_delay_ms: default_max_delay_ms(),
+        }
+    }
+}
+
+/// The `[batch]` config section: defaults plus per-sink overrides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchConfig {
+    /// Defaults applied to every sink.
+    #[serde(flatten)]
+    pub defaults: BatchSettings,
+    /// Overrides keyed by sink name, e.g. `[batch.sink.clickhouse]`.
+    #[serde(default, rename = "sink")]
+    pub per_sink: HashMap<String, BatchSettings>,
+}
+
+impl BatchConfig {
+    /// Effective settings for the named sink.
+    pub fn settings_for(&self, sink: &str) -> BatchSettings {
+        self.per_sink.get(sink).copied().unwrap_or(self.defaults)
+    }
+}
+
+/// Wraps a sink and groups incoming events into bounded batches.
+pub struct BatchingSink {
+    inner: Box<dyn Sink>,
+    settings: BatchSettings,
+    buffer: Vec<Event>,
+    oldest: Option<Instant>,
+}
+
+impl BatchingSink {
+    /// Wrap `inner` with the given bounds.
+    pub fn new(inner: Box<dyn Sink>, settings: BatchSettings) -> Self {
+        Self {
+            inner,
+            settings,
+            buffer: Vec::with_capacity(settings.max_events),
+            oldest: None,
+        }
+    }
+
+    /// Number of events currently buffered.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    fn due(&self) -> bool {
+        if self.buffer.len() >= self.settings.max_events {
+            return true;
+        }
+        match self.oldest {
+            Some(oldest) => oldest.elapsed() >= Duration::from_millis(self.settings.max_delay_ms),
+            None => false,
+        }
+    }
+
+    async fn deliver(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let batch = std::mem::take(&mut self.buffer);
+        self.oldest = None;
+        self.inner.write(&batch).await
+    }
+}
+
+#[async_trait]
+impl Sink for BatchingSink {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        if self.buffer.is_empty() && !events.is_empty() {
+            self.oldest = Some(Instant::now());
+        }
+        self.buffer.extend_from_slice(events);
+        if self.due() {
+            self.deliver().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.deliver().await?;
+        self.inner.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::model::Trade;
+
+    /// Records delivered batch sizes for assertions.
+    struct RecordingSink(Arc<Mutex<Vec<usize>>>);
+
+    #[async_trait]
+    impl Sink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        async fn write(&mut self, events: &[Event]) -> Result<()> {
+            self.0.lock().unwrap().push(events.len());
+            Ok(())
+        }
+    }
+
+    fn trade(id: i64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: id,
+            price: 100.0,
+            qty: 1.0,
+            trade_time: id,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn batching(max_events: usize, max_delay_ms: u64) -> (BatchingSink, Arc<Mutex<Vec<usize>>>) {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let sink = BatchingSink::new(
+            Box::new(RecordingSink(batches.clone())),
+            BatchSettings {
+                max_events,
+                max_delay_ms,
+            },
+        );
+        (sink, batches)
+    }
+
+    #[tokio::test]
+    async fn buffers_until_size_bound() {
+        let (mut sink, batches) = batching(3, 60_000);
+        sink.write(&[trade(1)]).await.unwrap();
+        sink.write(&[trade(2)]).await.unwrap();
+        assert!(batches.lock().unwrap().is_empty());
+        assert_eq!(sink.buffered(), 2);
+        sink.write(&[trade(3)]).await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![3]);
+        assert_eq!(sink.buffered(), 0);
+    }
+
+    #[tokio::test]
+    async fn age_bound_delivers_small_batches() {
+        let (mut sink, batches) = batching(1_000, 0);
+        sink.write(&[trade(1)]).await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn flush_delivers_remainder() {
+        let (mut sink, batches) = batching(100, 60_000);
+        sink.write(&[trade(1), trade(2)]).await.unwrap();
+        sink.flush().await.unwrap();
+        assert_eq!(*batches.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn per_sink_overrides_apply() {
+        let cfg: BatchConfig = toml::from_str(
+            r#"
+            max_events = 200
+            [sink.clickhouse]
+            max_events = 5000
+            max_delay_ms = 2000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(cfg.settings_for("csv").max_events, 200);
+        assert_eq!(cfg.settings_for("clickhouse").max_events, 5_000);
+    }
+}
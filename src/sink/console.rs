@@ -0,0 +1,113 @@
+//! Console sink: prints events, mainly for dry runs and debugging.
+
+use async_trait::async_trait;
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+use crate::precision::PrecisionMap;
+use crate::util::{format_ms, TimeStyle};
+
+/// Prints one line per event to stdout.
+#[derive(Debug, Default)]
+pub struct ConsoleSink {
+    style: TimeStyle,
+    precision: PrecisionMap,
+}
+
+impl ConsoleSink {
+    /// Create a console sink rendering timestamps with `style`.
+    pub fn new(style: TimeStyle) -> Self {
+        Self {
+            style,
+            precision: PrecisionMap::default(),
+        }
+    }
+
+    /// Render prices/quantities with per-symbol exchange precision.
+    pub fn with_precision(mut self, precision: PrecisionMap) -> Self {
+        self.precision = precision;
+        self
+    }
+}
+
+#[async_trait]
+impl Sink for ConsoleSink {
+    fn name(&self) -> &str {
+        "console"
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        for event in events {
+            match event {
+                Event::Trade(t) => {
+                    let p = self.precision.get(&t.symbol);
+                    println!(
+                        "[{}] {} {} @ {} qty {}",
+                        crate::i18n::t("trade"),
+                        format_ms(t.trade_time, self.style),
+                        t.symbol,
+                        p.format_price(t.price),
+                        p.format_qty(t.qty)
+                    )
+                }
+                Event::Kline(k) => {
+                    let p = self.precision.get(&k.symbol);
+                    println!(
+                        "[{}] {} {} {} o {} h {} l {} c {} v {}",
+                        crate::i18n::t("kline"),
+                        format_ms(k.open_time, self.style),
+                        k.symbol,
+                        k.interval,
+                        p.format_price(k.open),
+                        p.format_price(k.high),
+                        p.format_price(k.low),
+                        p.format_price(k.close),
+                        p.format_qty(k.volume)
+                    )
+                }
+                Event::Bbo(b) => {
+                    let p = self.precision.get(&b.symbol);
+                    println!(
+                        "[bbo] {} {} bid {} x {} ask {} x {}",
+                        format_ms(b.time, self.style),
+                        b.symbol,
+                        p.format_price(b.bid_price),
+                        p.format_qty(b.bid_qty),
+                        p.format_price(b.ask_price),
+                        p.format_qty(b.ask_qty)
+                    )
+                }
+                Event::Depth(d) => println!(
+                    "[depth] {} {} {}x{} levels",
+                    format_ms(d.time, self.style),
+                    d.symbol,
+                    d.bids.len(),
+                    d.asks.len()
+                ),
+                Event::Ticker(t) => println!(
+                    "[ticker] {} {} last {} 24h vol {}",
+                    format_ms(t.time, self.style),
+                    t.symbol,
+                    t.close,
+                    t.volume
+                ),
+                Event::Rolling(r) => println!(
+                    "[rolling] {} {} {} high {} low {} vol {} ret {:+.2}%",
+                    format_ms(r.time, self.style),
+                    r.symbol,
+                    r.window,
+                    r.high,
+                    r.low,
+                    r.volume,
+                    r.return_pct
+                ),
+                Event::Alert(a) => println!("[{}] {} {}: {}", crate::i18n::t("alert"), a.symbol, a.source, a.message),
+                Event::Quarantined(q) => {
+                    println!("[{}] {} {}: {}", crate::i18n::t("quarantine"), q.symbol, q.reason, q.payload)
+                }
+            }
+        }
+        Ok(())
+    }
+}
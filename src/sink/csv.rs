@@ -0,0 +1,301 @@
+//! CSV sink: daily append-mode files under a data directory.
+//!
+//! Layout mirrors the historical download layout:
+//! `<root>/<SYMBOL>/<kind>-YYYY-MM-DD.csv`, one file per UTC day.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+
+use super::rowbuf::RowBuffer;
+use super::Sink;
+use crate::error::Result;
+use crate::model::{Kline, Trade};
+use crate::pipeline::Event;
+use crate::util::TimeStyle;
+
+/// Appends events to per-symbol, per-day CSV files.
+pub struct CsvSink {
+    root: PathBuf,
+    writers: HashMap<PathBuf, BufWriter<File>>,
+    row: RowBuffer,
+    style: TimeStyle,
+}
+
+impl CsvSink {
+    /// Create a sink rooted at `root` (created on demand).
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            writers: HashMap::new(),
+            row: RowBuffer::new(),
+            style: TimeStyle::default(),
+        }
+    }
+
+    /// Render timestamps with `style` instead of raw milliseconds.
+    pub fn with_style(mut self, style: TimeStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    fn path_for(&self, symbol: &str, kind: &str, time_ms: i64) -> PathBuf {
+        let day = Utc
+            .timestamp_millis_opt(time_ms)
+            .single()
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "invalid".to_string());
+        self.root.join(symbol).join(format!("{kind}-{day}.csv"))
+    }
+
+    fn writer(
+        writers: &mut HashMap<PathBuf, BufWriter<File>>,
+        path: PathBuf,
+    ) -> Result<&mut BufWriter<File>> {
+        if !writers.contains_key(&path) {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // A crash mid-write can leave a truncated last line that
+            // poisons downstream parsers; trim it before appending.
+            if let Ok(true) = repair_incomplete_tail(&path) {
+                tracing::warn!(path = %path.display(), "trimmed incomplete trailing line");
+            }
+            let file = OpenOptions::new().create(true).append(true).open(&path)?;
+            writers.insert(path.clone(), BufWriter::new(file));
+        }
+        Ok(writers.get_mut(&path).expect("inserted above"))
+    }
+
+    fn write_trade(&mut self, t: &Trade) -> Result<()> {
+        let path = self.path_for(&t.symbol, "trades", t.trade_time);
+        self.row.clear();
+        self.row.push_trade_styled(t, self.style);
+        let w = Self::writer(&mut self.writers, path)?;
+        w.write_all(self.row.as_bytes())?;
+        Ok(())
+    }
+
+    fn write_bbo(&mut self, b: &crate::model::Bbo) -> Result<()> {
+        let path = self.path_for(&b.symbol, "bbo", b.time);
+        let w = Self::writer(&mut self.writers, path)?;
+        writeln!(
+            w,
+            "{},{},{},{},{}",
+            b.time, b.bid_price, b.bid_qty, b.ask_price, b.ask_qty
+        )?;
+        Ok(())
+    }
+
+    fn write_depth(&mut self, d: &crate::model::DepthSnapshot) -> Result<()> {
+        let path = self.path_for(&d.symbol, "depth", d.time);
+        let w = Self::writer(&mut self.writers, path)?;
+        // Level arrays are JSON so the row stays one line.
+        writeln!(
+            w,
+            "{},{},{}",
+            d.time,
+            serde_json::to_string(&d.bids)?,
+            serde_json::to_string(&d.asks)?
+        )?;
+        Ok(())
+    }
+
+    fn write_rolling(&mut self, r: &crate::model::RollingStats) -> Result<()> {
+        let path = self.path_for(&r.symbol, &format!("rolling-{}", r.window), r.time);
+        let w = Self::writer(&mut self.writers, path)?;
+        writeln!(
+            w,
+            "{},{},{},{},{:.4}",
+            r.time, r.high, r.low, r.volume, r.return_pct
+        )?;
+        Ok(())
+    }
+
+    fn write_quarantine(&mut self, q: &crate::pipeline::Quarantine) -> Result<()> {
+        let path = self.path_for(&q.symbol, "quarantine", q.time);
+        let w = Self::writer(&mut self.writers, path)?;
+        // The payload is JSON (may contain commas), so it goes last and
+        // the reason is kept comma-free.
+        writeln!(w, "{},{},{}", q.time, q.reason.replace(',', ";"), q.payload)?;
+        Ok(())
+    }
+
+    fn write_kline(&mut self, k: &Kline) -> Result<()> {
+        let path = self.path_for(&k.symbol, &format!("klines-{}", k.interval), k.open_time);
+        self.row.clear();
+        self.row.push_kline_styled(k, self.style);
+        let w = Self::writer(&mut self.writers, path)?;
+        w.write_all(self.row.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Truncate a CSV file back to its last complete line. Returns whether
+/// anything was trimmed; a missing file is a no-op.
+pub fn repair_incomplete_tail(path: &std::path::Path) -> Result<bool> {
+    use std::io::{Read, Seek, SeekFrom};
+
+    let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e.into()),
+    };
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(false);
+    }
+    // Scan backwards in chunks for the last newline.
+    const CHUNK: u64 = 4096;
+    let mut end = len;
+    loop {
+        let start = end.saturating_sub(CHUNK);
+        let mut buf = vec![0u8; (end - start) as usize];
+        file.seek(SeekFrom::Start(start))?;
+        file.read_exact(&mut buf)?;
+        if end == len && buf.last() == Some(&b'\n') {
+            return Ok(false);
+        }
+        if let Some(pos) = buf.iter().rposition(|&b| b == b'\n') {
+            file.set_len(start + pos as u64 + 1)?;
+            return Ok(true);
+        }
+        if start == 0 {
+            // No newline at all: the whole file is one partial line.
+            file.set_len(0)?;
+            return Ok(true);
+        }
+        end = start;
+    }
+}
+
+/// Repair every CSV file under an archive tree; returns how many files
+/// were trimmed.
+pub fn repair_tree(root: &std::path::Path) -> Result<usize> {
+    let mut repaired = 0;
+    if !root.exists() {
+        return Ok(0);
+    }
+    for symbol_dir in std::fs::read_dir(root)? {
+        let symbol_dir = symbol_dir?;
+        if !symbol_dir.file_type()?.is_dir() {
+            continue;
+        }
+        for file in std::fs::read_dir(symbol_dir.path())? {
+            let path = file?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("csv")
+                && repair_incomplete_tail(&path)?
+            {
+                tracing::warn!(path = %path.display(), "trimmed incomplete trailing line");
+                repaired += 1;
+            }
+        }
+    }
+    Ok(repaired)
+}
+
+#[async_trait]
+impl Sink for CsvSink {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        for event in events {
+            match event {
+                Event::Trade(t) => self.write_trade(t)?,
+                Event::Kline(k) => self.write_kline(k)?,
+                Event::Bbo(b) => self.write_bbo(b)?,
+                Event::Depth(d) => self.write_depth(d)?,
+                // Raw ticker updates are transient; only the derived
+                // rolling stats are archived.
+                Event::Ticker(_) => {}
+                Event::Rolling(r) => self.write_rolling(r)?,
+                // Alerts are operational, not archive data.
+                Event::Alert(_) => {}
+                Event::Quarantined(q) => self.write_quarantine(q)?,
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        for writer in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(time: i64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 42,
+            price: 50_000.5,
+            qty: 0.25,
+            trade_time: time,
+            is_buyer_maker: true,
+        })
+    }
+
+    #[tokio::test]
+    async fn truncated_tail_is_repaired_before_append() {
+        let dir = std::env::temp_dir().join(format!("bzl-csv-repair-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let day_dir = dir.join("BTCUSDT");
+        std::fs::create_dir_all(&day_dir).unwrap();
+        let path = day_dir.join("trades-2023-11-14.csv");
+        std::fs::write(&path, "1,1699920000000,100.0,1.0,true\n2,169992").unwrap();
+        let mut sink = CsvSink::new(&dir);
+        sink.write(&[trade(1_699_920_000_000)]).await.unwrap();
+        sink.flush().await.unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "1,1699920000000,100.0,1.0,true");
+        assert!(lines[1].starts_with("42,"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn repair_handles_edge_cases() {
+        let dir = std::env::temp_dir().join(format!("bzl-csv-repair2-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("BTCUSDT")).unwrap();
+        let complete = dir.join("BTCUSDT").join("a.csv");
+        std::fs::write(&complete, "1,2,3\n").unwrap();
+        assert!(!repair_incomplete_tail(&complete).unwrap());
+        let headless = dir.join("BTCUSDT").join("b.csv");
+        std::fs::write(&headless, "no newline at all").unwrap();
+        assert!(repair_incomplete_tail(&headless).unwrap());
+        assert_eq!(std::fs::metadata(&headless).unwrap().len(), 0);
+        assert_eq!(repair_tree(&dir).unwrap(), 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn writes_daily_files() {
+        let dir = std::env::temp_dir().join(format!("bzl-csv-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut sink = CsvSink::new(&dir);
+        // 2023-11-14 and 2023-11-15 UTC.
+        sink.write(&[trade(1_699_920_000_000), trade(1_700_006_400_000)])
+            .await
+            .unwrap();
+        sink.flush().await.unwrap();
+        let day1 = dir.join("BTCUSDT").join("trades-2023-11-14.csv");
+        let day2 = dir.join("BTCUSDT").join("trades-2023-11-15.csv");
+        let line = std::fs::read_to_string(&day1).unwrap();
+        assert_eq!(line.trim(), "42,1699920000000,50000.5,0.25,true");
+        assert!(day2.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Dry-run sink: full parsing, zero writes.
+//!
+//! Wraps the name of a real sink and records what *would* have been
+//! written — per-kind counts and the tables/files that would be
+//! touched — printing the summary on every flush. Useful to validate a
+//! new config against production storage without touching it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+
+/// Swallows events while recording their would-be destinations.
+pub struct DryRunSink {
+    inner_name: String,
+    counts: BTreeMap<&'static str, usize>,
+    targets: BTreeSet<String>,
+}
+
+impl DryRunSink {
+    /// Create a dry-run stand-in for the sink named `inner_name`.
+    pub fn new(inner_name: impl Into<String>) -> Self {
+        Self {
+            inner_name: inner_name.into(),
+            counts: BTreeMap::new(),
+            targets: BTreeSet::new(),
+        }
+    }
+
+    fn day(time_ms: i64) -> String {
+        Utc.timestamp_millis_opt(time_ms)
+            .single()
+            .map(|t| t.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "invalid".to_string())
+    }
+
+    /// The table or file one event would land in.
+    fn target(&self, event: &Event) -> String {
+        let is_csv = self.inner_name == "csv";
+        match event {
+            Event::Trade(t) if is_csv => {
+                format!("{}/trades-{}.csv", t.symbol, Self::day(t.trade_time))
+            }
+            Event::Kline(k) if is_csv => format!(
+                "{}/klines-{}-{}.csv",
+                k.symbol,
+                k.interval,
+                Self::day(k.open_time)
+            ),
+            Event::Quarantined(q) if is_csv => {
+                format!("{}/quarantine-{}.csv", q.symbol, Self::day(q.time))
+            }
+            Event::Bbo(b) if is_csv => format!("{}/bbo-{}.csv", b.symbol, Self::day(b.time)),
+            Event::Depth(d) if is_csv => format!("{}/depth-{}.csv", d.symbol, Self::day(d.time)),
+            Event::Ticker(_) if is_csv => "(not persisted)".to_string(),
+            Event::Rolling(r) if is_csv => {
+                format!("{}/rolling-{}-{}.csv", r.symbol, r.window, Self::day(r.time))
+            }
+            Event::Alert(_) if is_csv => "(not persisted)".to_string(),
+            Event::Trade(_) => "table trades".to_string(),
+            Event::Bbo(_) => "table bbo".to_string(),
+            Event::Depth(_) => "table depth".to_string(),
+            Event::Ticker(_) => "(not persisted)".to_string(),
+            Event::Rolling(_) => "table rolling_stats".to_string(),
+            Event::Kline(_) => "table klines".to_string(),
+            Event::Alert(_) => "table alerts".to_string(),
+            Event::Quarantined(_) => "table quarantine".to_string(),
+        }
+    }
+
+    fn kind(event: &Event) -> &'static str {
+        match event {
+            Event::Trade(_) => "trades",
+            Event::Kline(_) => "klines",
+            Event::Bbo(_) => "bbo",
+            Event::Depth(_) => "depth",
+            Event::Ticker(_) => "tickers",
+            Event::Rolling(_) => "rolling",
+            Event::Alert(_) => "alerts",
+            Event::Quarantined(_) => "quarantined",
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for DryRunSink {
+    fn name(&self) -> &str {
+        &self.inner_name
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        for event in events {
+            *self.counts.entry(Self::kind(event)).or_default() += 1;
+            self.targets.insert(self.target(event));
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.counts.is_empty() {
+            return Ok(());
+        }
+        let counts: Vec<String> = self
+            .counts
+            .iter()
+            .map(|(kind, n)| format!("{n} {kind}"))
+            .collect();
+        println!(
+            "[dry-run] sink `{}`: would write {}",
+            self.inner_name,
+            counts.join(", ")
+        );
+        for target in &self.targets {
+            println!("[dry-run]   -> {target}");
+        }
+        Ok(())
+    }
+}
+
+/// Replace every sink of every flow with a dry-run stand-in.
+pub fn make_flows_dry(flows: &mut [crate::pipeline::spec::Flow]) {
+    for flow in flows {
+        flow.sinks = flow
+            .sinks
+            .iter()
+            .map(|s| Box::new(DryRunSink::new(s.name())) as Box<dyn Sink>)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Kline, Trade};
+
+    #[tokio::test]
+    async fn records_targets_without_writing() {
+        let mut sink = DryRunSink::new("csv");
+        sink.write(&[
+            Event::Trade(Trade {
+                symbol: "BTCUSDT".into(),
+                trade_id: 1,
+                price: 1.0,
+                qty: 1.0,
+                trade_time: 1_699_920_000_000,
+                is_buyer_maker: false,
+            }),
+            Event::Kline(Kline {
+                symbol: "BTCUSDT".into(),
+                interval: "1m".into(),
+                open_time: 1_699_920_000_000,
+                close_time: 1_699_920_059_999,
+                open: 1.0,
+                high: 1.0,
+                low: 1.0,
+                close: 1.0,
+                volume: 1.0,
+                quote_volume: 1.0,
+                trade_count: 1,
+            }),
+        ])
+        .await
+        .unwrap();
+        assert_eq!(sink.counts.get("trades"), Some(&1));
+        assert!(sink.targets.contains("BTCUSDT/trades-2023-11-14.csv"));
+        assert!(sink
+            .targets
+            .contains("BTCUSDT/klines-1m-2023-11-14.csv"));
+
+        let mut ch = DryRunSink::new("clickhouse");
+        ch.write(&[Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price: 1.0,
+            qty: 1.0,
+            trade_time: 0,
+            is_buyer_maker: false,
+        })])
+        .await
+        .unwrap();
+        assert!(ch.targets.contains("table trades"));
+    }
+}
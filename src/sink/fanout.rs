@@ -0,0 +1,122 @@
+//! Fan-out to several sinks.
+//!
+//! Used for dual-writing to multiple ClickHouse clusters: every batch
+//! goes to every target, each target keeps its own retry/WAL state,
+//! and one target's failure never starves the others — the error is
+//! surfaced only after all targets were attempted.
+
+use async_trait::async_trait;
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+
+/// Writes every batch to every inner sink.
+pub struct FanoutSink {
+    name: String,
+    sinks: Vec<Box<dyn Sink>>,
+}
+
+impl FanoutSink {
+    /// Combine sinks under one name.
+    pub fn new(name: impl Into<String>, sinks: Vec<Box<dyn Sink>>) -> Self {
+        Self {
+            name: name.into(),
+            sinks,
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for FanoutSink {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        let mut first_error = None;
+        for sink in self.sinks.iter_mut() {
+            if let Err(e) = sink.write(events).await {
+                tracing::error!(target = sink.name(), error = %e, "fanout target write failed");
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let mut first_error = None;
+        for sink in self.sinks.iter_mut() {
+            if let Err(e) = sink.flush().await {
+                first_error.get_or_insert(e);
+            }
+        }
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::model::Trade;
+
+    struct CountingSink {
+        writes: Arc<AtomicUsize>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Sink for CountingSink {
+        fn name(&self) -> &str {
+            "counting"
+        }
+
+        async fn write(&mut self, _events: &[Event]) -> Result<()> {
+            self.writes.fetch_add(1, Ordering::Relaxed);
+            if self.fail {
+                Err(crate::error::Error::Database("down".to_string()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn all_targets_are_attempted_even_when_one_fails() {
+        let a = Arc::new(AtomicUsize::new(0));
+        let b = Arc::new(AtomicUsize::new(0));
+        let mut fanout = FanoutSink::new(
+            "clickhouse",
+            vec![
+                Box::new(CountingSink {
+                    writes: a.clone(),
+                    fail: true,
+                }),
+                Box::new(CountingSink {
+                    writes: b.clone(),
+                    fail: false,
+                }),
+            ],
+        );
+        let events = vec![Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: 1,
+            price: 1.0,
+            qty: 1.0,
+            trade_time: 0,
+            is_buyer_maker: false,
+        })];
+        assert!(fanout.write(&events).await.is_err());
+        assert_eq!(a.load(Ordering::Relaxed), 1);
+        assert_eq!(b.load(Ordering::Relaxed), 1);
+    }
+}
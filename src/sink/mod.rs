@@ -0,0 +1,33 @@
+//! Sinks: where events go once the pipeline is done with them.
+
+pub mod batch;
+pub mod clickhouse;
+pub mod console;
+pub mod csv;
+pub mod dry_run;
+pub mod fanout;
+pub mod rowbuf;
+pub mod ticker;
+pub mod wal;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::pipeline::Event;
+
+/// A destination for pipeline output. Sinks receive events in batches
+/// (a batch may be a single event) and must tolerate being flushed at
+/// any time.
+#[async_trait]
+pub trait Sink: Send {
+    /// Name used in pipeline definitions and logs.
+    fn name(&self) -> &str;
+
+    /// Persist a batch of events.
+    async fn write(&mut self, events: &[Event]) -> Result<()>;
+
+    /// Flush any buffered state to durable storage.
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+//! Aggregated console ticker.
+//!
+//! One line per trade is unreadable on liquid pairs. The `ticker` sink
+//! aggregates trades per symbol into fixed windows (default one
+//! second, `[output] ticker_window` to change) and prints one summary
+//! line per window — last price, net volume, buy/sell ratio — leaving
+//! raw persistence to the other sinks.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+
+#[derive(Default)]
+struct WindowState {
+    window_start: i64,
+    last_price: f64,
+    buy_volume: f64,
+    sell_volume: f64,
+    trades: u64,
+}
+
+const SPARKS: [char; 8] = ['\u{2581}', '\u{2582}', '\u{2583}', '\u{2584}', '\u{2585}', '\u{2586}', '\u{2587}', '\u{2588}'];
+const HISTORY: usize = 16;
+
+/// Render values as a unicode sparkline.
+pub fn sparkline(values: &[f64]) -> String {
+    let (min, max) = values.iter().fold((f64::MAX, f64::MIN), |(lo, hi), &v| {
+        (lo.min(v), hi.max(v))
+    });
+    values
+        .iter()
+        .map(|&v| {
+            if max <= min {
+                SPARKS[0]
+            } else {
+                let idx = ((v - min) / (max - min) * (SPARKS.len() - 1) as f64).round() as usize;
+                SPARKS[idx.min(SPARKS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+/// Prints per-window trade summaries.
+pub struct TickerSink {
+    window_ms: i64,
+    color: bool,
+    state: HashMap<String, WindowState>,
+    history: HashMap<String, Vec<f64>>,
+}
+
+impl TickerSink {
+    /// Create a ticker aggregating over `window_ms`.
+    pub fn new(window_ms: i64) -> Self {
+        Self {
+            window_ms: window_ms.max(1),
+            color: false,
+            state: HashMap::new(),
+            history: HashMap::new(),
+        }
+    }
+
+    /// Colorize by direction and append a price sparkline.
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    fn print(&mut self, symbol: &str) {
+        let Some(state) = self.state.get(symbol) else {
+            return;
+        };
+        let total = state.buy_volume + state.sell_volume;
+        let buy_ratio = if total > 0.0 {
+            state.buy_volume / total * 100.0
+        } else {
+            0.0
+        };
+        let line = format!(
+            "[ticker] {} {} last {} vol {:.4} buy {:.0}% ({} trades)",
+            state.window_start, symbol, state.last_price, total, buy_ratio, state.trades
+        );
+        if !self.color {
+            println!("{line}");
+            return;
+        }
+        let history = self.history.entry(symbol.to_string()).or_default();
+        let up = history.last().is_none_or(|&prev| state.last_price >= prev);
+        history.push(state.last_price);
+        if history.len() > HISTORY {
+            history.remove(0);
+        }
+        let tint = if up { "\x1b[32m" } else { "\x1b[31m" };
+        println!("{tint}{line}\x1b[0m {}", sparkline(history));
+    }
+}
+
+#[async_trait]
+impl Sink for TickerSink {
+    fn name(&self) -> &str {
+        "ticker"
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        for event in events {
+            let Event::Trade(t) = event else {
+                continue;
+            };
+            let window_start = t.trade_time - t.trade_time.rem_euclid(self.window_ms);
+            let state = self.state.entry(t.symbol.clone()).or_default();
+            let rolled = state.trades > 0 && state.window_start != window_start;
+            if rolled {
+                let symbol = t.symbol.clone();
+                self.print(&symbol);
+                *self.state.entry(symbol).or_default() = WindowState::default();
+            }
+            let state = self.state.entry(t.symbol.clone()).or_default();
+            state.window_start = window_start;
+            state.last_price = t.price;
+            state.trades += 1;
+            if t.is_buyer_maker {
+                state.sell_volume += t.qty;
+            } else {
+                state.buy_volume += t.qty;
+            }
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let symbols: Vec<String> = self
+            .state
+            .iter()
+            .filter(|(_, s)| s.trades > 0)
+            .map(|(symbol, _)| symbol.clone())
+            .collect();
+        for symbol in symbols {
+            self.print(&symbol);
+        }
+        self.state.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Trade;
+
+    fn trade(time: i64, qty: f64, buyer_maker: bool) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: time,
+            price: 100.0,
+            qty,
+            trade_time: time,
+            is_buyer_maker: buyer_maker,
+        })
+    }
+
+    #[tokio::test]
+    async fn windows_accumulate_and_roll() {
+        let mut sink = TickerSink::new(1_000);
+        sink.write(&[trade(100, 1.0, false), trade(200, 3.0, true)])
+            .await
+            .unwrap();
+        {
+            let state = &sink.state["BTCUSDT"];
+            assert_eq!(state.trades, 2);
+            assert_eq!(state.buy_volume, 1.0);
+            assert_eq!(state.sell_volume, 3.0);
+        }
+        // Crossing into the next second rolls the window.
+        sink.write(&[trade(1_100, 0.5, false)]).await.unwrap();
+        let state = &sink.state["BTCUSDT"];
+        assert_eq!(state.trades, 1);
+        assert_eq!(state.window_start, 1_000);
+        sink.flush().await.unwrap();
+        assert!(sink.state.is_empty());
+    }
+
+    #[test]
+    fn sparklines_scale_to_the_range() {
+        assert_eq!(sparkline(&[1.0, 1.0, 1.0]), "\u{2581}\u{2581}\u{2581}");
+        let line = sparkline(&[0.0, 0.5, 1.0]);
+        let chars: Vec<char> = line.chars().collect();
+        assert_eq!(chars[0], '\u{2581}');
+        assert_eq!(chars[2], '\u{2588}');
+        assert!(chars[1] > chars[0] && chars[1] < chars[2]);
+    }
+}
@@ -0,0 +1,398 @@
+//! Local write-ahead buffer for database outages.
+//!
+//! When the wrapped sink (normally ClickHouse) fails, batches are
+//! spilled to numbered segment files instead of being dropped, and
+//! replayed in order as soon as a later write or flush succeeds
+//! against the sink again. The buffer is size-capped: at the limit the
+//! oldest segments are dropped (loudly) rather than filling the disk.
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::Sink;
+use crate::error::Result;
+use crate::pipeline::Event;
+
+/// The `[wal]` config section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalConfig {
+    /// Segment directory; defaults to `<data_dir>/wal`.
+    #[serde(default)]
+    pub dir: Option<String>,
+    /// Size cap for the buffer in megabytes.
+    #[serde(default = "default_max_mb")]
+    pub max_mb: u64,
+    /// Replay attempts before a segment is moved to the dead-letter
+    /// directory instead of being retried forever.
+    #[serde(default = "default_max_replay_retries")]
+    pub max_replay_retries: u32,
+}
+
+fn default_max_replay_retries() -> u32 {
+    5
+}
+
+fn default_max_mb() -> u64 {
+    512
+}
+
+impl Default for WalConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            max_mb: default_max_mb(),
+            max_replay_retries: default_max_replay_retries(),
+        }
+    }
+}
+
+/// Wraps a sink with a disk write-ahead buffer.
+pub struct WalSink {
+    inner: Box<dyn Sink>,
+    dir: PathBuf,
+    max_bytes: u64,
+    max_replay_retries: u32,
+    next_seq: u64,
+    replay_failures: std::collections::HashMap<PathBuf, u32>,
+}
+
+/// The dead-letter directory under a WAL directory.
+pub fn dead_letter_dir(wal_dir: &Path) -> PathBuf {
+    wal_dir.join("dead-letter")
+}
+
+/// Move dead-lettered segments back into the WAL (after the underlying
+/// problem — a schema mismatch, bad rows — was fixed); returns how many
+/// segments were redriven.
+pub fn redrive(wal_dir: &Path) -> Result<usize> {
+    let dead = dead_letter_dir(wal_dir);
+    if !dead.exists() {
+        return Ok(0);
+    }
+    let mut redriven = 0;
+    for entry in std::fs::read_dir(&dead)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if name.ends_with(".error") {
+            std::fs::remove_file(&path)?;
+        } else if name.starts_with("wal-") && name.ends_with(".jsonl") {
+            std::fs::rename(&path, wal_dir.join(name))?;
+            redriven += 1;
+        }
+    }
+    Ok(redriven)
+}
+
+impl WalSink {
+    /// Wrap `inner`, spilling into `dir` with the given size cap.
+    pub fn new(inner: Box<dyn Sink>, dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self> {
+        Self::with_retries(inner, dir, max_bytes, default_max_replay_retries())
+    }
+
+    /// Like [`WalSink::new`] with an explicit dead-letter threshold.
+    pub fn with_retries(
+        inner: Box<dyn Sink>,
+        dir: impl Into<PathBuf>,
+        max_bytes: u64,
+        max_replay_retries: u32,
+    ) -> Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+        let next_seq = Self::segments(&dir)?
+            .last()
+            .and_then(|p| Self::seq_of(p))
+            .map_or(0, |s| s + 1);
+        Ok(Self {
+            inner,
+            dir,
+            max_bytes,
+            max_replay_retries,
+            next_seq,
+            replay_failures: std::collections::HashMap::new(),
+        })
+    }
+
+    /// Pending segment files, oldest first.
+    fn segments(dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut segments: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.file_name()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("wal-") && n.ends_with(".jsonl"))
+            })
+            .collect();
+        segments.sort();
+        Ok(segments)
+    }
+
+    fn seq_of(path: &Path) -> Option<u64> {
+        path.file_name()?
+            .to_str()?
+            .strip_prefix("wal-")?
+            .strip_suffix(".jsonl")?
+            .parse()
+            .ok()
+    }
+
+    /// Number of pending segments and their total size.
+    pub fn backlog(&self) -> (usize, u64) {
+        let segments = Self::segments(&self.dir).unwrap_or_default();
+        let bytes = segments
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        (segments.len(), bytes)
+    }
+
+    fn spill(&mut self, events: &[Event]) -> Result<()> {
+        let path = self.dir.join(format!("wal-{:012}.jsonl", self.next_seq));
+        self.next_seq += 1;
+        let mut body = Vec::with_capacity(events.len() * 128);
+        for event in events {
+            serde_json::to_writer(&mut body, event)?;
+            body.push(b'\n');
+        }
+        std::fs::write(&path, body)?;
+        self.enforce_cap()?;
+        Ok(())
+    }
+
+    /// Drop oldest segments until the buffer fits the cap.
+    fn enforce_cap(&self) -> Result<()> {
+        let segments = Self::segments(&self.dir)?;
+        let mut total: u64 = segments
+            .iter()
+            .filter_map(|p| std::fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        for segment in segments {
+            if total <= self.max_bytes {
+                break;
+            }
+            let len = std::fs::metadata(&segment).map(|m| m.len()).unwrap_or(0);
+            tracing::error!(segment = %segment.display(), "WAL over size cap, dropping oldest segment");
+            std::fs::remove_file(&segment)?;
+            total = total.saturating_sub(len);
+        }
+        Ok(())
+    }
+
+    /// Replay pending segments, oldest first, stopping at the first
+    /// failure.
+    async fn replay(&mut self) -> Result<()> {
+        for segment in Self::segments(&self.dir)? {
+            let text = std::fs::read_to_string(&segment)?;
+            let events: Vec<Event> = text
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .filter_map(|l| serde_json::from_str(l).ok())
+                .collect();
+            if !events.is_empty() {
+                if let Err(e) = self.inner.write(&events).await {
+                    let failures = self.replay_failures.entry(segment.clone()).or_insert(0);
+                    *failures += 1;
+                    if *failures >= self.max_replay_retries {
+                        // Persistent failures (schema mismatch, bad
+                        // rows) go to the dead-letter queue with the
+                        // error attached, instead of blocking replay
+                        // forever.
+                        let dead = dead_letter_dir(&self.dir);
+                        std::fs::create_dir_all(&dead)?;
+                        let name = segment.file_name().expect("segment has a name");
+                        let target = dead.join(name);
+                        std::fs::rename(&segment, &target)?;
+                        std::fs::write(target.with_extension("jsonl.error"), format!("{e}\n"))?;
+                        self.replay_failures.remove(&segment);
+                        tracing::error!(
+                            segment = %target.display(),
+                            error = %e,
+                            "segment dead-lettered after repeated failures; fix the cause and run `bzl redrive`"
+                        );
+                        continue;
+                    }
+                    tracing::warn!(error = %e, "WAL replay halted; database still down");
+                    return Ok(());
+                }
+                self.replay_failures.remove(&segment);
+            }
+            std::fs::remove_file(&segment)?;
+            tracing::info!(segment = %segment.display(), events = events.len(), "replayed WAL segment");
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Sink for WalSink {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn write(&mut self, events: &[Event]) -> Result<()> {
+        match self.inner.write(events).await {
+            Ok(()) => {
+                self.replay().await?;
+                Ok(())
+            }
+            Err(e) => {
+                let (segments, bytes) = self.backlog();
+                tracing::warn!(
+                    error = %e,
+                    segments,
+                    bytes,
+                    "sink write failed, spilling batch to WAL"
+                );
+                self.spill(events)
+            }
+        }
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await?;
+        self.replay().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::model::Trade;
+
+    struct FlakySink {
+        down: Arc<AtomicBool>,
+        written: Arc<Mutex<Vec<i64>>>,
+    }
+
+    #[async_trait]
+    impl Sink for FlakySink {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn write(&mut self, events: &[Event]) -> Result<()> {
+            if self.down.load(Ordering::Relaxed) {
+                return Err(crate::error::Error::Database("down".to_string()));
+            }
+            let mut written = self.written.lock().unwrap();
+            for event in events {
+                if let Event::Trade(t) = event {
+                    written.push(t.trade_id);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn trade(id: i64) -> Event {
+        Event::Trade(Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: id,
+            price: 1.0,
+            qty: 1.0,
+            trade_time: id,
+            is_buyer_maker: false,
+        })
+    }
+
+    fn setup(name: &str, max_bytes: u64) -> (WalSink, Arc<AtomicBool>, Arc<Mutex<Vec<i64>>>, PathBuf) {
+        let dir = std::env::temp_dir().join(format!("bzl-wal-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let down = Arc::new(AtomicBool::new(false));
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let sink = WalSink::new(
+            Box::new(FlakySink {
+                down: down.clone(),
+                written: written.clone(),
+            }),
+            &dir,
+            max_bytes,
+        )
+        .unwrap();
+        (sink, down, written, dir)
+    }
+
+    #[tokio::test]
+    async fn spills_while_down_and_replays_in_order() {
+        let (mut sink, down, written, dir) = setup("replay", 1 << 20);
+        sink.write(&[trade(1)]).await.unwrap();
+        down.store(true, Ordering::Relaxed);
+        sink.write(&[trade(2)]).await.unwrap();
+        sink.write(&[trade(3)]).await.unwrap();
+        assert_eq!(sink.backlog().0, 2);
+        assert_eq!(*written.lock().unwrap(), vec![1]);
+        down.store(false, Ordering::Relaxed);
+        sink.write(&[trade(4)]).await.unwrap();
+        assert_eq!(*written.lock().unwrap(), vec![1, 4, 2, 3]);
+        assert_eq!(sink.backlog().0, 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn size_cap_drops_oldest() {
+        let (mut sink, down, _written, dir) = setup("cap", 150);
+        down.store(true, Ordering::Relaxed);
+        for i in 0..5 {
+            sink.write(&[trade(i)]).await.unwrap();
+        }
+        let (segments, bytes) = sink.backlog();
+        assert!(bytes <= 300, "cap should bound the backlog, got {bytes}");
+        assert!(segments < 5);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn resumes_sequence_numbers_across_restarts() {
+        let (mut sink, down, _written, dir) = setup("seq", 1 << 20);
+        down.store(true, Ordering::Relaxed);
+        sink.write(&[trade(1)]).await.unwrap();
+        drop(sink);
+        let reopened = WalSink::new(
+            Box::new(FlakySink {
+                down: down.clone(),
+                written: Arc::new(Mutex::new(Vec::new())),
+            }),
+            &dir,
+            1 << 20,
+        )
+        .unwrap();
+        assert_eq!(reopened.next_seq, 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn repeated_failures_dead_letter_and_redrive_restores() {
+        let (mut sink, down, _written, dir) = setup("dead", 1 << 20);
+        down.store(true, Ordering::Relaxed);
+        sink.max_replay_retries = 2;
+        sink.write(&[trade(1)]).await.unwrap();
+        sink.write(&[trade(2)]).await.unwrap();
+        // Replay runs on every flush; two attempts hit the threshold.
+        let _ = sink.flush().await;
+        let _ = sink.flush().await;
+        let dead = dead_letter_dir(&dir);
+        let dead_segments: Vec<_> = std::fs::read_dir(&dead)
+            .map(|d| d.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+            .unwrap_or_default();
+        assert!(
+            dead_segments.iter().any(|p| p.extension().is_some_and(|e| e == "jsonl")),
+            "expected a dead-lettered segment, got {dead_segments:?}"
+        );
+        assert!(dead_segments.iter().any(|p| p.to_string_lossy().ends_with(".error")));
+        // After fixing the cause, redrive moves segments back.
+        let redriven = redrive(&dir).unwrap();
+        assert!(redriven >= 1);
+        down.store(false, Ordering::Relaxed);
+        sink.write(&[trade(9)]).await.unwrap();
+        assert_eq!(sink.backlog().0, 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Streaming access to stored klines.
+//!
+//! Library consumers should never have to materialize a year of 1m
+//! candles: [`KlineStore::iter_range`] returns an async stream that
+//! reads from ClickHouse (keyset-paged) or the local CSV archive (one
+//! day file at a time) in chunks.
+
+use std::path::PathBuf;
+
+use chrono::{Duration, TimeZone, Utc};
+use futures_util::Stream;
+
+use crate::db::ClickHouse;
+use crate::error::{Error, Result};
+use crate::model::Kline;
+
+/// Rows fetched per ClickHouse round trip.
+const CHUNK_ROWS: usize = 10_000;
+
+/// Where stored klines live.
+pub enum KlineStore {
+    /// The `klines` table of a ClickHouse database.
+    ClickHouse(ClickHouse),
+    /// The daily CSV archive under a data directory.
+    Csv {
+        /// Root of the data directory (as written by the CSV sink).
+        root: PathBuf,
+    },
+}
+
+impl KlineStore {
+    /// Stream candles with `from <= open_time < to`, oldest first,
+    /// without ever holding more than one chunk in memory.
+    pub fn iter_range<'a>(
+        &'a self,
+        symbol: &'a str,
+        interval: &'a str,
+        from: i64,
+        to: i64,
+    ) -> impl Stream<Item = Result<Kline>> + 'a {
+        async_stream::try_stream! {
+            match self {
+                KlineStore::ClickHouse(db) => {
+                    let mut cursor = from;
+                    loop {
+                        let sql = format!(
+                            "SELECT symbol, interval, \
+                             toUnixTimestamp64Milli(open_time) AS open_time, \
+                             toUnixTimestamp64Milli(close_time) AS close_time, \
+                             open, high, low, close, volume, quote_volume, trade_count \
+                             FROM klines \
+                             WHERE symbol = '{symbol}' AND interval = '{interval}' \
+                             AND open_time >= {} AND open_time < {} \
+                             ORDER BY open_time LIMIT {CHUNK_ROWS}",
+                            crate::db::dt64_literal(cursor),
+                            crate::db::dt64_literal(to),
+                        );
+                        let rows: Vec<Kline> = db.query_rows(&sql).await?;
+                        let done = rows.len() < CHUNK_ROWS;
+                        let last = rows.last().map(|k| k.open_time);
+                        for kline in rows {
+                            yield kline;
+                        }
+                        match (done, last) {
+                            (false, Some(last)) => cursor = last + 1,
+                            _ => break,
+                        }
+                    }
+                }
+                KlineStore::Csv { root } => {
+                    // The archive cannot hold future days, so clamp the
+                    // day walk for open-ended ranges (`to = i64::MAX`)
+                    // instead of overflowing chrono.
+                    let horizon = Utc::now().timestamp_millis() + 2 * 86_400_000;
+                    let mut day = Utc
+                        .timestamp_millis_opt(from.clamp(0, horizon))
+                        .single()
+                        .ok_or_else(|| Error::Config(format!("bad range start {from}")))?
+                        .date_naive();
+                    let end_day = Utc
+                        .timestamp_millis_opt(to.max(from).clamp(0, horizon))
+                        .single()
+                        .ok_or_else(|| Error::Config(format!("bad range end {to}")))?
+                        .date_naive();
+                    while day <= end_day {
+                        let path = root
+                            .join(symbol)
+                            .join(format!("klines-{interval}-{}.csv", day.format("%Y-%m-%d")));
+                        if let Ok(text) = std::fs::read_to_string(&path) {
+                            for line in text.lines() {
+                                let kline = parse_csv_row(symbol, interval, line)?;
+                                if kline.open_time >= from && kline.open_time < to {
+                                    yield kline;
+                                }
+                            }
+                        }
+                        day += Duration::days(1);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse one row of the CSV archive
+/// (`open_time,open,high,low,close,volume,close_time,quote_volume,trade_count`).
+pub(crate) fn parse_csv_row(symbol: &str, interval: &str, line: &str) -> Result<Kline> {
+    let fields: Vec<&str> = line.split(',').collect();
+    if fields.len() != 9 {
+        return Err(Error::Config(format!("bad kline CSV row: {line}")));
+    }
+    let num = |i: usize| -> Result<f64> {
+        fields[i]
+            .parse()
+            .map_err(|_| Error::Config(format!("bad field {i} in kline CSV row: {line}")))
+    };
+    let int = |i: usize| -> Result<i64> {
+        fields[i]
+            .parse()
+            .map_err(|_| Error::Config(format!("bad field {i} in kline CSV row: {line}")))
+    };
+    Ok(Kline {
+        symbol: symbol.to_string(),
+        interval: interval.to_string(),
+        open_time: int(0)?,
+        open: num(1)?,
+        high: num(2)?,
+        low: num(3)?,
+        close: num(4)?,
+        volume: num(5)?,
+        close_time: int(6)?,
+        quote_volume: num(7)?,
+        trade_count: int(8)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::StreamExt;
+
+    use super::*;
+
+    fn write_day(root: &std::path::Path, day: &str, rows: &[(i64, f64)]) {
+        let dir = root.join("BTCUSDT");
+        std::fs::create_dir_all(&dir).unwrap();
+        let lines: String = rows
+            .iter()
+            .map(|(t, p)| format!("{t},{p},{p},{p},{p},1,{},10,5\n", t + 59_999))
+            .collect();
+        std::fs::write(dir.join(format!("klines-1m-{day}.csv")), lines).unwrap();
+    }
+
+    #[tokio::test]
+    async fn streams_csv_archive_across_days() {
+        let root =
+            std::env::temp_dir().join(format!("bzl-kline-store-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        // 2023-11-14 and 2023-11-15 UTC.
+        write_day(&root, "2023-11-14", &[(1_699_920_000_000, 100.0), (1_699_920_060_000, 101.0)]);
+        write_day(&root, "2023-11-15", &[(1_700_006_400_000, 102.0)]);
+        let store = KlineStore::Csv { root: root.clone() };
+        let klines: Vec<Kline> = store
+            .iter_range("BTCUSDT", "1m", 1_699_920_000_000, 1_700_100_000_000)
+            .map(|k| k.unwrap())
+            .collect()
+            .await;
+        assert_eq!(klines.len(), 3);
+        assert_eq!(klines[0].open, 100.0);
+        assert_eq!(klines[2].open_time, 1_700_006_400_000);
+        // A sub-range trims both ends.
+        let middle: Vec<Kline> = store
+            .iter_range("BTCUSDT", "1m", 1_699_920_060_000, 1_700_006_400_000)
+            .map(|k| k.unwrap())
+            .collect()
+            .await;
+        assert_eq!(middle.len(), 1);
+        assert_eq!(middle[0].open, 101.0);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn csv_row_round_trips() {
+        let row = "60000,1.5,2.0,1.0,1.8,42.5,119999,76.5,12";
+        let k = parse_csv_row("BTCUSDT", "1m", row).unwrap();
+        assert_eq!(k.open_time, 60_000);
+        assert_eq!(k.high, 2.0);
+        assert_eq!(k.trade_count, 12);
+        assert!(parse_csv_row("BTCUSDT", "1m", "1,2,3").is_err());
+    }
+}
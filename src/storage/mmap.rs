@@ -0,0 +1,196 @@
+//! Memory-mapped tick archive reader.
+//!
+//! For backtests scanning months of ticks, going through `read`
+//! syscalls per frame wastes time and the page cache. This reader maps
+//! the whole data file once and hands out slices of it; only the
+//! frames a query touches are decompressed, lazily, one at a time.
+
+use std::fs::File;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use super::tickfile::{decode_record, index_path, FrameInfo, INDEX_ENTRY_LEN, MAGIC, RECORD_LEN};
+use crate::error::{Error, Result};
+use crate::model::Trade;
+
+/// A tick file mapped into memory.
+pub struct MmapTickReader {
+    mmap: Mmap,
+    symbol: String,
+    frames: Vec<FrameInfo>,
+}
+
+impl MmapTickReader {
+    /// Map a tick file and load its sidecar index.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)?;
+        // Safety: the file is opened read-only; concurrent appends only
+        // grow it past the mapped length, which we never read beyond.
+        let mmap = unsafe { Mmap::map(&file)? };
+        if mmap.len() < 6 || &mmap[..4] != MAGIC {
+            return Err(Error::Config(format!(
+                "{}: not a tick file (bad magic)",
+                path.display()
+            )));
+        }
+        let symbol_len = u16::from_le_bytes(mmap[4..6].try_into().expect("header width")) as usize;
+        let symbol = std::str::from_utf8(&mmap[6..6 + symbol_len])
+            .map_err(|_| Error::Config("bad symbol in tick file".to_string()))?
+            .to_string();
+        let index_bytes = std::fs::read(index_path(path))?;
+        let frames = index_bytes
+            .chunks_exact(INDEX_ENTRY_LEN)
+            .map(|e| FrameInfo {
+                offset: u64::from_le_bytes(e[0..8].try_into().expect("entry width")),
+                min_time: i64::from_le_bytes(e[8..16].try_into().expect("entry width")),
+                max_time: i64::from_le_bytes(e[16..24].try_into().expect("entry width")),
+                count: u32::from_le_bytes(e[24..28].try_into().expect("entry width")),
+            })
+            .collect();
+        Ok(Self {
+            mmap,
+            symbol,
+            frames,
+        })
+    }
+
+    /// The symbol this file stores.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Index entries, in file order.
+    pub fn frames(&self) -> &[FrameInfo] {
+        &self.frames
+    }
+
+    /// The raw compressed bytes of one frame — a zero-copy slice of
+    /// the mapping.
+    pub fn frame_bytes(&self, frame: &FrameInfo) -> Result<&[u8]> {
+        let start = frame.offset as usize;
+        let header = self
+            .mmap
+            .get(start..start + 8)
+            .ok_or_else(|| Error::Config("frame offset beyond mapping".to_string()))?;
+        let compressed_len =
+            u32::from_le_bytes(header[0..4].try_into().expect("header width")) as usize;
+        self.mmap
+            .get(start + 8..start + 8 + compressed_len)
+            .ok_or_else(|| Error::Config("frame extends beyond mapping".to_string()))
+    }
+
+    /// Decompress one frame into trades.
+    pub fn frame_trades(&self, frame: &FrameInfo) -> Result<Vec<Trade>> {
+        let compressed = self.frame_bytes(frame)?;
+        let raw = zstd::bulk::decompress(compressed, frame.count as usize * RECORD_LEN)
+            .map_err(|e| Error::Config(format!("zstd decompression failed: {e}")))?;
+        Ok(raw
+            .chunks_exact(RECORD_LEN)
+            .map(|r| decode_record(&self.symbol, r))
+            .collect())
+    }
+
+    /// Iterate trades with `from <= trade_time < to`, decompressing one
+    /// overlapping frame at a time so a year-long scan never holds more
+    /// than one frame's trades in memory.
+    pub fn iter_range(&self, from: i64, to: i64) -> RangeIter<'_> {
+        RangeIter {
+            reader: self,
+            from,
+            to,
+            frame_idx: 0,
+            current: Vec::new(),
+            current_pos: 0,
+        }
+    }
+}
+
+/// Lazy iterator over a time range; see [`MmapTickReader::iter_range`].
+pub struct RangeIter<'a> {
+    reader: &'a MmapTickReader,
+    from: i64,
+    to: i64,
+    frame_idx: usize,
+    current: Vec<Trade>,
+    current_pos: usize,
+}
+
+impl Iterator for RangeIter<'_> {
+    type Item = Result<Trade>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            while self.current_pos < self.current.len() {
+                let trade = self.current[self.current_pos].clone();
+                self.current_pos += 1;
+                if trade.trade_time >= self.from && trade.trade_time < self.to {
+                    return Some(Ok(trade));
+                }
+            }
+            let frame = loop {
+                let frame = self.reader.frames.get(self.frame_idx)?;
+                self.frame_idx += 1;
+                if frame.min_time < self.to && frame.max_time >= self.from {
+                    break *frame;
+                }
+            };
+            match self.reader.frame_trades(&frame) {
+                Ok(trades) => {
+                    self.current = trades;
+                    self.current_pos = 0;
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::tickfile::TickFileWriter;
+
+    fn trade(id: i64, time: i64) -> Trade {
+        Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: id,
+            price: 100.0,
+            qty: 1.0,
+            trade_time: time,
+            is_buyer_maker: false,
+        }
+    }
+
+    #[test]
+    fn mmap_reader_matches_file_reader() {
+        let path = std::env::temp_dir().join(format!("bzl-mmap-{}.bzt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(index_path(&path));
+        let mut w = TickFileWriter::open(&path, "BTCUSDT").unwrap();
+        w.write_batch(&[trade(1, 1_000), trade(2, 2_000)]).unwrap();
+        w.write_batch(&[trade(3, 10_000), trade(4, 11_000)]).unwrap();
+        w.flush().unwrap();
+
+        let r = MmapTickReader::open(&path).unwrap();
+        assert_eq!(r.symbol(), "BTCUSDT");
+        assert_eq!(r.frames().len(), 2);
+        let all: Vec<Trade> = r.iter_range(0, i64::MAX).map(|t| t.unwrap()).collect();
+        assert_eq!(all.len(), 4);
+        // Range touching only the second frame skips the first.
+        let tail: Vec<Trade> = r.iter_range(10_500, 20_000).map(|t| t.unwrap()).collect();
+        assert_eq!(tail.len(), 1);
+        assert_eq!(tail[0].trade_id, 4);
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(index_path(&path)).unwrap();
+    }
+
+    #[test]
+    fn rejects_non_tick_files() {
+        let path = std::env::temp_dir().join(format!("bzl-mmap-bad-{}.bzt", std::process::id()));
+        std::fs::write(&path, b"not a tick file").unwrap();
+        assert!(MmapTickReader::open(&path).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
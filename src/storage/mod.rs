@@ -0,0 +1,5 @@
+//! Local archive storage beyond plain CSV.
+
+pub mod kline_store;
+pub mod mmap;
+pub mod tickfile;
@@ -0,0 +1,318 @@
+//! Append-only binary tick files (`.bzt`).
+//!
+//! CSV is convenient but ruinous at billions of trades. A tick file
+//! stores one symbol's trades as zstd-compressed frames of fixed-width
+//! records, with a sidecar index (`.bzi`) mapping each frame's time
+//! span to its byte offset so readers only decompress what a query
+//! touches.
+//!
+//! Data file layout:
+//!
+//! ```text
+//! magic "BZT1" | u16 symbol len | symbol bytes | frames...
+//! frame: u32 compressed len | u32 record count | compressed records
+//! record (33 bytes): i64 trade_id | f64 price | f64 qty | i64 time | u8 flags
+//! ```
+//!
+//! Index file layout: one 28-byte entry per frame,
+//! `u64 offset | i64 min_time | i64 max_time | u32 count`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::model::Trade;
+
+pub(crate) const MAGIC: &[u8; 4] = b"BZT1";
+pub(crate) const RECORD_LEN: usize = 33;
+pub(crate) const INDEX_ENTRY_LEN: usize = 28;
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// One frame's entry in the sidecar index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameInfo {
+    /// Byte offset of the frame header in the data file.
+    pub offset: u64,
+    /// Earliest trade time in the frame (epoch ms).
+    pub min_time: i64,
+    /// Latest trade time in the frame (epoch ms).
+    pub max_time: i64,
+    /// Number of records in the frame.
+    pub count: u32,
+}
+
+pub(crate) fn index_path(path: &Path) -> PathBuf {
+    path.with_extension("bzi")
+}
+
+fn encode_record(buf: &mut Vec<u8>, t: &Trade) {
+    buf.extend_from_slice(&t.trade_id.to_le_bytes());
+    buf.extend_from_slice(&t.price.to_le_bytes());
+    buf.extend_from_slice(&t.qty.to_le_bytes());
+    buf.extend_from_slice(&t.trade_time.to_le_bytes());
+    buf.push(t.is_buyer_maker as u8);
+}
+
+pub(crate) fn decode_record(symbol: &str, bytes: &[u8]) -> Trade {
+    let i64_at = |o: usize| i64::from_le_bytes(bytes[o..o + 8].try_into().expect("record width"));
+    let f64_at = |o: usize| f64::from_le_bytes(bytes[o..o + 8].try_into().expect("record width"));
+    Trade {
+        symbol: symbol.to_string(),
+        trade_id: i64_at(0),
+        price: f64_at(8),
+        qty: f64_at(16),
+        trade_time: i64_at(24),
+        is_buyer_maker: bytes[32] != 0,
+    }
+}
+
+/// Appends trades to a tick file, one compressed frame per batch.
+pub struct TickFileWriter {
+    data: File,
+    index: File,
+    symbol: String,
+}
+
+impl TickFileWriter {
+    /// Open (or create) the tick file for `symbol` at `path`.
+    pub fn open(path: impl AsRef<Path>, symbol: &str) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut data = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        let index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(index_path(path))?;
+        if data.metadata()?.len() == 0 {
+            data.write_all(MAGIC)?;
+            data.write_all(&(symbol.len() as u16).to_le_bytes())?;
+            data.write_all(symbol.as_bytes())?;
+        } else {
+            let existing = read_header(&mut data)?;
+            if existing != symbol {
+                return Err(Error::Config(format!(
+                    "tick file {} holds `{existing}`, not `{symbol}`",
+                    path.display()
+                )));
+            }
+        }
+        Ok(Self {
+            data,
+            index,
+            symbol: symbol.to_string(),
+        })
+    }
+
+    /// Append one batch of trades as a single compressed frame.
+    /// Trades should already be in time order.
+    pub fn write_batch(&mut self, trades: &[Trade]) -> Result<()> {
+        if trades.is_empty() {
+            return Ok(());
+        }
+        let mut raw = Vec::with_capacity(trades.len() * RECORD_LEN);
+        let mut min_time = i64::MAX;
+        let mut max_time = i64::MIN;
+        for t in trades {
+            encode_record(&mut raw, t);
+            min_time = min_time.min(t.trade_time);
+            max_time = max_time.max(t.trade_time);
+        }
+        let compressed = zstd::bulk::compress(&raw, COMPRESSION_LEVEL)
+            .map_err(|e| Error::Config(format!("zstd compression failed: {e}")))?;
+        let offset = self.data.seek(SeekFrom::End(0))?;
+        self.data.write_all(&(compressed.len() as u32).to_le_bytes())?;
+        self.data.write_all(&(trades.len() as u32).to_le_bytes())?;
+        self.data.write_all(&compressed)?;
+        let mut entry = Vec::with_capacity(INDEX_ENTRY_LEN);
+        entry.extend_from_slice(&offset.to_le_bytes());
+        entry.extend_from_slice(&min_time.to_le_bytes());
+        entry.extend_from_slice(&max_time.to_le_bytes());
+        entry.extend_from_slice(&(trades.len() as u32).to_le_bytes());
+        self.index.write_all(&entry)?;
+        Ok(())
+    }
+
+    /// Flush both files to the OS.
+    pub fn flush(&mut self) -> Result<()> {
+        self.data.flush()?;
+        self.index.flush()?;
+        Ok(())
+    }
+
+    /// The symbol this file stores.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+}
+
+fn read_header(file: &mut File) -> Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(Error::Config("not a tick file (bad magic)".to_string()));
+    }
+    let mut len = [0u8; 2];
+    file.read_exact(&mut len)?;
+    let mut symbol = vec![0u8; u16::from_le_bytes(len) as usize];
+    file.read_exact(&mut symbol)?;
+    String::from_utf8(symbol).map_err(|_| Error::Config("bad symbol in tick file".to_string()))
+}
+
+/// Reads trades back out of a tick file by time range.
+pub struct TickFileReader {
+    data: File,
+    symbol: String,
+    frames: Vec<FrameInfo>,
+}
+
+impl TickFileReader {
+    /// Open a tick file and load its index.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let mut data = File::open(path)?;
+        let symbol = read_header(&mut data)?;
+        let index_bytes = std::fs::read(index_path(path))?;
+        let frames = index_bytes
+            .chunks_exact(INDEX_ENTRY_LEN)
+            .map(|e| FrameInfo {
+                offset: u64::from_le_bytes(e[0..8].try_into().expect("entry width")),
+                min_time: i64::from_le_bytes(e[8..16].try_into().expect("entry width")),
+                max_time: i64::from_le_bytes(e[16..24].try_into().expect("entry width")),
+                count: u32::from_le_bytes(e[24..28].try_into().expect("entry width")),
+            })
+            .collect();
+        Ok(Self {
+            data,
+            symbol,
+            frames,
+        })
+    }
+
+    /// The symbol this file stores.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// Index entries, in file order.
+    pub fn frames(&self) -> &[FrameInfo] {
+        &self.frames
+    }
+
+    /// Read all trades with `from <= trade_time < to`, decompressing
+    /// only the frames whose spans overlap.
+    pub fn read_range(&mut self, from: i64, to: i64) -> Result<Vec<Trade>> {
+        let mut out = Vec::new();
+        let overlapping: Vec<FrameInfo> = self
+            .frames
+            .iter()
+            .copied()
+            .filter(|f| f.min_time < to && f.max_time >= from)
+            .collect();
+        for frame in overlapping {
+            self.data.seek(SeekFrom::Start(frame.offset))?;
+            let mut header = [0u8; 8];
+            self.data.read_exact(&mut header)?;
+            let compressed_len =
+                u32::from_le_bytes(header[0..4].try_into().expect("header width")) as usize;
+            let count = u32::from_le_bytes(header[4..8].try_into().expect("header width")) as usize;
+            let mut compressed = vec![0u8; compressed_len];
+            self.data.read_exact(&mut compressed)?;
+            let raw = zstd::bulk::decompress(&compressed, count * RECORD_LEN)
+                .map_err(|e| Error::Config(format!("zstd decompression failed: {e}")))?;
+            for record in raw.chunks_exact(RECORD_LEN) {
+                let trade = decode_record(&self.symbol, record);
+                if trade.trade_time >= from && trade.trade_time < to {
+                    out.push(trade);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(id: i64, time: i64) -> Trade {
+        Trade {
+            symbol: "BTCUSDT".into(),
+            trade_id: id,
+            price: 50_000.0 + id as f64,
+            qty: 0.5,
+            trade_time: time,
+            is_buyer_maker: id % 2 == 0,
+        }
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("bzl-tick-{}-{name}.bzt", std::process::id()))
+    }
+
+    #[test]
+    fn round_trips_batches() {
+        let path = temp_path("roundtrip");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(index_path(&path));
+        let mut w = TickFileWriter::open(&path, "BTCUSDT").unwrap();
+        w.write_batch(&[trade(1, 1_000), trade(2, 2_000)]).unwrap();
+        w.write_batch(&[trade(3, 3_000), trade(4, 4_000)]).unwrap();
+        w.flush().unwrap();
+        let mut r = TickFileReader::open(&path).unwrap();
+        assert_eq!(r.symbol(), "BTCUSDT");
+        assert_eq!(r.frames().len(), 2);
+        let all = r.read_range(0, i64::MAX).unwrap();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all[0], trade(1, 1_000));
+        assert_eq!(all[3], trade(4, 4_000));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(index_path(&path)).unwrap();
+    }
+
+    #[test]
+    fn range_reads_skip_frames() {
+        let path = temp_path("range");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(index_path(&path));
+        let mut w = TickFileWriter::open(&path, "BTCUSDT").unwrap();
+        w.write_batch(&[trade(1, 1_000), trade(2, 2_000)]).unwrap();
+        w.write_batch(&[trade(3, 10_000), trade(4, 11_000)]).unwrap();
+        w.flush().unwrap();
+        let mut r = TickFileReader::open(&path).unwrap();
+        let hit = r.read_range(1_500, 2_500).unwrap();
+        assert_eq!(hit.len(), 1);
+        assert_eq!(hit[0].trade_id, 2);
+        assert!(r.read_range(20_000, 30_000).unwrap().is_empty());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(index_path(&path)).unwrap();
+    }
+
+    #[test]
+    fn appending_resumes_existing_file() {
+        let path = temp_path("append");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(index_path(&path));
+        {
+            let mut w = TickFileWriter::open(&path, "BTCUSDT").unwrap();
+            w.write_batch(&[trade(1, 1_000)]).unwrap();
+        }
+        {
+            let mut w = TickFileWriter::open(&path, "BTCUSDT").unwrap();
+            w.write_batch(&[trade(2, 2_000)]).unwrap();
+        }
+        let mut r = TickFileReader::open(&path).unwrap();
+        assert_eq!(r.read_range(0, i64::MAX).unwrap().len(), 2);
+        // Reopening under another symbol must refuse.
+        assert!(TickFileWriter::open(&path, "ETHUSDT").is_err());
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(index_path(&path)).unwrap();
+    }
+}
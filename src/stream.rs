@@ -0,0 +1,127 @@
+//! Async Stream API for live market data.
+//!
+//! Rust consumers get `impl Stream` over live events without
+//! re-implementing the tungstenite plumbing — connection, subscribe,
+//! reconnect with backoff are handled internally:
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! # async fn demo() {
+//! let exchange = bzl_database::exchange::binance::Binance::default();
+//! let mut trades = Box::pin(bzl_database::stream::trade_stream(
+//!     exchange,
+//!     vec!["BTCUSDT".into()],
+//! ));
+//! while let Some(trade) = trades.next().await {
+//!     println!("{} @ {}", trade.symbol, trade.price);
+//! }
+//! # }
+//! ```
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::exchange::binance::Binance;
+use crate::exchange::Exchange;
+use crate::model::{Interval, Kline, Trade};
+use crate::pipeline::spec::StreamSource;
+use crate::pipeline::Event;
+
+const INITIAL_BACKOFF_MS: u64 = 500;
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// All events for the given sources, reconnecting forever.
+pub fn event_stream(
+    exchange: Binance,
+    sources: Vec<StreamSource>,
+) -> impl Stream<Item = Event> {
+    async_stream::stream! {
+        let mut backoff = INITIAL_BACKOFF_MS;
+        loop {
+            let url = exchange.ws_url();
+            let ws = match tokio_tungstenite::connect_async(&url).await {
+                Ok((ws, _)) => ws,
+                Err(e) => {
+                    tracing::warn!(%url, error = %e, backoff_ms = backoff, "stream connect failed");
+                    tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF_MS);
+                    exchange.next_ws_url();
+                    continue;
+                }
+            };
+            backoff = INITIAL_BACKOFF_MS;
+            let (mut write, mut read) = ws.split();
+            let mut subscribed = true;
+            for payload in exchange.ws_subscribe(&sources) {
+                if write.send(Message::Text(payload)).await.is_err() {
+                    subscribed = false;
+                    break;
+                }
+            }
+            if !subscribed {
+                continue;
+            }
+            tracing::debug!(%url, "stream connected");
+            while let Some(frame) = read.next().await {
+                match frame {
+                    Ok(Message::Text(text)) => {
+                        match exchange.parse_ws_message(&text) {
+                            Ok(events) => {
+                                for event in events {
+                                    yield event;
+                                }
+                            }
+                            Err(e) => tracing::warn!(error = %e, "unparseable frame"),
+                        }
+                    }
+                    Ok(Message::Ping(payload)) => {
+                        if write.send(Message::Pong(payload)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Ok(Message::Close(_)) | Err(_) => break,
+                    Ok(_) => {}
+                }
+            }
+            tracing::warn!(%url, "stream disconnected; reconnecting");
+        }
+    }
+}
+
+/// Live trades for the given symbols.
+pub fn trade_stream(exchange: Binance, symbols: Vec<String>) -> impl Stream<Item = Trade> {
+    let sources = symbols
+        .into_iter()
+        .map(|s| StreamSource {
+            symbol: s.to_lowercase(),
+            stream: "trade".to_string(),
+        })
+        .collect();
+    event_stream(exchange, sources).filter_map(|event| async move {
+        match event {
+            Event::Trade(t) => Some(t),
+            _ => None,
+        }
+    })
+}
+
+/// Live closed candles for the given symbols and interval.
+pub fn kline_stream(
+    exchange: Binance,
+    symbols: Vec<String>,
+    interval: Interval,
+) -> impl Stream<Item = Kline> {
+    let sources = symbols
+        .into_iter()
+        .map(|s| StreamSource {
+            symbol: s.to_lowercase(),
+            stream: format!("kline_{interval}"),
+        })
+        .collect();
+    event_stream(exchange, sources).filter_map(|event| async move {
+        match event {
+            Event::Kline(k) => Some(k),
+            _ => None,
+        }
+    })
+}
@@ -0,0 +1,64 @@
+//! Persisted subscription set.
+//!
+//! Pipelines added at runtime (auto-onboarded listings, `add-symbol`)
+//! only exist in memory; a restart used to fall back to the static
+//! config. The collector now persists those dynamic pipeline
+//! definitions to `<data_dir>/subscriptions.json` and restores them on
+//! startup, so it resubscribes to exactly what it was collecting.
+
+use std::path::PathBuf;
+
+use crate::error::Result;
+
+/// The on-disk dynamic subscription store.
+pub struct SubscriptionSet {
+    path: PathBuf,
+}
+
+impl SubscriptionSet {
+    /// Store under the given data directory.
+    pub fn new(data_dir: &str) -> Self {
+        Self {
+            path: PathBuf::from(data_dir).join("subscriptions.json"),
+        }
+    }
+
+    /// Load persisted pipeline definitions (empty when none).
+    pub fn load(&self) -> Vec<String> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the definitions atomically.
+    pub fn save(&self, defs: &[String]) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(defs)?)?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_tolerates_absence() {
+        let dir = std::env::temp_dir().join(format!("bzl-subs-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = SubscriptionSet::new(dir.to_str().unwrap());
+        assert!(store.load().is_empty());
+        store
+            .save(&["newusdt@trade -> console".to_string()])
+            .unwrap();
+        assert_eq!(store.load(), vec!["newusdt@trade -> console".to_string()]);
+        store.save(&[]).unwrap();
+        assert!(store.load().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
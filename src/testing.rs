@@ -0,0 +1,160 @@
+//! Test support: a local mock exchange.
+//!
+//! Spins up an HTTP server serving canned REST fixtures and a
+//! WebSocket endpoint that records subscriptions and lets tests push
+//! frames — so pagination, retries and reconnection logic can be
+//! exercised end-to-end without the real API. The collectors accept
+//! arbitrary base URLs via `[binance] rest_urls`/`ws_urls`, so a test
+//! only needs to point them here.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::error::{Error, Result};
+
+type Fixtures = Arc<Mutex<HashMap<String, String>>>;
+type WsClients = Arc<tokio::sync::Mutex<Vec<futures_util::stream::SplitSink<
+    tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>,
+    Message,
+>>>>;
+
+/// A running mock exchange; dropped servers keep running until the
+/// runtime shuts down, so tests should reuse one per case.
+pub struct MockExchange {
+    /// Base URL for REST fixtures, e.g. `http://127.0.0.1:PORT`.
+    pub rest_url: String,
+    /// WebSocket URL, e.g. `ws://127.0.0.1:PORT`.
+    pub ws_url: String,
+    fixtures: Fixtures,
+    clients: WsClients,
+    subscriptions: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockExchange {
+    /// Bind both servers on ephemeral ports and start serving.
+    pub async fn start() -> Result<Self> {
+        let fixtures: Fixtures = Arc::new(Mutex::new(HashMap::new()));
+        let clients: WsClients = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+
+        let http_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::Config(format!("mock http bind: {e}")))?;
+        let rest_url = format!("http://{}", http_listener.local_addr()?);
+        let fixtures_for_http = fixtures.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = http_listener.accept().await {
+                let fixtures = fixtures_for_http.clone();
+                tokio::spawn(async move {
+                    let _ = serve_http(stream, fixtures).await;
+                });
+            }
+        });
+
+        let ws_listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| Error::Config(format!("mock ws bind: {e}")))?;
+        let ws_url = format!("ws://{}", ws_listener.local_addr()?);
+        let clients_for_ws = clients.clone();
+        let subs_for_ws = subscriptions.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = ws_listener.accept().await {
+                let clients = clients_for_ws.clone();
+                let subs = subs_for_ws.clone();
+                tokio::spawn(async move {
+                    let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    let (sink, mut read) = ws.split();
+                    clients.lock().await.push(sink);
+                    while let Some(Ok(msg)) = read.next().await {
+                        if let Message::Text(text) = msg {
+                            subs.lock().unwrap().push(text.to_string());
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            rest_url,
+            ws_url,
+            fixtures,
+            clients,
+            subscriptions,
+        })
+    }
+
+    /// Register (or replace) the body served for a REST path
+    /// (query strings are ignored when matching).
+    pub fn set_response(&self, path: &str, body: serde_json::Value) {
+        self.fixtures
+            .lock()
+            .unwrap()
+            .insert(path.to_string(), body.to_string());
+    }
+
+    /// Text frames received on the WebSocket (subscribe payloads).
+    pub fn received(&self) -> Vec<String> {
+        self.subscriptions.lock().unwrap().clone()
+    }
+
+    /// Push one text frame to every connected WebSocket client.
+    pub async fn push_ws(&self, frame: &str) {
+        let mut clients = self.clients.lock().await;
+        for client in clients.iter_mut() {
+            let _ = client.send(Message::Text(frame.to_string())).await;
+        }
+    }
+
+    /// Number of currently connected WebSocket clients.
+    pub async fn ws_clients(&self) -> usize {
+        self.clients.lock().await.len()
+    }
+}
+
+async fn serve_http(mut stream: tokio::net::TcpStream, fixtures: Fixtures) -> Result<()> {
+    let mut buf = vec![0u8; 8192];
+    let mut read = 0;
+    loop {
+        let n = stream.read(&mut buf[read..]).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        read += n;
+        if buf[..read].windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+        if read == buf.len() {
+            break;
+        }
+    }
+    let request = String::from_utf8_lossy(&buf[..read]);
+    let path = request
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or("/")
+        .split('?')
+        .next()
+        .unwrap_or("/")
+        .to_string();
+    let body = fixtures.lock().unwrap().get(&path).cloned();
+    let response = match body {
+        Some(body) => format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        ),
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
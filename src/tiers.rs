@@ -0,0 +1,120 @@
+//! Per-symbol priority tiers.
+//!
+//! Config assigns symbols to tiers (`hot`, `cold`, ...) that control
+//! how much of the limited budget they get: request pacing for REST
+//! polling, batch latency targets, and which sinks their data reaches:
+//!
+//! ```text
+//! [[tier]]
+//! name = "hot"
+//! symbols = ["BTCUSDT", "ETHUSDT"]
+//! page_delay_ms = 100
+//! [tier.batch]
+//! max_events = 100
+//! max_delay_ms = 250
+//!
+//! [[tier]]
+//! name = "cold"
+//! symbols = ["*"]
+//! page_delay_ms = 1000
+//! sinks = ["csv"]
+//! ```
+//!
+//! `"*"` matches any symbol not claimed by an earlier tier.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sink::batch::BatchSettings;
+
+/// One `[[tier]]` entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TierConfig {
+    /// Tier name used in logs.
+    pub name: String,
+    /// Symbols in the tier; `"*"` is a catch-all.
+    pub symbols: Vec<String>,
+    /// Delay between REST pages for these symbols.
+    #[serde(default)]
+    pub page_delay_ms: Option<u64>,
+    /// Batch bounds for these symbols' sinks.
+    #[serde(default)]
+    pub batch: Option<BatchSettings>,
+    /// Restrict which sinks these symbols reach.
+    #[serde(default)]
+    pub sinks: Option<Vec<String>>,
+}
+
+/// Resolved symbol-to-tier lookup.
+#[derive(Debug, Clone, Default)]
+pub struct TierMap {
+    tiers: Vec<TierConfig>,
+    by_symbol: HashMap<String, usize>,
+    catch_all: Option<usize>,
+}
+
+impl TierMap {
+    /// Build the lookup; earlier tiers win on overlap and the first
+    /// `"*"` becomes the catch-all.
+    pub fn new(tiers: Vec<TierConfig>) -> Self {
+        let mut by_symbol = HashMap::new();
+        let mut catch_all = None;
+        for (idx, tier) in tiers.iter().enumerate() {
+            for symbol in &tier.symbols {
+                if symbol == "*" {
+                    catch_all.get_or_insert(idx);
+                } else {
+                    by_symbol.entry(symbol.to_uppercase()).or_insert(idx);
+                }
+            }
+        }
+        Self {
+            tiers,
+            by_symbol,
+            catch_all,
+        }
+    }
+
+    /// The tier for a symbol, if any.
+    pub fn get(&self, symbol: &str) -> Option<&TierConfig> {
+        self.by_symbol
+            .get(&symbol.to_uppercase())
+            .copied()
+            .or(self.catch_all)
+            .map(|idx| &self.tiers[idx])
+    }
+
+    /// Whether any tiers are configured.
+    pub fn is_empty(&self) -> bool {
+        self.tiers.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tier(name: &str, symbols: &[&str]) -> TierConfig {
+        TierConfig {
+            name: name.to_string(),
+            symbols: symbols.iter().map(|s| s.to_string()).collect(),
+            page_delay_ms: None,
+            batch: None,
+            sinks: None,
+        }
+    }
+
+    #[test]
+    fn lookup_prefers_explicit_then_catch_all() {
+        let map = TierMap::new(vec![
+            tier("hot", &["BTCUSDT", "ETHUSDT"]),
+            tier("cold", &["*"]),
+        ]);
+        assert_eq!(map.get("btcusdt").unwrap().name, "hot");
+        assert_eq!(map.get("DOGEUSDT").unwrap().name, "cold");
+        let no_catch_all = TierMap::new(vec![tier("hot", &["BTCUSDT"])]);
+        assert!(no_catch_all.get("DOGEUSDT").is_none());
+        assert!(TierMap::default().is_empty());
+    }
+}
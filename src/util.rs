@@ -0,0 +1,211 @@
+//! Small shared helpers for the binaries.
+
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// The `[output]` config section: how timestamps are rendered in
+/// console and file output.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Display timezone: `UTC` or a fixed offset like `+08:00`.
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Render ISO-8601 timestamps instead of raw epoch milliseconds.
+    #[serde(default)]
+    pub iso_timestamps: bool,
+    /// Aggregation window for the `ticker` sink, e.g. `1s` or `5s`.
+    #[serde(default)]
+    pub ticker_window: Option<String>,
+    /// Colorize the `ticker` sink by direction, with a price
+    /// sparkline per symbol.
+    #[serde(default)]
+    pub color: bool,
+    /// Language for console and log messages.
+    #[serde(default)]
+    pub lang: crate::i18n::Lang,
+}
+
+fn default_timezone() -> String {
+    "UTC".to_string()
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            timezone: default_timezone(),
+            iso_timestamps: false,
+            ticker_window: None,
+            color: false,
+            lang: crate::i18n::Lang::default(),
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Resolve the section into a [`TimeStyle`].
+    pub fn time_style(&self) -> Result<TimeStyle> {
+        if !self.iso_timestamps {
+            return Ok(TimeStyle::Millis);
+        }
+        Ok(TimeStyle::Iso(parse_offset(&self.timezone)?))
+    }
+}
+
+/// How a timestamp is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeStyle {
+    /// Raw epoch milliseconds (the historical format).
+    #[default]
+    Millis,
+    /// ISO-8601 in the given fixed offset.
+    Iso(FixedOffset),
+}
+
+/// Parse `UTC` or a `±HH:MM` offset.
+pub fn parse_offset(tz: &str) -> Result<FixedOffset> {
+    if tz.eq_ignore_ascii_case("utc") || tz == "Z" {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset"));
+    }
+    let bad = || Error::Config(format!("cannot parse timezone `{tz}` (use UTC or ±HH:MM)"));
+    let (sign, rest) = match tz.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(bad()),
+    };
+    let (hours, minutes) = rest.split_once(':').ok_or_else(bad)?;
+    let hours: i32 = hours.parse().map_err(|_| bad())?;
+    let minutes: i32 = minutes.parse().map_err(|_| bad())?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(bad)
+}
+
+/// Render a timestamp according to the chosen style.
+pub fn format_ms(ms: i64, style: TimeStyle) -> String {
+    match style {
+        TimeStyle::Millis => ms.to_string(),
+        TimeStyle::Iso(offset) => Utc
+            .timestamp_millis_opt(ms)
+            .single()
+            .map(|t| {
+                t.with_timezone(&offset)
+                    .format("%Y-%m-%dT%H:%M:%S%.3f%:z")
+                    .to_string()
+            })
+            .unwrap_or_else(|| ms.to_string()),
+    }
+}
+
+/// Parse a user-supplied date, datetime or relative time into epoch
+/// milliseconds.
+///
+/// Accepts `YYYY-MM-DD`, `YYYY-MM` (first of the month),
+/// `YYYY-MM-DD HH:MM:SS` (all UTC), unix timestamps in seconds or
+/// milliseconds, relative ages like `7d`/`24h`/`30m`, and
+/// `now`/`now-30m`/`now+1h`.
+pub fn parse_date(input: &str) -> Result<i64> {
+    parse_date_at(input, Utc::now().timestamp_millis())
+}
+
+/// [`parse_date`] with an explicit `now` anchor for relative forms.
+pub fn parse_date_at(input: &str, now_ms: i64) -> Result<i64> {
+    let input = input.trim();
+    if let Ok(v) = input.parse::<i64>() {
+        // Ten-digit values are unix seconds; longer ones milliseconds.
+        return Ok(if v.abs() < 100_000_000_000 { v * 1000 } else { v });
+    }
+    if let Some(rest) = input.strip_prefix("now") {
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Ok(now_ms);
+        }
+        let (sign, dur) = match rest.split_at_checked(1) {
+            Some(("-", dur)) => (-1, dur),
+            Some(("+", dur)) => (1, dur),
+            _ => return Err(Error::Config(format!("cannot parse date `{input}`"))),
+        };
+        let ms = parse_duration_ms(dur.trim())
+            .ok_or_else(|| Error::Config(format!("cannot parse date `{input}`")))?;
+        return Ok(now_ms + sign * ms);
+    }
+    // A bare duration means "that long ago".
+    if let Some(ms) = parse_duration_ms(input) {
+        return Ok(now_ms - ms);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        let dt = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        return Ok(Utc.from_utc_datetime(&dt).timestamp_millis());
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&format!("{input}-01"), "%Y-%m-%d") {
+        let dt = date.and_hms_opt(0, 0, 0).expect("midnight is valid");
+        return Ok(Utc.from_utc_datetime(&dt).timestamp_millis());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S") {
+        return Ok(Utc.from_utc_datetime(&dt).timestamp_millis());
+    }
+    Err(Error::Config(format!("cannot parse date `{input}`")))
+}
+
+/// Parse `<n><s|m|h|d|w>` into milliseconds.
+fn parse_duration_ms(input: &str) -> Option<i64> {
+    let (value, unit) = input.split_at_checked(input.len().checked_sub(1)?)?;
+    let value: i64 = value.parse().ok()?;
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 7 * 86_400_000,
+        _ => return None,
+    };
+    value.checked_mul(unit_ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_parse_and_format() {
+        let style = TimeStyle::Iso(parse_offset("+08:00").unwrap());
+        assert_eq!(format_ms(0, style), "1970-01-01T08:00:00.000+08:00");
+        assert_eq!(format_ms(0, TimeStyle::Millis), "0");
+        assert_eq!(
+            format_ms(0, TimeStyle::Iso(parse_offset("UTC").unwrap())),
+            "1970-01-01T00:00:00.000+00:00"
+        );
+        assert!(parse_offset("Asia/Shanghai").is_err());
+        let cfg = OutputConfig {
+            timezone: "-05:30".into(),
+            iso_timestamps: true,
+            ticker_window: None,
+            color: false,
+            lang: crate::i18n::Lang::default(),
+        };
+        assert!(matches!(cfg.time_style().unwrap(), TimeStyle::Iso(_)));
+    }
+
+    #[test]
+    fn parses_supported_forms() {
+        assert_eq!(parse_date("1970-01-02").unwrap(), 86_400_000);
+        assert_eq!(parse_date("1970-01-01 01:00:00").unwrap(), 3_600_000);
+        assert_eq!(parse_date("1700000000000").unwrap(), 1_700_000_000_000);
+        assert!(parse_date("not-a-date").is_err());
+    }
+
+    #[test]
+    fn parses_relative_and_month_forms() {
+        const NOW: i64 = 1_700_000_000_000;
+        assert_eq!(parse_date_at("now", NOW).unwrap(), NOW);
+        assert_eq!(parse_date_at("now-30m", NOW).unwrap(), NOW - 1_800_000);
+        assert_eq!(parse_date_at("now+1h", NOW).unwrap(), NOW + 3_600_000);
+        assert_eq!(parse_date_at("7d", NOW).unwrap(), NOW - 7 * 86_400_000);
+        assert_eq!(parse_date_at("24h", NOW).unwrap(), NOW - 86_400_000);
+        // Unix seconds vs milliseconds.
+        assert_eq!(parse_date_at("1700000000", NOW).unwrap(), 1_700_000_000_000);
+        // First of the month.
+        assert_eq!(parse_date_at("1970-02", NOW).unwrap(), 31 * 86_400_000);
+        assert!(parse_date_at("now*3", NOW).is_err());
+        assert!(parse_date_at("7y", NOW).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+//! Stream API tests against the mock exchange.
+
+use bzl_database::exchange::binance::Binance;
+use bzl_database::stream::trade_stream;
+use bzl_database::testing::MockExchange;
+use futures_util::StreamExt;
+
+#[tokio::test]
+async fn trade_stream_yields_pushed_trades() {
+    let mock = MockExchange::start().await.unwrap();
+    let exchange = Binance::with_urls(vec![mock.rest_url.clone()], vec![mock.ws_url.clone()]);
+    // The stream is lazy: poll it on a task so it connects while the
+    // test pushes frames.
+    let consumer = tokio::spawn(async move {
+        let mut stream = Box::pin(trade_stream(exchange, vec!["BTCUSDT".into()]));
+        stream.next().await
+    });
+    for _ in 0..100 {
+        if !mock.received().is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(mock.received().iter().any(|m| m.contains("btcusdt@trade")));
+    // An ack (filtered out) followed by a real trade.
+    mock.push_ws(r#"{"result":null,"id":1}"#).await;
+    mock.push_ws(
+        r#"{"stream":"btcusdt@trade","data":{"e":"trade","s":"BTCUSDT","t":5,"p":"42.5","q":"1.0","T":1700000000000,"m":false}}"#,
+    )
+    .await;
+    let trade = tokio::time::timeout(std::time::Duration::from_secs(3), consumer)
+        .await
+        .expect("trade should arrive")
+        .expect("consumer task lives")
+        .expect("stream stays open");
+    assert_eq!(trade.trade_id, 5);
+    assert_eq!(trade.price, 42.5);
+}
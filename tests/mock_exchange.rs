@@ -0,0 +1,86 @@
+//! End-to-end tests against the in-crate mock exchange.
+
+use bzl_database::exchange::binance::Binance;
+use bzl_database::exchange::Exchange;
+use bzl_database::pipeline::spec::StreamSource;
+use bzl_database::pipeline::Event;
+use bzl_database::testing::MockExchange;
+
+#[tokio::test]
+async fn rest_klines_round_trip_through_the_mock() {
+    let mock = MockExchange::start().await.unwrap();
+    mock.set_response(
+        "/api/v3/klines",
+        serde_json::json!([[
+            1_699_920_000_000i64,
+            "37500.0",
+            "37600.0",
+            "37400.0",
+            "37550.0",
+            "100.0",
+            1_699_920_059_999i64,
+            "3750000.0",
+            42,
+            "50.0",
+            "1875000.0",
+            "0"
+        ]]),
+    );
+    let exchange = Binance::with_urls(vec![mock.rest_url.clone()], vec![mock.ws_url.clone()]);
+    let klines = exchange
+        .fetch_klines(&reqwest::Client::new(), "BTCUSDT", "1m", None, None, 1000)
+        .await
+        .unwrap();
+    assert_eq!(klines.len(), 1);
+    assert_eq!(klines[0].open, 37_500.0);
+    assert_eq!(klines[0].trade_count, 42);
+}
+
+#[tokio::test]
+async fn websocket_subscribe_and_frame_delivery() {
+    let mock = MockExchange::start().await.unwrap();
+    let exchange = Binance::with_urls(vec![mock.rest_url.clone()], vec![mock.ws_url.clone()]);
+
+    let (ws, _) = tokio_tungstenite::connect_async(&exchange.ws_url())
+        .await
+        .unwrap();
+    use futures_util::{SinkExt, StreamExt};
+    let (mut write, mut read) = ws.split();
+    for payload in exchange.ws_subscribe(&[StreamSource {
+        symbol: "btcusdt".into(),
+        stream: "trade".into(),
+    }]) {
+        write
+            .send(tokio_tungstenite::tungstenite::Message::Text(payload))
+            .await
+            .unwrap();
+    }
+    // The mock records the subscription...
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    assert!(mock.received().iter().any(|m| m.contains("btcusdt@trade")));
+    // ...and pushed frames arrive and parse.
+    mock.push_ws(
+        r#"{"stream":"btcusdt@trade","data":{"e":"trade","s":"BTCUSDT","t":9,"p":"37500.5","q":"0.5","T":1699920000000,"m":false}}"#,
+    )
+    .await;
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(2), read.next())
+        .await
+        .expect("frame should arrive")
+        .unwrap()
+        .unwrap();
+    let events = exchange.parse_ws_message(frame.to_text().unwrap()).unwrap();
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], Event::Trade(t) if t.trade_id == 9));
+}
+
+#[tokio::test]
+async fn unknown_paths_return_404() {
+    let mock = MockExchange::start().await.unwrap();
+    let status = reqwest::Client::new()
+        .get(format!("{}/api/v3/unknown", mock.rest_url))
+        .send()
+        .await
+        .unwrap()
+        .status();
+    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+}